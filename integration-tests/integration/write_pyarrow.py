@@ -117,13 +117,21 @@ def case_struct(size):
 
 
 def write_pyarrow(
-    case, size=1, page_version=1, use_dictionary=False, use_compression=False
+    case,
+    size=1,
+    page_version=1,
+    use_dictionary=False,
+    use_compression=False,
+    use_byte_stream_split=False,
+    compression="snappy",
 ):
     data, schema, path = case(size)
 
-    compression_path = "/snappy" if use_compression else ""
+    compression_path = f"/{compression}" if use_compression else ""
 
-    if use_dictionary:
+    if use_byte_stream_split:
+        base_path = f"{PYARROW_PATH}/v{page_version}/byte_stream_split{compression_path}"
+    elif use_dictionary:
         base_path = f"{PYARROW_PATH}/v{page_version}/dict{compression_path}"
     else:
         base_path = f"{PYARROW_PATH}/v{page_version}/non_dict{compression_path}"
@@ -136,8 +144,16 @@ def write_pyarrow(
         version=f"{page_version}.0",
         data_page_version=f"{page_version}.0",
         write_statistics=True,
-        compression="snappy" if use_compression else None,
+        compression=compression if use_compression else None,
         use_dictionary=use_dictionary,
+        # pyarrow only supports byte stream split for floating point columns
+        use_byte_stream_split=[
+            field.name
+            for field in schema
+            if pa.types.is_floating(field.type)
+        ]
+        if use_byte_stream_split
+        else False,
     )
 
 
@@ -146,3 +162,11 @@ for case in [case_basic_nullable, case_basic_required, case_nested, case_struct]
         for use_dict in [False, True]:
             for compression in [False, True]:
                 write_pyarrow(case, 1, version, use_dict, compression)
+
+for case in [case_basic_nullable, case_basic_required]:
+    for version in [1, 2]:
+        write_pyarrow(case, 1, version, False, False, True)
+
+for case in [case_basic_nullable, case_basic_required]:
+    for version in [1, 2]:
+        write_pyarrow(case, 1, version, False, True, compression="lz4")
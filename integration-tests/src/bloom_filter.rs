@@ -0,0 +1,110 @@
+use std::io::Cursor;
+
+use parquet::{
+    bloom_filter::{self, BloomFilter},
+    error::{ParquetError, Result},
+    metadata::SchemaDescriptor,
+    read::read_metadata,
+    write::{FileWriter, WriteOptions},
+};
+
+use crate::write::{primitive::array_to_page_v1, row_group};
+use crate::Array;
+
+#[test]
+fn bloom_filter() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3), Some(4), Some(5), Some(6)];
+
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let page = array_to_page_v1(&array, &options, &schema.columns()[0])?;
+
+    let mut filter = BloomFilter::with_fpp(array.len(), 0.01)?;
+    array
+        .iter()
+        .flatten()
+        .for_each(|x| filter.insert_native(*x));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.start()?;
+    writer.write(row_group(vec![vec![page]], options.compression), 7)?;
+    writer.add_bloom_filters(vec![Some(filter.clone())])?;
+    let data = writer.end(None)?.1.into_inner();
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let result = bloom_filter::read(column, &mut reader)?;
+    assert_eq!(result, Some(filter));
+    let result = result.unwrap();
+    assert!(array.iter().flatten().all(|x| result.contains_native(*x)));
+
+    // the data is still readable
+    let (result, _) = crate::read::tests::read_column(&mut reader, 0, 0)?;
+    assert_eq!(result, Array::Int32(array));
+
+    Ok(())
+}
+
+/// Writes a column chunk of two values with a bloom filter of 1024 bytes, and returns the file.
+fn write_larger_bloom_filter() -> Result<Vec<u8>> {
+    let array = vec![Some(0), Some(1)];
+
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let page = array_to_page_v1(&array, &options, &schema.columns()[0])?;
+
+    let mut filter = BloomFilter::new(1024);
+    array
+        .iter()
+        .flatten()
+        .for_each(|x| filter.insert_native(*x));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+
+    writer.start()?;
+    writer.write(row_group(vec![vec![page]], options.compression), 2)?;
+    writer.add_bloom_filters(vec![Some(filter)])?;
+    Ok(writer.end(None)?.1.into_inner())
+}
+
+#[test]
+fn bloom_filter_larger_than_column_chunk() -> Result<()> {
+    let data = write_larger_bloom_filter()?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert!(column.uncompressed_size() < 1024);
+
+    let result = bloom_filter::read(column, &mut reader)?.unwrap();
+    assert!(result.contains_native(0i32));
+    assert!(result.contains_native(1i32));
+    Ok(())
+}
+
+#[test]
+fn bloom_filter_larger_than_file() -> Result<()> {
+    let mut data = write_larger_bloom_filter()?;
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    // the header is kept, but the bitset is truncated
+    data.truncate(column.bloom_filter_offset().unwrap() as usize + 64);
+    let mut reader = Cursor::new(data);
+
+    // the declared size is not trusted
+    assert!(matches!(
+        bloom_filter::read(column, &mut reader),
+        Err(ParquetError::OutOfSpec(_))
+    ));
+    Ok(())
+}
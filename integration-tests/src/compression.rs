@@ -0,0 +1,216 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use parquet::{
+    compression::{
+        BrotliLevel, Codec, CodecRegistry, Compression, CompressionOptions, GzipLevel, ZstdLevel,
+    },
+    error::{ParquetError, Result},
+    metadata::SchemaDescriptor,
+    read::{
+        decompress_with_codecs,
+        deserialize::{deserialize_column, Values},
+        get_page_iterator, get_page_stream_with_codecs, read_metadata, BasicDecompressor,
+        Decompressor,
+    },
+    write::{
+        dictionary::PrimitiveDictEncoder, Compressor, DynIter, DynStreamingIterator, FileWriter,
+        Version, WriteOptions,
+    },
+    FallibleStreamingIterator,
+};
+
+use crate::write::{primitive::array_to_page_v1, write_to_buffer};
+use crate::Array;
+
+#[test]
+fn compression_levels() -> Result<()> {
+    let array = (0..1000).map(|x| Some(x % 7)).collect::<Vec<_>>();
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let cases = [
+        (
+            CompressionOptions::Zstd(Some(ZstdLevel::try_new(19)?)),
+            Compression::Zstd,
+        ),
+        (
+            CompressionOptions::Gzip(Some(GzipLevel::try_new(9)?)),
+            Compression::Gzip,
+        ),
+        (
+            CompressionOptions::Brotli(Some(BrotliLevel::try_new(11)?)),
+            Compression::Brotli,
+        ),
+        (CompressionOptions::Lz4, Compression::Lz4),
+        (CompressionOptions::Lz4Raw, Compression::Lz4Raw),
+    ];
+    for (compression, codec) in cases {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression,
+            ..Default::default()
+        };
+
+        let page = array_to_page_v1(&array, &options, &schema.columns()[0])?;
+        let data = write_to_buffer(schema.clone(), vec![vec![page]], array.len(), options)?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata(&mut reader)?;
+        // the level is not part of the metadata
+        assert_eq!(metadata.row_groups[0].columns()[0].compression(), codec);
+
+        let (result, _) = crate::read::tests::read_column(&mut reader, 0, 0)?;
+        assert_eq!(result, Array::Int32(array.clone()));
+    }
+    Ok(())
+}
+
+/// A codec that xors every byte, so that pages cannot be read without it
+#[derive(Debug)]
+struct XorCodec;
+
+impl Codec for XorCodec {
+    fn compress(
+        &self,
+        _: CompressionOptions,
+        input_buf: &[u8],
+        output_buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        output_buf.extend(input_buf.iter().map(|x| x ^ 0xAA));
+        Ok(())
+    }
+
+    fn decompress(&self, _: Compression, input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+        output_buf
+            .iter_mut()
+            .zip(input_buf.iter())
+            .for_each(|(x, y)| *x = y ^ 0xAA);
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_codec() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: false,
+        compression: CompressionOptions::Lzo,
+        ..Default::default()
+    };
+    let mut codecs = CodecRegistry::default();
+    codecs.register(Compression::Lzo, Arc::new(XorCodec));
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 col; }")?;
+    let descriptor = schema.columns()[0].clone();
+    let array = (0..100)
+        .map(|x| if x % 10 == 0 { None } else { Some(x % 7) })
+        .collect::<Vec<_>>();
+
+    // a dictionary page and a data page
+    let mut encoder =
+        PrimitiveDictEncoder::try_new(descriptor.clone(), options, Default::default())?;
+    encoder.encode_page(&array)?;
+    let pages = encoder.finish();
+    let pages = DynStreamingIterator::new(
+        Compressor::new(
+            DynIter::new(pages.into_iter().map(Ok)),
+            options.compression,
+            vec![],
+        )
+        .with_codecs(codecs.clone()),
+    );
+    let columns = std::iter::once(Ok(pages));
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema, options, None);
+    writer.start()?;
+    writer.write(DynIter::new(columns), array.len())?;
+    let data = writer.end(None)?.1.into_inner();
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+    assert_eq!(column.compression(), Compression::Lzo);
+
+    // the builtin codecs cannot read the dictionary page
+    let mut pages = get_page_iterator(column, &mut reader, None, vec![])?;
+    assert!(pages.next().unwrap().is_err());
+
+    let pages = get_page_iterator(column, &mut reader, None, vec![])?.with_codecs(codecs.clone());
+    let pages = BasicDecompressor::new_with_codecs(pages, vec![], codecs.clone(), None);
+    let result = match deserialize_column(pages, &descriptor)? {
+        Values::Int64(result) => result,
+        _ => unreachable!(),
+    };
+    assert_eq!(result, array);
+
+    // and so do asynchronous readers
+    let mut reader = futures::io::Cursor::new(reader.into_inner());
+    let pages = futures::executor::block_on(async {
+        let filter = Arc::new(|_: &_, _: &_| true);
+        let stream =
+            get_page_stream_with_codecs(column, &mut reader, vec![], filter, &codecs, None).await?;
+        stream.try_collect::<Vec<_>>().await
+    })?;
+    let pages = BasicDecompressor::new_with_codecs(pages.into_iter().map(Ok), vec![], codecs, None);
+    let result = match deserialize_column(pages, &descriptor)? {
+        Values::Int64(result) => result,
+        _ => unreachable!(),
+    };
+    assert_eq!(result, array);
+    Ok(())
+}
+
+#[test]
+fn max_page_size() -> Result<()> {
+    let options = WriteOptions {
+        write_statistics: false,
+        compression: CompressionOptions::Snappy,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+    let array = (0..1000).map(|x| Some(x % 7)).collect::<Vec<_>>();
+
+    let page = array_to_page_v1(&array, &options, &schema.columns()[0])?;
+    let data = write_to_buffer(schema, vec![vec![page]], array.len(), options)?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    // the page is larger than the maximum
+    let mut pages = get_page_iterator(column, &mut reader, None, vec![])?.with_max_page_size(100);
+    assert!(matches!(
+        pages.next(),
+        Some(Err(ParquetError::OutOfSpec(_)))
+    ));
+
+    let pages = get_page_iterator(column, &mut reader, None, vec![])?.with_max_page_size(1 << 20);
+    let mut pages = Decompressor::new(pages, vec![]);
+    let page = pages.next()?.unwrap();
+    assert_eq!(page.num_values(), array.len());
+
+    // pages are also bounded when decompressed on their own
+    let mut pages = get_page_iterator(column, &mut reader, None, vec![])?;
+    let page = pages.next().unwrap()?;
+    let result = decompress_with_codecs(page, &mut vec![], &CodecRegistry::default(), Some(100));
+    assert!(matches!(result, Err(ParquetError::OutOfSpec(_))));
+    let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+    let mut pages =
+        BasicDecompressor::new_with_codecs(pages, vec![], Default::default(), Some(100));
+    assert!(matches!(pages.next(), Err(ParquetError::OutOfSpec(_))));
+
+    // and when read asynchronously
+    let mut reader = futures::io::Cursor::new(reader.into_inner());
+    let result = futures::executor::block_on(async {
+        let filter = Arc::new(|_: &_, _: &_| true);
+        let codecs = CodecRegistry::default();
+        let stream =
+            get_page_stream_with_codecs(column, &mut reader, vec![], filter, &codecs, Some(100))
+                .await?;
+        stream.try_collect::<Vec<_>>().await
+    });
+    assert!(matches!(result, Err(ParquetError::OutOfSpec(_))));
+    Ok(())
+}
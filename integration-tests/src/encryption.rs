@@ -0,0 +1,302 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use parquet::{
+    bloom_filter::{self, BloomFilter},
+    encryption::{
+        ColumnEncryptionProperties, EncryptionAlgorithm, FileDecryptionProperties,
+        FileEncryptionProperties, KeyRetriever,
+    },
+    error::{ParquetError, Result},
+    indexes::NativeIndex,
+    metadata::SchemaDescriptor,
+    read::{
+        get_column_iterator, get_indexed_page_iterator, get_page_iterator,
+        predicate::{Predicate, Scalar},
+        prune_row_groups, read_columns_indexes, read_metadata, read_metadata_with_decryption,
+        read_pages_locations,
+    },
+    write::{FileWriter, WriteOptions},
+};
+
+use crate::write::{primitive::array_to_page_v1, row_group};
+use crate::Array;
+
+const FOOTER_KEY: &[u8] = b"0123456789012345";
+const COLUMN_KEY: &[u8] = b"1234567890123450";
+
+/// Encryption properties of a file with a plaintext footer whose column `a` is encrypted.
+fn plaintext_footer_properties() -> FileEncryptionProperties {
+    let mut properties =
+        FileEncryptionProperties::new(EncryptionAlgorithm::AesGcmCtrV1, FOOTER_KEY.to_vec());
+    properties.plaintext_footer = true;
+    properties.columns = Some(vec![ColumnEncryptionProperties {
+        path: vec!["a".to_string()],
+        key: Some(COLUMN_KEY.to_vec()),
+        key_metadata: None,
+    }]);
+    properties
+}
+
+fn plaintext_footer_decryption() -> FileDecryptionProperties {
+    let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    properties
+        .column_keys
+        .insert(vec!["a".to_string()], COLUMN_KEY.to_vec());
+    properties
+}
+
+fn write_encrypted(array: &[Option<i32>], properties: FileEncryptionProperties) -> Result<Vec<u8>> {
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { OPTIONAL INT32 a; OPTIONAL INT32 b; }",
+    )?;
+
+    let columns = schema
+        .columns()
+        .iter()
+        .map(|descriptor| Ok(vec![array_to_page_v1(array, &options, descriptor)?]))
+        .collect::<Result<Vec<_>>>()?;
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::with_encryption(writer, schema, options, None, properties)?;
+
+    writer.start()?;
+    writer.write(row_group(columns, options.compression), array.len())?;
+    Ok(writer.end(None)?.1.into_inner())
+}
+
+fn read_encrypted(
+    data: Vec<u8>,
+    properties: &FileDecryptionProperties,
+    field: usize,
+) -> Result<Array> {
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata_with_decryption(&mut reader, properties)?;
+
+    let columns = get_column_iterator(&mut reader, &metadata, 0, field, None, vec![]);
+    let field = &metadata.schema().fields()[field];
+    crate::read::columns_to_array(columns, field)
+}
+
+#[test]
+fn encrypted_footer() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+
+    let properties =
+        FileEncryptionProperties::new(EncryptionAlgorithm::AesGcmV1, FOOTER_KEY.to_vec());
+    let data = write_encrypted(&array, properties)?;
+    assert_eq!(&data[..4], b"PARE");
+
+    let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    for field in 0..2 {
+        let result = read_encrypted(data.clone(), &properties, field)?;
+        assert_eq!(result, Array::Int32(array.clone()));
+    }
+
+    // the metadata cannot be read without keys
+    assert!(read_metadata(&mut Cursor::new(data.clone())).is_err());
+    let properties = FileDecryptionProperties::new(COLUMN_KEY.to_vec());
+    assert!(read_encrypted(data, &properties, 0).is_err());
+    Ok(())
+}
+
+#[test]
+fn plaintext_footer() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+
+    let mut properties =
+        FileEncryptionProperties::new(EncryptionAlgorithm::AesGcmCtrV1, FOOTER_KEY.to_vec());
+    properties.plaintext_footer = true;
+    properties.aad_prefix = Some(b"file".to_vec());
+    properties.columns = Some(vec![ColumnEncryptionProperties {
+        path: vec!["a".to_string()],
+        key: Some(COLUMN_KEY.to_vec()),
+        key_metadata: None,
+    }]);
+    let data = write_encrypted(&array, properties)?;
+    assert_eq!(&data[..4], b"PAR1");
+
+    let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    properties
+        .column_keys
+        .insert(vec!["a".to_string()], COLUMN_KEY.to_vec());
+    properties.aad_prefix = Some(b"file".to_vec());
+    for field in 0..2 {
+        let result = read_encrypted(data.clone(), &properties, field)?;
+        assert_eq!(result, Array::Int32(array.clone()));
+    }
+
+    // readers without keys can read the metadata and the columns that are not encrypted
+    let mut reader = Cursor::new(data.clone());
+    let metadata = read_metadata(&mut reader)?;
+    assert!(metadata.row_groups[0].columns()[0].statistics().is_none());
+    let (result, statistics) = crate::read::tests::read_column(&mut reader, 0, 1)?;
+    assert_eq!(result, Array::Int32(array));
+    assert!(statistics.is_some());
+
+    // encrypted column chunks cannot be read via their offset index
+    let columns = metadata.row_groups[0].columns();
+    assert!(columns[0].is_encrypted());
+    assert!(get_indexed_page_iterator(&columns[0], reader.clone(), vec![], vec![]).is_err());
+    assert!(get_indexed_page_iterator(&columns[1], reader, vec![], vec![]).is_ok());
+
+    // the AAD prefix is required, as it is not stored in the file
+    properties.aad_prefix = None;
+    assert!(read_encrypted(data, &properties, 0).is_err());
+    Ok(())
+}
+
+#[test]
+fn partial_keys() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+
+    for plaintext_footer in [true, false] {
+        let mut properties = plaintext_footer_properties();
+        properties.plaintext_footer = plaintext_footer;
+        let data = write_encrypted(&array, properties)?;
+
+        // without the key of column `a`, the other columns can still be read
+        let properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+        let result = read_encrypted(data.clone(), &properties, 1)?;
+        assert_eq!(result, Array::Int32(array.clone()));
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata_with_decryption(&mut reader, &properties)?;
+        let columns = metadata.row_groups[0].columns();
+        assert!(columns[0].is_encrypted());
+        assert!(get_page_iterator(&columns[0], reader.clone(), None, vec![]).is_err());
+        assert!(read_columns_indexes(&mut reader, &columns[..1]).is_err());
+        assert!(read_pages_locations(&mut reader, &columns[..1]).is_err());
+        assert!(read_columns_indexes(&mut reader, &columns[1..])?[0].is_some());
+
+        // with an encrypted footer, the metadata of column `a` is only readable with its key
+        assert_eq!(columns[0].has_metadata(), plaintext_footer);
+        assert!(columns[0].statistics().is_none());
+        assert!(columns[0].distinct_sketch().is_none());
+        assert_eq!(
+            bloom_filter::read(&columns[0], &mut reader).is_ok(),
+            plaintext_footer
+        );
+        assert!(columns[1].has_metadata());
+        assert_eq!(columns[1].num_values(), array.len() as i64);
+        metadata.clone().into_thrift()?;
+
+        // column `a` has no statistics and thus never prunes row groups
+        let predicate = Predicate::Gt(vec!["a".to_string()], Scalar::Int32(100));
+        assert_eq!(prune_row_groups(&metadata, &predicate)?.row_groups.len(), 1);
+        let predicate = Predicate::Gt(vec!["b".to_string()], Scalar::Int32(100));
+        assert_eq!(prune_row_groups(&metadata, &predicate)?.row_groups.len(), 0);
+    }
+
+    // without the footer key, the columns encrypted with it are left encrypted
+    let mut properties = plaintext_footer_properties();
+    properties
+        .columns
+        .as_mut()
+        .unwrap()
+        .push(ColumnEncryptionProperties {
+            path: vec!["b".to_string()],
+            key: None,
+            key_metadata: None,
+        });
+    let data = write_encrypted(&array, properties)?;
+
+    let mut properties = FileDecryptionProperties::default();
+    properties
+        .column_keys
+        .insert(vec!["a".to_string()], COLUMN_KEY.to_vec());
+    let result = read_encrypted(data.clone(), &properties, 0)?;
+    assert_eq!(result, Array::Int32(array.clone()));
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata_with_decryption(&mut reader, &properties)?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(columns[1].is_encrypted());
+    assert!(get_page_iterator(&columns[1], reader.clone(), None, vec![]).is_err());
+    Ok(())
+}
+
+struct FailingRetriever;
+
+impl KeyRetriever for FailingRetriever {
+    fn retrieve_key(&self, _: &[u8]) -> Result<Vec<u8>> {
+        Err(ParquetError::General("unavailable".to_string()))
+    }
+}
+
+#[test]
+fn key_retriever_error() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+    let mut properties = plaintext_footer_properties();
+    properties.columns.as_mut().unwrap()[0].key_metadata = Some(b"a".to_vec());
+    let data = write_encrypted(&array, properties)?;
+
+    // errors of the retriever are not mistaken for an absent key
+    let mut properties = FileDecryptionProperties::new(FOOTER_KEY.to_vec());
+    properties.key_retriever = Some(Arc::new(FailingRetriever));
+    let mut reader = Cursor::new(data);
+    assert!(read_metadata_with_decryption(&mut reader, &properties).is_err());
+    Ok(())
+}
+
+#[test]
+fn encrypted_indexes() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+    let data = write_encrypted(&array, plaintext_footer_properties())?;
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata_with_decryption(&mut reader, &plaintext_footer_decryption())?;
+    let columns = metadata.row_groups[0].columns();
+
+    // the indexes of the encrypted column are decrypted
+    let indexes = read_columns_indexes(&mut reader, columns)?;
+    for index in indexes {
+        let index = index.unwrap();
+        let index = index.as_any().downcast_ref::<NativeIndex<i32>>().unwrap();
+        assert_eq!(index.indexes[0].min, Some(0));
+        assert_eq!(index.indexes[0].max, Some(3));
+    }
+    let locations = read_pages_locations(&mut reader, columns)?;
+    assert!(locations
+        .iter()
+        .all(|x| x.as_ref().map(|x| x.len()) == Some(1)));
+
+    // and cannot be read without its key
+    let metadata = read_metadata(&mut reader)?;
+    let columns = metadata.row_groups[0].columns();
+    assert!(read_columns_indexes(&mut reader, &columns[..1]).is_err());
+    assert!(read_pages_locations(&mut reader, &columns[..1]).is_err());
+    assert!(read_columns_indexes(&mut reader, &columns[1..])?[0].is_some());
+    Ok(())
+}
+
+#[test]
+fn encrypted_bloom_filter() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+    let options = WriteOptions::default();
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { OPTIONAL INT32 a; OPTIONAL INT32 b; }",
+    )?;
+    let columns = schema
+        .columns()
+        .iter()
+        .map(|descriptor| Ok(vec![array_to_page_v1(&array, &options, descriptor)?]))
+        .collect::<Result<Vec<_>>>()?;
+
+    let writer = Cursor::new(vec![]);
+    let mut writer =
+        FileWriter::with_encryption(writer, schema, options, None, plaintext_footer_properties())?;
+    writer.start()?;
+    writer.write(row_group(columns, options.compression), array.len())?;
+
+    // bloom filters of encrypted columns are not supported
+    let filter = BloomFilter::with_fpp(array.len(), 0.01)?;
+    assert!(writer
+        .add_bloom_filters(vec![Some(filter.clone()), None])
+        .is_err());
+    writer.add_bloom_filters(vec![None, Some(filter)])?;
+    writer.end(None)?;
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use std::io::Cursor;
+
+use parquet::{
+    compression::CompressionOptions,
+    error::{ParquetError, Result},
+    indexes::{select_pages, BoundaryOrder, Interval, NativeIndex, PageIndex, PageLocation},
+    metadata::SchemaDescriptor,
+    page::{EncodedDictPage, EncodedPage},
+    read::{
+        decompress, get_indexed_page_iterator, read_columns_indexes, read_metadata,
+        read_pages_locations,
+    },
+    write::WriteOptions,
+};
+
+use crate::write::{primitive::array_to_page_v1, write_to_buffer};
+
+#[test]
+fn indexes() -> Result<()> {
+    let array1 = vec![Some(0), Some(1), None, Some(3), Some(4), Some(5), Some(6)];
+    let array2 = vec![Some(10), Some(11)];
+
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let pages = vec![
+        array_to_page_v1(&array1, &options, &schema.columns()[0])?,
+        array_to_page_v1(&array2, &options, &schema.columns()[0])?,
+    ];
+    let data = write_to_buffer(schema, vec![pages], 9, options)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+
+    let columns = &metadata.row_groups[0].columns();
+
+    let expected_page_locations = vec![
+        PageLocation {
+            offset: 4,
+            compressed_page_size: 63,
+            first_row_index: 0,
+        },
+        PageLocation {
+            offset: 67,
+            compressed_page_size: 47,
+            first_row_index: 7,
+        },
+    ];
+    let expected_index = NativeIndex::<i32> {
+        indexes: vec![
+            PageIndex {
+                min: Some(0),
+                max: Some(6),
+                null_count: Some(1),
+            },
+            PageIndex {
+                min: Some(10),
+                max: Some(11),
+                null_count: Some(0),
+            },
+        ],
+        boundary_order: BoundaryOrder::Unordered,
+    };
+
+    let indexes = read_columns_indexes(&mut reader, columns)?;
+    let index = indexes[0].as_ref().unwrap();
+    assert_eq!(
+        index.as_any().downcast_ref::<NativeIndex<i32>>(),
+        Some(&expected_index)
+    );
+
+    let pages = read_pages_locations(&mut reader, columns)?;
+    assert_eq!(pages, vec![Some(expected_page_locations)]);
+
+    Ok(())
+}
+
+#[test]
+fn indexes_null_page() -> Result<()> {
+    let array1: Vec<Option<i32>> = vec![None, None];
+    let array2 = vec![Some(10), None];
+
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let pages = vec![
+        array_to_page_v1(&array1, &options, &schema.columns()[0])?,
+        array_to_page_v1(&array2, &options, &schema.columns()[0])?,
+    ];
+    let data = write_to_buffer(schema, vec![pages], 4, options)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let columns = &metadata.row_groups[0].columns();
+
+    // the page with only nulls has no min and max
+    let expected_index = NativeIndex::<i32> {
+        indexes: vec![
+            PageIndex {
+                min: None,
+                max: None,
+                null_count: Some(2),
+            },
+            PageIndex {
+                min: Some(10),
+                max: Some(10),
+                null_count: Some(1),
+            },
+        ],
+        boundary_order: BoundaryOrder::Unordered,
+    };
+
+    let indexes = read_columns_indexes(&mut reader, columns)?;
+    let index = indexes[0].as_ref().unwrap();
+    assert_eq!(
+        index.as_any().downcast_ref::<NativeIndex<i32>>(),
+        Some(&expected_index)
+    );
+    Ok(())
+}
+
+#[test]
+fn indexes_larger_than_file() -> Result<()> {
+    let array = vec![Some(0), Some(1), None, Some(3)];
+
+    let options = WriteOptions::default();
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    let pages = vec![array_to_page_v1(&array, &options, &schema.columns()[0])?];
+    let mut data = write_to_buffer(schema, vec![pages], 4, options)?;
+
+    let metadata = read_metadata(&mut Cursor::new(&data))?;
+    let columns = &metadata.row_groups[0].columns();
+
+    // the offsets and lengths of the indexes are not trusted
+    data.truncate(columns[0].column_index_offset().unwrap() as usize + 1);
+    let mut reader = Cursor::new(data);
+    assert!(matches!(
+        read_columns_indexes(&mut reader, columns),
+        Err(ParquetError::OutOfSpec(_))
+    ));
+    assert!(matches!(
+        read_pages_locations(&mut reader, columns),
+        Err(ParquetError::OutOfSpec(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn indexed_page_iterator() -> Result<()> {
+    let array1 = vec![Some(0), Some(1), None, Some(3), Some(4), Some(5), Some(6)];
+    let array2 = vec![Some(10), Some(11)];
+
+    let options = WriteOptions {
+        compression: CompressionOptions::Snappy,
+        ..Default::default()
+    };
+
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
+
+    // a dictionary page, so that we can check that it is always read
+    let dict = [10i32, 11]
+        .iter()
+        .flat_map(|x| x.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    let pages = vec![
+        EncodedPage::Dict(EncodedDictPage::new(dict, 2)),
+        array_to_page_v1(&array1, &options, &schema.columns()[0])?,
+        array_to_page_v1(&array2, &options, &schema.columns()[0])?,
+    ];
+    let data = write_to_buffer(schema, vec![pages], 9, options)?;
+    let mut reader = Cursor::new(data);
+
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let locations = read_pages_locations(&mut reader, std::slice::from_ref(column))?;
+    let locations = locations[0].as_ref().unwrap();
+    assert_eq!(locations.len(), 2);
+
+    // only rows of the second page are selected
+    let pages = select_pages(&[Interval::new(8, 1)], locations, 9)?;
+    assert_eq!(pages.len(), 1);
+
+    let mut pages =
+        get_indexed_page_iterator(column, reader.clone(), pages, vec![])?.collect::<Result<Vec<_>>>()?;
+    assert_eq!(pages.len(), 1);
+    let page = decompress(pages.pop().unwrap(), &mut vec![])?;
+    assert_eq!(page.num_values(), 2);
+    assert_eq!(page.selected_rows(), Some([Interval::new(1, 1)].as_ref()));
+    assert!(page.dictionary_page().is_some());
+
+    // the length of a page must match the one of the offset index
+    let mut pages = select_pages(&[Interval::new(8, 1)], locations, 9)?;
+    pages[0].length += 1;
+    let mut pages = get_indexed_page_iterator(column, reader, pages, vec![])?;
+    assert!(matches!(pages.next(), Some(Err(ParquetError::OutOfSpec(_)))));
+
+    Ok(())
+}
@@ -3,9 +3,20 @@
 pub mod read;
 pub mod write;
 
+#[cfg(test)]
+mod bloom_filter;
+#[cfg(test)]
+mod compression;
+#[cfg(test)]
+mod encryption;
+#[cfg(test)]
+mod indexes;
+#[cfg(test)]
+mod statistics;
+
 // The dynamic representation of values in native Rust. This is not exaustive.
 // todo: maybe refactor this into serde/json?
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Array {
     UInt32(Vec<Option<u32>>),
     Int32(Vec<Option<i32>>),
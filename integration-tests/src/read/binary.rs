@@ -70,16 +70,18 @@ pub fn page_dict_to_vec(
     let (_, def_levels, values) = split_buffer(page, descriptor);
 
     match (&page.encoding(), &page.dictionary_page()) {
-        (Encoding::PlainDictionary, Some(dict)) => Ok(read_dict_buffer(
-            def_levels,
-            values,
-            page.num_values() as u32,
-            dict.as_any().downcast_ref().unwrap(),
-            (
-                &page.definition_level_encoding(),
-                descriptor.max_def_level(),
-            ),
-        )),
+        (Encoding::PlainDictionary, Some(dict)) | (Encoding::RleDictionary, Some(dict)) => {
+            Ok(read_dict_buffer(
+                def_levels,
+                values,
+                page.num_values() as u32,
+                dict.as_any().downcast_ref().unwrap(),
+                (
+                    &page.definition_level_encoding(),
+                    descriptor.max_def_level(),
+                ),
+            ))
+        }
         (_, None) => todo!("Dictionary-encoded page requires a dictionary"),
         _ => todo!(),
     }
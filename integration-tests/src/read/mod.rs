@@ -3,6 +3,8 @@
 /// but OTOH it has no external dependencies and is very familiar to Rust developers.
 mod binary;
 mod boolean;
+#[cfg(test)]
+mod predicate;
 mod primitive;
 mod primitive_nested;
 mod struct_;
@@ -68,20 +70,7 @@ pub fn page_to_array(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<A
                 _ => todo!(),
             },
         },
-        (ParquetType::PrimitiveType { physical_type, .. }, _) => match page.dictionary_page() {
-            None => match physical_type {
-                PhysicalType::Int64 => {
-                    Ok(primitive_nested::page_to_array::<i64>(page, descriptor)?)
-                }
-                _ => todo!(),
-            },
-            Some(_) => match physical_type {
-                PhysicalType::Int64 => Ok(primitive_nested::page_dict_to_array::<i64>(
-                    page, descriptor,
-                )?),
-                _ => todo!(),
-            },
-        },
+        (ParquetType::PrimitiveType { .. }, _) => primitive_nested::page_to_array(page, descriptor),
         _ => todo!("Nested types are not supported by this in-memory format"),
     }
 }
@@ -260,6 +249,63 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    fn test_lz4(file: &str) -> Result<()> {
+        let mut path = get_path();
+        path.push(file);
+        let path = path.to_str().unwrap();
+
+        let (array, _) = get_column(path, 0)?;
+        let expected = vec![1593604800i64, 1593604800, 1593604801, 1593604801];
+        assert_eq!(
+            array,
+            Array::Int64(expected.into_iter().map(Some).collect())
+        );
+
+        let (array, _) = get_column(path, 1)?;
+        let expected = vec!["abc", "def", "abc", "def"];
+        let expected = expected.into_iter().map(|x| Some(x.as_bytes().to_vec()));
+        assert_eq!(array, Array::Binary(expected.collect()));
+
+        let (array, _) = get_column(path, 2)?;
+        let expected = vec![42.0f64, 7.7, 42.125, 7.7];
+        assert_eq!(
+            array,
+            Array::Float64(expected.into_iter().map(Some).collect())
+        );
+        Ok(())
+    }
+
+    // LZ4 with the Hadoop framing, as written by parquet-mr
+    #[test]
+    fn lz4_hadoop() -> Result<()> {
+        test_lz4("hadoop_lz4_compressed.parquet")
+    }
+
+    // LZ4 as raw blocks, as written by older versions of parquet-cpp
+    #[test]
+    fn lz4_non_hadoop() -> Result<()> {
+        test_lz4("non_hadoop_lz4_compressed.parquet")
+    }
+
+    #[test]
+    fn lz4_raw() -> Result<()> {
+        test_lz4("lz4_raw_compressed.parquet")
+    }
+
+    #[test]
+    fn lz4_larger() -> Result<()> {
+        for file in [
+            "hadoop_lz4_compressed_larger.parquet",
+            "lz4_raw_compressed_larger.parquet",
+        ] {
+            let mut path = get_path();
+            path.push(file);
+            let (array, _) = get_column(path.to_str().unwrap(), 0)?;
+            assert_eq!(array.len(), 10000);
+        }
+        Ok(())
+    }
+
     fn assert_eq_stats(expected: (Option<i64>, Value, Value), stats: &dyn Statistics) {
         match (expected.1, expected.2) {
             (Value::Int32(min), Value::Int32(max)) => {
@@ -326,7 +372,39 @@ pub(crate) mod tests {
             version, use_dictionary_s, compression, file, required_s
         );
 
-        let (array, statistics) = get_column(&path, column)?;
+        check_pyarrow(&path, file, column, required)
+    }
+
+    fn test_pyarrow_byte_stream_split(column: usize, version: usize, required: bool) -> Result<()> {
+        if std::env::var("PARQUET2_IGNORE_PYARROW_TESTS").is_ok() {
+            return Ok(());
+        }
+        let required_s = if required { "required" } else { "nullable" };
+
+        let path = format!(
+            "fixtures/pyarrow3/v{}/byte_stream_split/basic_{}_10.parquet",
+            version, required_s
+        );
+
+        check_pyarrow(&path, "basic", column, required)
+    }
+
+    fn test_pyarrow_lz4(column: usize, version: usize, required: bool) -> Result<()> {
+        if std::env::var("PARQUET2_IGNORE_PYARROW_TESTS").is_ok() {
+            return Ok(());
+        }
+        let required_s = if required { "required" } else { "nullable" };
+
+        let path = format!(
+            "fixtures/pyarrow3/v{}/non_dict/lz4/basic_{}_10.parquet",
+            version, required_s
+        );
+
+        check_pyarrow(&path, "basic", column, required)
+    }
+
+    fn check_pyarrow(path: &str, file: &str, column: usize, required: bool) -> Result<()> {
+        let (array, statistics) = get_column(path, column)?;
 
         let expected = match (file, required) {
             ("basic", true) => pyarrow_required(column),
@@ -401,6 +479,16 @@ pub(crate) mod tests {
         test_pyarrow_integration("basic", 0, 2, false, false, true)
     }
 
+    #[test]
+    fn pyarrow_v1_non_dict_int64_optional_lz4() -> Result<()> {
+        test_pyarrow_lz4(0, 1, false)
+    }
+
+    #[test]
+    fn pyarrow_v2_non_dict_int64_required_lz4() -> Result<()> {
+        test_pyarrow_lz4(0, 2, true)
+    }
+
     #[test]
     fn pyarrow_v1_dict_string_required() -> Result<()> {
         test_pyarrow_integration("basic", 2, 1, true, true, false)
@@ -450,4 +538,24 @@ pub(crate) mod tests {
     fn pyarrow_v2_struct_required() -> Result<()> {
         test_pyarrow_integration("struct", 1, 2, false, false, false)
     }
+
+    #[test]
+    fn pyarrow_v1_byte_stream_split_float64_required() -> Result<()> {
+        test_pyarrow_byte_stream_split(1, 1, true)
+    }
+
+    #[test]
+    fn pyarrow_v1_byte_stream_split_float64_optional() -> Result<()> {
+        test_pyarrow_byte_stream_split(1, 1, false)
+    }
+
+    #[test]
+    fn pyarrow_v2_byte_stream_split_float64_required() -> Result<()> {
+        test_pyarrow_byte_stream_split(1, 2, true)
+    }
+
+    #[test]
+    fn pyarrow_v2_byte_stream_split_float64_optional() -> Result<()> {
+        test_pyarrow_byte_stream_split(1, 2, false)
+    }
 }
@@ -0,0 +1,212 @@
+use std::io::Cursor;
+
+use parquet::{
+    error::Result,
+    metadata::{ColumnChunkMetaData, SchemaDescriptor},
+    read::{
+        get_indexed_page_iterator, get_page_iterator,
+        predicate::{Predicate, Scalar},
+        prune_row_groups, read_columns_indexes, read_metadata, read_pages_locations,
+    },
+    write::{FileWriter, WriteOptions},
+};
+
+use crate::write::{primitive::array_to_page_v1, row_group, write_to_buffer};
+
+#[test]
+fn prune() -> Result<()> {
+    let options = WriteOptions::default();
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 a; }")?;
+
+    let row_groups = vec![
+        vec![Some(1i32), Some(2), Some(3), None],
+        vec![Some(10), Some(11), Some(12)],
+        vec![None, None],
+    ];
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema.clone(), options, None);
+    writer.start()?;
+    for array in &row_groups {
+        let page = array_to_page_v1(array, &options, &schema.columns()[0])?;
+        writer.write(
+            row_group(vec![vec![page]], options.compression),
+            array.len(),
+        )?;
+    }
+    let data = writer.end(None)?.1.into_inner();
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+
+    // the number of rows of the row groups that are kept
+    let kept = |predicate: &Predicate| -> Result<Vec<i64>> {
+        Ok(prune_row_groups(&metadata, predicate)?
+            .row_groups
+            .iter()
+            .map(|row_group| row_group.num_rows())
+            .collect())
+    };
+    let a = vec!["a".to_string()];
+
+    assert_eq!(kept(&Predicate::Eq(a.clone(), Scalar::Int32(11)))?, vec![3]);
+    assert_eq!(
+        kept(&Predicate::Between(
+            a.clone(),
+            Scalar::Int32(2),
+            Scalar::Int32(10)
+        ))?,
+        vec![4, 3]
+    );
+    assert_eq!(kept(&Predicate::Gt(a.clone(), Scalar::Int32(100)))?, vec![]);
+    assert_eq!(kept(&Predicate::IsNull(a.clone()))?, vec![4, 2]);
+    assert_eq!(
+        kept(&Predicate::Not(Box::new(Predicate::IsNull(a.clone()))))?,
+        vec![4, 3]
+    );
+    assert!(kept(&Predicate::IsNull(vec!["b".to_string()])).is_err());
+    Ok(())
+}
+
+/// Returns the number of values of the pages of `column` that are read, from the statistics of
+/// the page headers and from the page indexes
+fn read_pages(
+    reader: &Cursor<Vec<u8>>,
+    column: &ColumnChunkMetaData,
+    num_rows: usize,
+    predicate: &Predicate,
+) -> Result<(Vec<usize>, Vec<usize>)> {
+    let mut reader = reader.clone();
+
+    let filter = predicate.page_filter(column.descriptor())?;
+    let filtered = get_page_iterator(column, &mut reader, Some(filter), vec![])?
+        .map(|page| page.map(|page| page.num_values()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let chunks = std::slice::from_ref(column);
+    let index = read_columns_indexes(&mut reader, chunks)?.pop().unwrap();
+    let locations = read_pages_locations(&mut reader, chunks)?.pop().unwrap();
+    let pages = predicate.select_pages(
+        column.descriptor(),
+        index.unwrap().as_ref(),
+        &locations.unwrap(),
+        num_rows,
+    )?;
+    let selected = get_indexed_page_iterator(column, &mut reader, pages, vec![])?
+        .map(|page| page.map(|page| page.num_values()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((filtered, selected))
+}
+
+#[test]
+fn page_filter() -> Result<()> {
+    let options = WriteOptions::default();
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 a; }")?;
+
+    let arrays = [
+        vec![Some(0i32), Some(1), None, Some(3)],
+        vec![Some(10), Some(11), Some(12)],
+        vec![None, None],
+    ];
+    let pages = arrays
+        .iter()
+        .map(|array| array_to_page_v1(array, &options, &schema.columns()[0]))
+        .collect::<Result<Vec<_>>>()?;
+    let data = write_to_buffer(schema, vec![pages], 9, options)?;
+
+    let reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader.clone())?;
+    let column = &metadata.row_groups[0].columns()[0];
+    let read = |predicate: &Predicate| read_pages(&reader, column, 9, predicate);
+    let a = vec!["a".to_string()];
+
+    let kept = read(&Predicate::Eq(a.clone(), Scalar::Int32(11)))?;
+    assert_eq!(kept, (vec![3], vec![3]));
+    let kept = read(&Predicate::Lt(a.clone(), Scalar::Int32(10)))?;
+    assert_eq!(kept, (vec![4], vec![4]));
+    let kept = read(&Predicate::IsNull(a.clone()))?;
+    assert_eq!(kept, (vec![4, 2], vec![4, 2]));
+    // predicates over other columns are unknown
+    let other = Predicate::Eq(vec!["b".to_string()], Scalar::Int32(11));
+    let kept = read(&Predicate::Or(
+        Box::new(other.clone()),
+        Box::new(Predicate::IsNull(a.clone())),
+    ))?;
+    assert_eq!(kept, (vec![4, 3, 2], vec![4, 3, 2]));
+    let kept = read(&Predicate::And(
+        Box::new(other),
+        Box::new(Predicate::IsNull(a.clone())),
+    ))?;
+    assert_eq!(kept, (vec![4, 2], vec![4, 2]));
+
+    assert!(Predicate::Eq(a, Scalar::Int64(1))
+        .page_filter(column.descriptor())
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn page_filter_sibling_columns() -> Result<()> {
+    let options = WriteOptions::default();
+    let schema = SchemaDescriptor::try_from_message(
+        "message schema { OPTIONAL INT32 a; OPTIONAL INT32 b; }",
+    )?;
+
+    // per row group, per column, the values of each page
+    let row_groups = vec![
+        vec![
+            vec![(0..2).collect::<Vec<i32>>(), (10..13).collect()],
+            vec![(10..14).collect(), (0..1).collect()],
+        ],
+        vec![
+            vec![(20..24).collect(), (1..6).collect()],
+            vec![(1..2).collect(), (20..28).collect()],
+        ],
+    ];
+
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema.clone(), options, None);
+    writer.start()?;
+    for columns in &row_groups {
+        let num_rows = columns[0].iter().map(|page| page.len()).sum();
+        let columns = columns
+            .iter()
+            .zip(schema.columns())
+            .map(|(pages, descriptor)| {
+                pages
+                    .iter()
+                    .map(|page| {
+                        let array = page.iter().copied().map(Some).collect::<Vec<_>>();
+                        array_to_page_v1(&array, &options, descriptor)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        writer.write(row_group(columns, options.compression), num_rows)?;
+    }
+    let reader = Cursor::new(writer.end(None)?.1.into_inner());
+    let metadata = read_metadata(&mut reader.clone())?;
+
+    // the same predicate over `a` is used for all column chunks of all row groups
+    let predicate = Predicate::Eq(vec!["a".to_string()], Scalar::Int32(1));
+    let kept = metadata
+        .row_groups
+        .iter()
+        .map(|row_group| {
+            row_group
+                .columns()
+                .iter()
+                .map(|column| {
+                    read_pages(&reader, column, row_group.num_rows() as usize, &predicate)
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // pages of `b` are never skipped
+    assert_eq!(
+        kept,
+        vec![
+            vec![(vec![2], vec![2]), (vec![4, 1], vec![4, 1])],
+            vec![(vec![5], vec![5]), (vec![1, 8], vec![1, 8])],
+        ]
+    );
+    Ok(())
+}
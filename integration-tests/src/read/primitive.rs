@@ -3,7 +3,7 @@ use std::convert::TryInto;
 use super::utils::ValuesDef;
 
 use parquet::{
-    encoding::{hybrid_rle::HybridRleDecoder, Encoding},
+    encoding::{byte_stream_split, hybrid_rle::HybridRleDecoder, Encoding},
     error::Result,
     metadata::ColumnDescriptor,
     page::{split_buffer, DataPage, PrimitivePageDict},
@@ -143,6 +143,19 @@ pub fn page_to_vec<T: NativeType>(
                 descriptor.max_def_level(),
             ),
         )),
+        (Encoding::ByteStreamSplit, None) => {
+            let mut plain = vec![];
+            byte_stream_split::decode_fixed_len(values, std::mem::size_of::<T>(), &mut plain)?;
+            Ok(read_buffer::<T>(
+                def_levels,
+                &plain,
+                page.num_values() as u32,
+                (
+                    &page.definition_level_encoding(),
+                    descriptor.max_def_level(),
+                ),
+            ))
+        }
         _ => todo!(),
     }
 }
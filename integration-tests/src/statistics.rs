@@ -0,0 +1,188 @@
+use std::io::Cursor;
+
+use parquet::{
+    error::{ParquetError, Result},
+    metadata::SchemaDescriptor,
+    page::DataPageHeader,
+    read::{get_page_iterator, read_metadata},
+    statistics::BinaryStatistics,
+    write::{
+        dictionary::{BinaryDictEncoder, PrimitiveDictEncoder},
+        FileWriter, RowGroupIter, Version, WriteOptions,
+    },
+};
+
+use crate::write::{primitive::array_to_page_v1, row_group, write_to_buffer};
+
+/// Writes the values `[2, None, 3, 1]` of an INT64 column, dictionary-encoded or not, and checks
+/// the deprecated min and max of the statistics of its page and column chunk.
+fn check_legacy_min_max(write_legacy_min_max: bool, dictionary: bool) -> Result<()> {
+    let options = WriteOptions {
+        write_legacy_min_max,
+        ..Default::default()
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 a; }")?;
+    let values = [Some(2i64), None, Some(3), Some(1)];
+
+    let pages = if dictionary {
+        let mut encoder = PrimitiveDictEncoder::try_new(
+            schema.columns()[0].clone(),
+            options,
+            Default::default(),
+        )?;
+        encoder.encode_page(&values)?;
+        encoder.finish()
+    } else {
+        vec![array_to_page_v1(&values, &options, &schema.columns()[0])?]
+    };
+    let data = write_to_buffer(schema, vec![pages], 4, options)?;
+
+    let (min, max) = if write_legacy_min_max {
+        (
+            Some(1i64.to_le_bytes().to_vec()),
+            Some(3i64.to_le_bytes().to_vec()),
+        )
+    } else {
+        (None, None)
+    };
+
+    let mut reader = Cursor::new(data);
+    let metadata = read_metadata(&mut reader)?;
+    let column = &metadata.row_groups[0].columns()[0];
+
+    let statistics = column
+        .clone()
+        .into_thrift()
+        .meta_data
+        .unwrap()
+        .statistics
+        .unwrap();
+    assert_eq!(statistics.min, min);
+    assert_eq!(statistics.max, max);
+    assert_eq!(statistics.min_value, Some(1i64.to_le_bytes().to_vec()));
+    assert_eq!(statistics.max_value, Some(3i64.to_le_bytes().to_vec()));
+
+    let mut pages = get_page_iterator(column, &mut reader, None, vec![])?;
+    let page = pages.next().unwrap()?;
+    let statistics = match page.header() {
+        DataPageHeader::V1(header) => header.statistics.clone().unwrap(),
+        DataPageHeader::V2(header) => header.statistics.clone().unwrap(),
+    };
+    assert_eq!(statistics.min, min);
+    assert_eq!(statistics.max, max);
+    Ok(())
+}
+
+#[test]
+fn legacy_min_max() -> Result<()> {
+    check_legacy_min_max(true, true)?;
+    check_legacy_min_max(true, false)
+}
+
+#[test]
+fn no_legacy_min_max() -> Result<()> {
+    check_legacy_min_max(false, true)?;
+    check_legacy_min_max(false, false)
+}
+
+/// Writes two row groups of a dictionary-encoded column whose pages estimate their distinct
+/// values, and returns the file
+fn write_distinct(write_distinct_count_sketch: bool) -> Result<Vec<u8>> {
+    let options = WriteOptions {
+        distinct_count_precision: Some(10),
+        write_distinct_count_sketch,
+        version: Version::V2,
+        ..Default::default()
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL BYTE_ARRAY a; }")?;
+
+    let row_group = |pages: &[&[Option<&str>]]| -> Result<RowGroupIter<'static, ParquetError>> {
+        let mut encoder =
+            BinaryDictEncoder::try_new(schema.columns()[0].clone(), options, Default::default())?;
+        for page in pages {
+            encoder.encode_page(page)?;
+        }
+        Ok(row_group(vec![encoder.finish()], options.compression))
+    };
+
+    let writer = Cursor::new(vec![]);
+    let mut writer = FileWriter::new(writer, schema.clone(), options, None);
+    writer.start()?;
+    writer.write(
+        row_group(&[
+            &[Some("a"), Some("b"), Some("a"), None],
+            &[Some("b"), Some("c")],
+        ])?,
+        6,
+    )?;
+    writer.write(row_group(&[&[Some("c"), Some("d")]])?, 2)?;
+    Ok(writer.end(None)?.1.into_inner())
+}
+
+#[test]
+fn distinct_count() -> Result<()> {
+    let mut reader = Cursor::new(write_distinct(false)?);
+    let metadata = read_metadata(&mut reader)?;
+
+    let column = &metadata.row_groups[0].columns()[0];
+    let statistics = column.statistics().unwrap()?;
+    let statistics = statistics
+        .as_any()
+        .downcast_ref::<BinaryStatistics>()
+        .unwrap();
+    assert_eq!(statistics.distinct_count, Some(3));
+
+    let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+    let page_counts = pages
+        .filter_map(|page| {
+            let page = page.unwrap();
+            let statistics = page.statistics()?.unwrap();
+            let statistics = statistics.as_any().downcast_ref::<BinaryStatistics>();
+            Some(statistics.unwrap().distinct_count)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(page_counts, vec![Some(2), Some(2)]);
+
+    // sketches are only written on request
+    assert!(column.distinct_sketch().is_none());
+    Ok(())
+}
+
+#[test]
+fn distinct_count_sketch() -> Result<()> {
+    let metadata = read_metadata(&mut Cursor::new(write_distinct(true)?))?;
+
+    // the sketches of the column chunks estimate the distinct values across row groups
+    let column = &metadata.row_groups[0].columns()[0];
+    let mut sketch = column.distinct_sketch().unwrap()?;
+    assert_eq!(sketch.estimate(), 3);
+    let other = metadata.row_groups[1].columns()[0]
+        .distinct_sketch()
+        .unwrap()?;
+    assert_eq!(other.estimate(), 2);
+    sketch.merge(&other)?;
+    assert_eq!(sketch.estimate(), 4);
+    Ok(())
+}
+
+#[test]
+fn distinct_count_plain_pages() -> Result<()> {
+    let options = WriteOptions {
+        distinct_count_precision: Some(10),
+        write_distinct_count_sketch: true,
+        ..Default::default()
+    };
+    let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT64 a; }")?;
+    let page = array_to_page_v1(&[Some(1i64), Some(1), None], &options, &schema.columns()[0])?;
+    let data = write_to_buffer(schema, vec![vec![page]], 3, options)?;
+
+    // pages not written by the dictionary encoders carry no sketch
+    let metadata = read_metadata(&mut Cursor::new(data))?;
+    let column = &metadata.row_groups[0].columns()[0];
+    let statistics = column.statistics().unwrap()?;
+    assert_eq!(statistics.null_count(), Some(1));
+    let statistics = column.clone().into_thrift().meta_data.unwrap().statistics;
+    assert_eq!(statistics.unwrap().distinct_count, None);
+    assert!(column.distinct_sketch().is_none());
+    Ok(())
+}
@@ -1,6 +1,15 @@
+mod nested;
 pub(crate) mod primitive;
 
-use parquet::{error::Result, metadata::ColumnDescriptor, page::EncodedPage, write::WriteOptions};
+use std::io::Cursor;
+
+use parquet::{
+    compression::CompressionOptions,
+    error::{ParquetError, Result},
+    metadata::{ColumnDescriptor, SchemaDescriptor},
+    page::EncodedPage,
+    write::{Compressor, DynIter, DynStreamingIterator, FileWriter, RowGroupIter, WriteOptions},
+};
 
 use super::Array;
 
@@ -16,10 +25,39 @@ pub fn array_to_page(
         Array::Int96(array) => primitive::array_to_page_v1(array, options, descriptor),
         Array::Float32(array) => primitive::array_to_page_v1(array, options, descriptor),
         Array::Float64(array) => primitive::array_to_page_v1(array, options, descriptor),
+        Array::List(_) | Array::Struct(_, _) => nested::array_to_page(array, options, descriptor),
         _ => todo!(),
     }
 }
 
+/// Returns a row group whose columns are made of `columns`' pages, compressed with `compression`.
+pub fn row_group(
+    columns: Vec<Vec<EncodedPage>>,
+    compression: CompressionOptions,
+) -> RowGroupIter<'static, ParquetError> {
+    DynIter::new(columns.into_iter().map(move |pages| {
+        Ok(DynStreamingIterator::new(Compressor::new(
+            DynIter::new(pages.into_iter().map(Ok)),
+            compression,
+            vec![],
+        )))
+    }))
+}
+
+/// Writes a file with a single row group of `num_rows` rows, whose columns are made of
+/// `columns`' pages.
+pub fn write_to_buffer(
+    schema: SchemaDescriptor,
+    columns: Vec<Vec<EncodedPage>>,
+    num_rows: usize,
+    options: WriteOptions,
+) -> Result<Vec<u8>> {
+    let mut writer = FileWriter::new(Cursor::new(vec![]), schema, options, None);
+    writer.start()?;
+    writer.write(row_group(columns, options.compression), num_rows)?;
+    Ok(writer.end(None)?.1.into_inner())
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::{Cursor, Read, Seek};
@@ -27,10 +65,16 @@ mod tests {
 
     use crate::tests::{alltypes_plain, alltypes_statistics};
 
-    use parquet::compression::Compression;
+    use parquet::encoding::Encoding;
     use parquet::error::Result;
-    use parquet::metadata::SchemaDescriptor;
+    use parquet::metadata::{PageEncodingStats, SchemaDescriptor, SortingColumn};
+    use parquet::page::PageType;
+    use parquet::read::{
+        deserialize::{deserialize_column, Values},
+        get_page_iterator, read_metadata, BasicDecompressor,
+    };
     use parquet::statistics::Statistics;
+    use parquet::write::dictionary::{BinaryDictEncoder, DictionaryOptions, PrimitiveDictEncoder};
     use parquet::write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version};
 
     use super::*;
@@ -43,11 +87,7 @@ mod tests {
     fn test_column(column: usize) -> Result<()> {
         let array = alltypes_plain(column);
 
-        let options = WriteOptions {
-            write_statistics: true,
-            compression: Compression::Uncompressed,
-            version: Version::V1,
-        };
+        let options = WriteOptions::default();
 
         // prepare schema
         let a = match array {
@@ -132,22 +172,6 @@ mod tests {
     fn float64_col() -> Result<()> {
         test_column(7)
     }
-}
-
-#[cfg(test)]
-mod tests2 {
-    use std::io::Cursor;
-
-    use super::*;
-
-    use crate::write::primitive::array_to_page_v1;
-    use parquet::{
-        compression::Compression,
-        error::Result,
-        metadata::SchemaDescriptor,
-        read::read_metadata,
-        write::{Compressor, DynIter, DynStreamingIterator, FileWriter, Version},
-    };
 
     #[test]
     fn basic() -> Result<()> {
@@ -163,42 +187,313 @@ mod tests2 {
 
         let options = WriteOptions {
             write_statistics: false,
-            compression: Compression::Uncompressed,
-            version: Version::V1,
+            ..Default::default()
         };
 
         let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT32 col; }")?;
 
-        let pages = DynStreamingIterator::new(Compressor::new_from_vec(
-            DynIter::new(std::iter::once(array_to_page_v1(
-                &array,
-                &options,
-                &schema.columns()[0],
-            ))),
-            options.compression,
-            vec![],
-        ));
-        let columns = std::iter::once(Ok(pages));
+        let pages = vec![primitive::array_to_page_v1(
+            &array,
+            &options,
+            &schema.columns()[0],
+        )?];
+        let data = write_to_buffer(schema, vec![pages], 7, options)?;
+
+        let metadata = read_metadata(&mut Cursor::new(data))?;
+
+        // validated against an equivalent array produced by pyarrow.
+        let expected = 51;
+        assert_eq!(
+            metadata.row_groups[0].columns()[0].uncompressed_size(),
+            expected
+        );
+
+        Ok(())
+    }
+
+    fn test_dictionary(array: Array, version: Version) -> Result<()> {
+        let options = WriteOptions {
+            compression: CompressionOptions::Snappy,
+            version,
+            ..Default::default()
+        };
+
+        let (type_, num_rows) = match &array {
+            Array::Int64(array) => ("INT64", array.len()),
+            Array::Binary(array) => ("BYTE_ARRAY", array.len()),
+            _ => unreachable!("dictionaries are only tested for INT64 and BYTE_ARRAY"),
+        };
+        let schema = SchemaDescriptor::try_from_message(&format!(
+            "message schema {{ OPTIONAL {} col; }}",
+            type_
+        ))?;
+
+        let descriptor = schema.columns()[0].clone();
+        let pages = match &array {
+            Array::Int64(array) => {
+                let mut encoder =
+                    PrimitiveDictEncoder::try_new(descriptor, options, Default::default())?;
+                encoder.encode_page(array)?;
+                encoder.finish()
+            }
+            Array::Binary(array) => {
+                let mut encoder =
+                    BinaryDictEncoder::try_new(descriptor, options, Default::default())?;
+                encoder.encode_page(array)?;
+                encoder.finish()
+            }
+            _ => unreachable!("dictionaries are only tested for INT64 and BYTE_ARRAY"),
+        };
+        let data = write_to_buffer(schema, vec![pages], num_rows, options)?;
+
+        let (result, _) = super::super::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+        assert_eq!(result, array);
+        Ok(())
+    }
+
+    #[test]
+    fn dictionary_int64() -> Result<()> {
+        let array = (0..100)
+            .map(|x| if x % 7 == 0 { None } else { Some(x % 5) })
+            .collect::<Vec<_>>();
+        test_dictionary(Array::Int64(array.clone()), Version::V1)?;
+        test_dictionary(Array::Int64(array), Version::V2)
+    }
+
+    #[test]
+    fn dictionary_single_value() -> Result<()> {
+        let array = vec![Some(1i64), None, Some(1)];
+        test_dictionary(Array::Int64(array), Version::V1)
+    }
+
+    #[test]
+    fn dictionary_binary() -> Result<()> {
+        let array = (0..100)
+            .map(|x| {
+                if x % 7 == 0 {
+                    None
+                } else {
+                    Some(format!("value {}", x % 5).into_bytes())
+                }
+            })
+            .collect::<Vec<_>>();
+        test_dictionary(Array::Binary(array.clone()), Version::V1)?;
+        test_dictionary(Array::Binary(array), Version::V2)
+    }
+
+    #[test]
+    fn dictionary_metadata() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message("message schema { REQUIRED INT64 col; }")?;
+
+        let dictionary_options = DictionaryOptions {
+            max_dictionary_size: 2 * 8,
+        };
+        let mut encoder = PrimitiveDictEncoder::try_new(
+            schema.columns()[0].clone(),
+            options,
+            dictionary_options,
+        )?;
+        encoder.encode_page(&[Some(1i64), Some(2), Some(1)])?;
+        encoder.encode_page(&[Some(1), Some(2), Some(3)])?;
+        let data = write_to_buffer(schema, vec![encoder.finish()], 6, options)?;
+
+        let metadata = read_metadata(&mut Cursor::new(data))?;
+        let column = &metadata.row_groups[0].columns()[0];
+
+        // the dictionary page is written right after the magic number
+        assert_eq!(column.dictionary_page_offset(), Some(4));
+        assert!(column.data_page_offset() > 4);
+        assert_eq!(column.byte_range().0, 4);
+        assert_eq!(
+            column.page_encoding_stats(),
+            Some(&vec![
+                PageEncodingStats {
+                    page_type: PageType::DictionaryPage.into(),
+                    encoding: Encoding::Plain.into(),
+                    count: 1
+                },
+                PageEncodingStats {
+                    page_type: PageType::DataPage.into(),
+                    encoding: Encoding::RleDictionary.into(),
+                    count: 1
+                },
+                PageEncodingStats {
+                    page_type: PageType::DataPage.into(),
+                    encoding: Encoding::Plain.into(),
+                    count: 1
+                },
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_multiple_pages() -> Result<()> {
+        let options = WriteOptions {
+            compression: CompressionOptions::Snappy,
+            version: Version::V2,
+            ..Default::default()
+        };
+        let schema =
+            SchemaDescriptor::try_from_message("message schema { OPTIONAL BYTE_ARRAY col; }")?;
+
+        let dictionary_options = DictionaryOptions {
+            max_dictionary_size: 2 * (4 + 1),
+        };
+        let mut encoder =
+            BinaryDictEncoder::try_new(schema.columns()[0].clone(), options, dictionary_options)?;
+        // the third value does not fit in the dictionary: the second page falls back to PLAIN
+        let values = [
+            vec![Some(b"a".to_vec()), None, Some(b"b".to_vec())],
+            vec![Some(b"c".to_vec()), Some(b"a".to_vec())],
+            vec![None, Some(b"b".to_vec())],
+        ];
+        for page in &values {
+            encoder.encode_page(page)?;
+        }
+        let data = write_to_buffer(schema, vec![encoder.finish()], 7, options)?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata(&mut reader)?;
+        let column = &metadata.row_groups[0].columns()[0];
+
+        let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+        let pages = BasicDecompressor::new(pages, vec![]);
+        let result = deserialize_column(pages, column.descriptor())?;
+
+        assert_eq!(result, Values::Binary(values.concat()));
+        Ok(())
+    }
+
+    fn write_columns(parallel: bool) -> Result<Vec<u8>> {
+        let options = WriteOptions {
+            compression: CompressionOptions::Zstd(None),
+            version: Version::V2,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { OPTIONAL INT32 a; OPTIONAL INT64 b; OPTIONAL BYTE_ARRAY c; }",
+        )?;
+
+        let num_rows = 1000;
+        let a = (0..num_rows)
+            .map(|x| if x % 5 == 0 { None } else { Some(x as i32) })
+            .collect::<Vec<_>>();
+        let b = (0..num_rows)
+            .map(|x| Some(x as i64 % 17))
+            .collect::<Vec<_>>();
+        let c = (0..num_rows)
+            .map(|x| Some(format!("value {}", x % 31).into_bytes()))
+            .collect::<Vec<_>>();
+
+        let a = primitive::array_to_page_v1(&a, &options, &schema.columns()[0])?;
+        let mut encoder = PrimitiveDictEncoder::try_new(
+            schema.columns()[1].clone(),
+            options,
+            Default::default(),
+        )?;
+        encoder.encode_page(&b)?;
+        let b = encoder.finish();
+        let mut encoder =
+            BinaryDictEncoder::try_new(schema.columns()[2].clone(), options, Default::default())?;
+        encoder.encode_page(&c)?;
+        let c = encoder.finish();
+
+        let columns = row_group(vec![vec![a], b, c], options.compression);
 
         let writer = Cursor::new(vec![]);
         let mut writer = FileWriter::new(writer, schema, options, None);
 
         writer.start()?;
-        writer.write(DynIter::new(columns), 7)?;
-        let writer = writer.end(None)?.1;
+        if parallel {
+            writer.write_parallel(columns, num_rows)?;
+        } else {
+            writer.write(columns, num_rows)?;
+        }
+        Ok(writer.end(None)?.1.into_inner())
+    }
 
-        let data = writer.into_inner();
-        let mut reader = Cursor::new(data);
+    #[test]
+    fn parallel() -> Result<()> {
+        let data = write_columns(true)?;
+        assert_eq!(data, write_columns(false)?);
 
+        let mut reader = Cursor::new(data);
         let metadata = read_metadata(&mut reader)?;
+        let column = &metadata.row_groups[0].columns()[2];
+        let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+        let pages = BasicDecompressor::new(pages, vec![]);
+        let result = deserialize_column(pages, column.descriptor())?;
+        assert_eq!(result.len(), 1000);
+        Ok(())
+    }
 
-        // validated against an equivalent array produced by pyarrow.
-        let expected = 51;
-        assert_eq!(
-            metadata.row_groups[0].columns()[0].uncompressed_size(),
-            expected
-        );
+    #[test]
+    fn sorting_columns() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { REQUIRED INT32 tenant_id; REQUIRED INT64 ts; }",
+        )?;
+
+        let tenant_id = vec![Some(1i32), Some(1), Some(2), Some(3)];
+        let ts = vec![Some(10i64), Some(20), Some(5), Some(1)];
+        let sorting_columns = vec![
+            SortingColumn::new(0, false, false),
+            SortingColumn::new(1, true, true),
+        ];
+
+        let columns = || -> Result<_> {
+            Ok(row_group(
+                vec![
+                    vec![primitive::array_to_page_v1(
+                        &tenant_id,
+                        &options,
+                        &schema.columns()[0],
+                    )?],
+                    vec![primitive::array_to_page_v1(
+                        &ts,
+                        &options,
+                        &schema.columns()[1],
+                    )?],
+                ],
+                options.compression,
+            ))
+        };
 
+        let writer = Cursor::new(vec![]);
+        let mut writer = FileWriter::new(writer, schema.clone(), options, None);
+        writer.start()?;
+        writer.write(columns()?, tenant_id.len())?;
+        // only the row group written with sorting columns is declared sorted
+        writer.write_with_sorting_columns(columns()?, tenant_id.len(), sorting_columns.clone())?;
+        writer.write_parallel_with_sorting_columns(
+            columns()?,
+            tenant_id.len(),
+            sorting_columns.clone(),
+        )?;
+        assert!(writer
+            .write_with_sorting_columns(
+                columns()?,
+                tenant_id.len(),
+                vec![SortingColumn::new(2, false, false)]
+            )
+            .is_err());
+        let data = writer.end(None)?.1.into_inner();
+
+        // invalid sorting columns are rejected before writing the row group
+        let metadata = read_metadata(&mut Cursor::new(data))?;
+        assert_eq!(metadata.row_groups.len(), 3);
+        assert_eq!(metadata.row_groups[0].sorting_columns(), None);
+        for row_group in &metadata.row_groups[1..] {
+            assert_eq!(row_group.sorting_columns(), Some(sorting_columns.as_ref()));
+        }
         Ok(())
     }
 }
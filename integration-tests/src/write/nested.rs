@@ -0,0 +1,273 @@
+use parquet::{
+    encoding::Encoding,
+    error::Result,
+    metadata::ColumnDescriptor,
+    page::EncodedPage,
+    types::NativeType,
+    write::nested::{nested_page, Nested},
+    write::WriteOptions,
+};
+
+use super::Array;
+
+fn extend_primitive<T: NativeType>(array: &[Option<T>], values: &mut Vec<u8>) {
+    array
+        .iter()
+        .flatten()
+        .for_each(|x| values.extend_from_slice(x.to_le_bytes().as_ref()))
+}
+
+/// Appends the slots of `array` to the levels `nested[depth..]` and its non-null leaf values,
+/// plain-encoded, to `values`
+fn extend_nested(array: &Array, depth: usize, nested: &mut Vec<Nested>, values: &mut Vec<u8>) {
+    if nested.len() == depth {
+        nested.push(match array {
+            Array::List(_) => Nested::List {
+                offsets: vec![0],
+                validity: Some(vec![]),
+            },
+            Array::Struct(_, _) => Nested::Struct {
+                length: 0,
+                validity: Some(vec![]),
+            },
+            _ => Nested::Primitive {
+                length: 0,
+                validity: Some(vec![]),
+            },
+        });
+    }
+
+    match (array, &mut nested[depth]) {
+        (
+            Array::List(lists),
+            Nested::List {
+                offsets,
+                validity: Some(validity),
+            },
+        ) => {
+            let mut items = vec![];
+            for list in lists {
+                validity.push(list.is_some());
+                let length = list.as_ref().map(|x| x.len()).unwrap_or(0) as i64;
+                offsets.push(offsets[offsets.len() - 1] + length);
+                items.extend(list.iter());
+            }
+            items
+                .into_iter()
+                .for_each(|items| extend_nested(items, depth + 1, nested, values));
+        }
+        (
+            Array::Struct(arrays, array_validity),
+            Nested::Struct {
+                length,
+                validity: Some(validity),
+            },
+        ) => {
+            *length += array_validity.len();
+            validity.extend_from_slice(array_validity);
+            // a nested column has a single leaf
+            extend_nested(&arrays[0], depth + 1, nested, values);
+        }
+        (
+            array,
+            Nested::Primitive {
+                length,
+                validity: Some(validity),
+            },
+        ) => {
+            *length += array.len();
+            match array {
+                Array::Int32(array) => {
+                    validity.extend(array.iter().map(|x| x.is_some()));
+                    extend_primitive(array, values)
+                }
+                Array::Int64(array) => {
+                    validity.extend(array.iter().map(|x| x.is_some()));
+                    extend_primitive(array, values)
+                }
+                Array::Float32(array) => {
+                    validity.extend(array.iter().map(|x| x.is_some()));
+                    extend_primitive(array, values)
+                }
+                Array::Float64(array) => {
+                    validity.extend(array.iter().map(|x| x.is_some()));
+                    extend_primitive(array, values)
+                }
+                _ => todo!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Writes a (plain-encoded) nested `array` to a page.
+pub fn array_to_page(
+    array: &Array,
+    options: &WriteOptions,
+    descriptor: &ColumnDescriptor,
+) -> Result<EncodedPage> {
+    let mut nested = vec![];
+    let mut values = vec![];
+    extend_nested(array, 0, &mut nested, &mut values);
+
+    nested_page(descriptor, options, &nested, &values, Encoding::Plain, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use parquet::{
+        compression::CompressionOptions,
+        metadata::SchemaDescriptor,
+        read::{
+            deserialize::{deserialize_column, Values},
+            get_page_iterator,
+            nested::{levels, reconstruct},
+            read_metadata, BasicDecompressor,
+        },
+        statistics::PrimitiveStatistics,
+        write::{nested::nested_pages, Version},
+        FallibleStreamingIterator,
+    };
+
+    use super::super::write_to_buffer;
+    use super::*;
+
+    fn test_nested(message: &str, array: Array, version: Version) -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: false,
+            compression: CompressionOptions::Snappy,
+            version,
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message(message)?;
+
+        let page = array_to_page(&array, &options, &schema.columns()[0])?;
+        let data = write_to_buffer(schema, vec![vec![page]], array.len(), options)?;
+
+        let (result, _) = crate::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+        assert_eq!(result, array);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_list() -> Result<()> {
+        let message = "message schema {
+            OPTIONAL group col (LIST) {
+                REPEATED group list {
+                    OPTIONAL INT64 element;
+                }
+            }
+        }";
+        let array = Array::List(vec![
+            Some(Array::Int64(vec![Some(1), None, Some(2)])),
+            Some(Array::Int64(vec![])),
+            None,
+            Some(Array::Int64(vec![Some(3)])),
+        ]);
+        test_nested(message, array.clone(), Version::V1)?;
+        test_nested(message, array, Version::V2)
+    }
+
+    #[test]
+    fn nested_list_of_structs() -> Result<()> {
+        let message = "message schema {
+            OPTIONAL group col (LIST) {
+                REPEATED group list {
+                    OPTIONAL group element {
+                        OPTIONAL INT32 a;
+                    }
+                }
+            }
+        }";
+        let array = Array::List(vec![
+            Some(Array::Struct(
+                vec![Array::Int32(vec![Some(1), None, None])],
+                vec![true, true, false],
+            )),
+            None,
+            Some(Array::Struct(vec![Array::Int32(vec![Some(2)])], vec![true])),
+        ]);
+        test_nested(message, array.clone(), Version::V1)?;
+        test_nested(message, array, Version::V2)
+    }
+
+    #[test]
+    fn nested_split_pages() -> Result<()> {
+        let options = WriteOptions {
+            write_statistics: true,
+            compression: CompressionOptions::Snappy,
+            data_page_row_limit: Some(4),
+            ..Default::default()
+        };
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema {
+                OPTIONAL group col (LIST) {
+                    REPEATED group list {
+                        REQUIRED INT64 element;
+                    }
+                }
+            }",
+        )?;
+        let descriptor = schema.columns()[0].clone();
+
+        // 10 rows: [], [0], [0, 1], ..., every third row null
+        let num_rows = 10;
+        let validity = (0..num_rows).map(|x| x % 3 != 2).collect::<Vec<_>>();
+        let mut offsets = vec![0i64];
+        for (row, is_valid) in validity.iter().enumerate() {
+            let length = if *is_valid { row as i64 } else { 0 };
+            offsets.push(offsets[offsets.len() - 1] + length);
+        }
+        let num_values = offsets[num_rows] as usize;
+        let nested = vec![
+            Nested::List {
+                offsets,
+                validity: Some(validity),
+            },
+            Nested::Primitive {
+                length: num_values,
+                validity: None,
+            },
+        ];
+        let values = (0..num_values as i64).collect::<Vec<_>>();
+        let encoded = values.iter().map(|x| x.to_le_bytes()).collect::<Vec<_>>();
+
+        let pages = nested_pages(&descriptor, &options, &nested, &encoded)?;
+        assert_eq!(pages.len(), 3);
+        let data = write_to_buffer(schema, vec![pages], num_rows, options)?;
+
+        let mut reader = Cursor::new(data);
+        let metadata = read_metadata(&mut reader)?;
+        let column = &metadata.row_groups[0].columns()[0];
+
+        // the statistics of the column chunk are reduced from those of its pages
+        let statistics = column.statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<i64>>()
+            .unwrap();
+        assert_eq!(statistics.min_value, Some(0));
+        assert_eq!(statistics.max_value, Some(num_values as i64 - 1));
+
+        // the levels of consecutive pages can be chained, as every page starts at a new row
+        let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+        let mut pages = BasicDecompressor::new(pages, vec![]);
+        let mut all_levels = vec![];
+        while let Some(page) = pages.next()? {
+            all_levels.extend(levels(page, &descriptor)?);
+        }
+        assert_eq!(reconstruct(&descriptor, all_levels)?, nested);
+
+        let pages = get_page_iterator(column, &mut reader, None, vec![])?;
+        let pages = BasicDecompressor::new(pages, vec![]);
+        // each empty or null list has a `None` value
+        let result = match deserialize_column(pages, &descriptor)? {
+            Values::Int64(result) => result.into_iter().flatten().collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+        assert_eq!(result, values);
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
 use parquet::{
-    encoding::Encoding,
+    encoding::{byte_stream_split, Encoding},
     metadata::ColumnDescriptor,
     page::{DataPage, DataPageHeader, DataPageHeaderV1, EncodedPage},
     statistics::{serialize_statistics, PrimitiveStatistics, Statistics},
     types::NativeType,
     write::WriteOptions,
-    {encoding::hybrid_rle::encode_bool, error::Result},
+    {
+        encoding::hybrid_rle::encode_bool,
+        error::{ParquetError, Result},
+    },
 };
 
 fn unzip_option<T: NativeType>(array: &[Option<T>]) -> Result<(Vec<u8>, Vec<u8>)> {
@@ -44,10 +47,32 @@ pub fn array_to_page_v1<T: NativeType>(
     array: &[Option<T>],
     options: &WriteOptions,
     descriptor: &ColumnDescriptor,
+) -> Result<EncodedPage> {
+    array_to_page_v1_encoded(array, options, descriptor, Encoding::Plain)
+}
+
+/// Like [`array_to_page_v1`], but encoding the values with `encoding`
+/// (`Plain` or `ByteStreamSplit`).
+pub fn array_to_page_v1_encoded<T: NativeType>(
+    array: &[Option<T>],
+    options: &WriteOptions,
+    descriptor: &ColumnDescriptor,
+    encoding: Encoding,
 ) -> Result<EncodedPage> {
     let (values, mut buffer) = unzip_option(array)?;
 
-    buffer.extend_from_slice(&values);
+    match encoding {
+        Encoding::Plain => buffer.extend_from_slice(&values),
+        Encoding::ByteStreamSplit => {
+            byte_stream_split::encode_fixed_len(&values, std::mem::size_of::<T>(), &mut buffer)?
+        }
+        _ => {
+            return Err(ParquetError::General(format!(
+                "Encoding {:?} is not supported by this writer",
+                encoding
+            )))
+        }
+    }
 
     let statistics = if options.write_statistics {
         let statistics = &PrimitiveStatistics {
@@ -64,7 +89,7 @@ pub fn array_to_page_v1<T: NativeType>(
 
     let header = DataPageHeaderV1 {
         num_values: array.len() as i32,
-        encoding: Encoding::Plain.into(),
+        encoding: encoding.into(),
         definition_level_encoding: Encoding::Rle.into(),
         repetition_level_encoding: Encoding::Rle.into(),
         statistics,
@@ -77,3 +102,75 @@ pub fn array_to_page_v1<T: NativeType>(
         descriptor.clone(),
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use parquet::{compression::CompressionOptions, metadata::SchemaDescriptor};
+
+    use super::super::write_to_buffer;
+    use super::*;
+    use crate::Array;
+
+    fn test_byte_stream_split(array: Array) -> Result<()> {
+        let options = WriteOptions {
+            compression: CompressionOptions::Zstd(None),
+            ..Default::default()
+        };
+
+        let (type_, num_rows) = match &array {
+            Array::Float32(array) => ("FLOAT", array.len()),
+            Array::Float64(array) => ("DOUBLE", array.len()),
+            _ => unreachable!("byte stream split is only tested for FLOAT and DOUBLE"),
+        };
+        let schema = SchemaDescriptor::try_from_message(&format!(
+            "message schema {{ OPTIONAL {} col; }}",
+            type_
+        ))?;
+
+        let descriptor = &schema.columns()[0];
+        let page = match &array {
+            Array::Float32(array) => {
+                array_to_page_v1_encoded(array, &options, descriptor, Encoding::ByteStreamSplit)
+            }
+            Array::Float64(array) => {
+                array_to_page_v1_encoded(array, &options, descriptor, Encoding::ByteStreamSplit)
+            }
+            _ => unreachable!("byte stream split is only tested for FLOAT and DOUBLE"),
+        }?;
+        let data = write_to_buffer(schema, vec![vec![page]], num_rows, options)?;
+
+        let (result, _) = crate::read::tests::read_column(&mut Cursor::new(data), 0, 0)?;
+        assert_eq!(result, array);
+        Ok(())
+    }
+
+    #[test]
+    fn byte_stream_split_f32() -> Result<()> {
+        let array = (0..100)
+            .map(|x| {
+                if x % 7 == 0 {
+                    None
+                } else {
+                    Some(x as f32 * 0.3)
+                }
+            })
+            .collect();
+        test_byte_stream_split(Array::Float32(array))
+    }
+
+    #[test]
+    fn byte_stream_split_f64() -> Result<()> {
+        let array = (0..100)
+            .map(|x| {
+                if x % 7 == 0 {
+                    None
+                } else {
+                    Some(x as f64 * 0.3)
+                }
+            })
+            .collect();
+        test_byte_stream_split(Array::Float64(array))
+    }
+}
@@ -0,0 +1,131 @@
+use crate::error::{ParquetError, Result};
+use crate::types::NativeType;
+
+use super::hash::{hash_byte, hash_native};
+use super::split_block::{insert, is_in_set, BLOCK_SIZE};
+
+/// The maximum size of a bitset, as recommended by the spec.
+pub(super) const MAX_NUM_BYTES: usize = 128 * 1024 * 1024;
+
+/// A split-block bloom filter, used to check whether a value is (probably) in a column chunk.
+///
+/// Bloom filters have no false negatives: when [`BloomFilter::contains_hash`] returns `false`,
+/// the value is guaranteed to not be in the column chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bitset: Vec<u8>,
+}
+
+impl BloomFilter {
+    /// Returns an empty [`BloomFilter`] of (approximately) `num_bytes`.
+    /// The size is rounded to the next power of two and bounded between 32 bytes and 128MiB.
+    pub fn new(num_bytes: usize) -> Self {
+        let num_bytes = num_bytes
+            .clamp(BLOCK_SIZE, MAX_NUM_BYTES)
+            .next_power_of_two();
+        Self {
+            bitset: vec![0; num_bytes],
+        }
+    }
+
+    /// Returns an empty [`BloomFilter`] sized to hold `num_distinct_values` with
+    /// a false positive probability of at most `fpp`.
+    /// # Error
+    /// Errors iff `fpp` is not within `(0, 1)`.
+    pub fn with_fpp(num_distinct_values: usize, fpp: f64) -> Result<Self> {
+        if fpp.is_nan() || fpp <= 0.0 || fpp >= 1.0 {
+            return Err(general_err!(
+                "The false positive probability of a bloom filter must be within (0, 1), got {}",
+                fpp
+            ));
+        }
+        // SPEC: m = -k * n / ln(1 - p^(1/k)), with k = 8 bits set per value
+        let num_bits = -8.0 * num_distinct_values as f64 / (1.0 - fpp.powf(1.0 / 8.0)).ln();
+        Ok(Self::new((num_bits / 8.0).ceil() as usize))
+    }
+
+    /// Returns a [`BloomFilter`] from an existing bitset.
+    /// # Error
+    /// Errors iff the length of `bitset` is not a non-zero multiple of 32.
+    pub fn try_from_bitset(bitset: Vec<u8>) -> Result<Self> {
+        // BLOCK_SIZE is a power of two
+        if bitset.is_empty() || bitset.len() & (BLOCK_SIZE - 1) != 0 {
+            return Err(ParquetError::OutOfSpec(
+                "The bitset of a bloom filter must be a non-zero multiple of 32 bytes".to_string(),
+            ));
+        }
+        Ok(Self { bitset })
+    }
+
+    /// The bitset of this filter
+    pub fn bitset(&self) -> &[u8] {
+        &self.bitset
+    }
+
+    /// Returns the bitset of this filter
+    pub fn into_bitset(self) -> Vec<u8> {
+        self.bitset
+    }
+
+    /// Inserts the (xxh64) hash of a value
+    pub fn insert_hash(&mut self, hash: u64) {
+        insert(&mut self.bitset, hash)
+    }
+
+    /// Returns whether the (xxh64) hash of a value may be in the filter
+    pub fn contains_hash(&self, hash: u64) -> bool {
+        is_in_set(&self.bitset, hash)
+    }
+
+    /// Inserts a value of a [`NativeType`] physical type (e.g. `Int32`, `Double`).
+    pub fn insert_native<T: NativeType>(&mut self, value: T) {
+        self.insert_hash(hash_native(value))
+    }
+
+    /// Returns whether a value of a [`NativeType`] physical type may be in the filter.
+    pub fn contains_native<T: NativeType>(&self, value: T) -> bool {
+        self.contains_hash(hash_native(value))
+    }
+
+    /// Inserts a value of the `ByteArray` or `FixedLenByteArray` physical types.
+    pub fn insert_bytes<A: AsRef<[u8]>>(&mut self, value: A) {
+        self.insert_hash(hash_byte(value))
+    }
+
+    /// Returns whether a value of the `ByteArray` or `FixedLenByteArray` physical types
+    /// may be in the filter.
+    pub fn contains_bytes<A: AsRef<[u8]>>(&self, value: A) -> bool {
+        self.contains_hash(hash_byte(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes() -> Result<()> {
+        assert_eq!(BloomFilter::new(0).bitset().len(), 32);
+        assert_eq!(BloomFilter::new(33).bitset().len(), 64);
+        assert_eq!(
+            BloomFilter::with_fpp(1_000_000, 0.01)?.bitset().len(),
+            2 * 1024 * 1024
+        );
+        assert!(BloomFilter::try_from_bitset(vec![0; 33]).is_err());
+        for fpp in [0.0, 1.0, -0.5, f64::NAN] {
+            assert!(BloomFilter::with_fpp(100, fpp).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn contains() -> Result<()> {
+        let mut filter = BloomFilter::with_fpp(100, 0.01)?;
+        (0..100i64).for_each(|x| filter.insert_native(x));
+        filter.insert_bytes("id-1");
+
+        assert!((0..100i64).all(|x| filter.contains_native(x)));
+        assert!(filter.contains_bytes(b"id-1"));
+        Ok(())
+    }
+}
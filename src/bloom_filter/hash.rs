@@ -0,0 +1,30 @@
+use xxhash_rust::xxh64::xxh64;
+
+use crate::types::NativeType;
+
+const SEED: u64 = 0;
+
+/// (xxh64) hash of a [`NativeType`], as required by the spec.
+#[inline]
+pub fn hash_native<T: NativeType>(value: T) -> u64 {
+    xxh64(value.to_le_bytes().as_ref(), SEED)
+}
+
+/// (xxh64) hash of a sequence of bytes (e.g. ByteArray), as required by the spec.
+#[inline]
+pub fn hash_byte<A: AsRef<[u8]>>(value: A) -> u64 {
+    xxh64(value.as_ref(), SEED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_values() {
+        // from the xxhash reference implementation
+        assert_eq!(hash_byte(b""), 0xEF46DB3751D8E999);
+        assert_eq!(hash_byte(b"a"), 0xD24EC4F1A98C6E5B);
+        assert_eq!(hash_native(0i32), hash_byte(0i32.to_le_bytes()));
+    }
+}
@@ -0,0 +1,11 @@
+//! API to read, write and probe the split-block bloom filters of column chunks.
+//! See the [spec](https://github.com/apache/parquet-format/blob/master/BloomFilter.md) for details.
+mod filter;
+mod hash;
+mod read;
+mod split_block;
+
+pub use filter::BloomFilter;
+pub use hash::{hash_byte, hash_native};
+pub use read::read;
+pub use split_block::{insert, is_in_set};
@@ -0,0 +1,67 @@
+use std::convert::TryInto;
+use std::io::{Read, Seek, SeekFrom};
+
+use parquet_format_async_temp::{thrift::protocol::TCompactInputProtocol, BloomFilterHeader};
+
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnChunkMetaData;
+
+use super::filter::MAX_NUM_BYTES;
+use super::BloomFilter;
+
+/// Reads the bloom filter associated to [`ColumnChunkMetaData`].
+/// Returns `None` if the column chunk has no bloom filter.
+/// # Error
+/// Errors if the metadata of the column chunk is not available (see
+/// [`ColumnChunkMetaData::has_metadata`]), or if the declared size of the bloom filter is
+/// negative, larger than 128MiB or larger than what remains of the file.
+pub fn read<R: Read + Seek>(
+    column_metadata: &ColumnChunkMetaData,
+    mut reader: &mut R,
+) -> Result<Option<BloomFilter>> {
+    if !column_metadata.has_metadata() {
+        return Err(general_err!(
+            "The metadata of an encrypted column chunk can only be read with its key"
+        ));
+    }
+    let offset = if let Some(offset) = column_metadata.bloom_filter_offset() {
+        offset as u64
+    } else {
+        return Ok(None);
+    };
+    reader.seek(SeekFrom::Start(offset))?;
+
+    // deserialize header
+    let mut prot = TCompactInputProtocol::new(&mut reader);
+    let header = BloomFilterHeader::read_from_in_protocol(&mut prot)?;
+
+    // the size is validated before allocating it, as it is read from the file.
+    let num_bytes: usize = header.num_bytes.try_into().map_err(|_| {
+        ParquetError::OutOfSpec(
+            "The number of bytes of a bloom filter must be positive".to_string(),
+        )
+    })?;
+    // a bitset is never larger than the maximum recommended by the spec. It may be larger than
+    // its column chunk (e.g. writers size it for an unknown number of distinct values).
+    if num_bytes > MAX_NUM_BYTES {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The bloom filter has {} bytes, but at most {} are allowed",
+            num_bytes, MAX_NUM_BYTES
+        )));
+    }
+    let position = reader.stream_position()?;
+    let remaining = reader.seek(SeekFrom::End(0))?.saturating_sub(position);
+    if num_bytes as u64 > remaining {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The bloom filter has {} bytes, but only {} remain in the file",
+            num_bytes, remaining
+        )));
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    // SPEC: only the split-block algorithm with xxh64 and no compression is currently defined,
+    // which the thrift deserialization already enforced.
+    let mut bitset = vec![0; num_bytes];
+    reader.read_exact(&mut bitset)?;
+
+    BloomFilter::try_from_bitset(bitset).map(Some)
+}
@@ -0,0 +1,119 @@
+use std::convert::TryInto;
+
+/// magic numbers taken from https://github.com/apache/parquet-format/blob/master/BloomFilter.md
+const SALT: [u32; 8] = [
+    0x47b6137b_u32,
+    0x44974d91_u32,
+    0x8824ad5b_u32,
+    0xa2b7289d_u32,
+    0x705495c7_u32,
+    0x2df1424b_u32,
+    0x9efc4947_u32,
+    0x5c6bfb31_u32,
+];
+
+/// The number of bytes of each block of a split-block bloom filter
+pub(super) const BLOCK_SIZE: usize = 32;
+
+fn hash_to_block_index(hash: u64, len: usize) -> usize {
+    let number_of_blocks = len as u64 / BLOCK_SIZE as u64;
+    let low_hash = hash >> 32;
+    let block_index = ((low_hash * number_of_blocks) >> 32) as u32;
+    block_index as usize
+}
+
+fn new_mask(x: u32) -> [u32; 8] {
+    let mut a = [0u32; 8];
+    for (a, salt) in a.iter_mut().zip(SALT.iter()) {
+        let mask = x.wrapping_mul(*salt);
+        let mask = mask >> 27;
+        *a = 0x1 << mask;
+    }
+    a
+}
+
+/// loads a block from the bitset to the stack
+#[inline]
+fn load_block(bitset: &[u8]) -> [u32; 8] {
+    let mut a = [0u32; 8];
+    let bitset = bitset.chunks_exact(4).take(8);
+    for (a, chunk) in a.iter_mut().zip(bitset) {
+        *a = u32::from_le_bytes(chunk.try_into().unwrap())
+    }
+    a
+}
+
+/// assigns a block from the stack to `bitset`
+#[inline]
+fn unload_block(block: [u32; 8], bitset: &mut [u8]) {
+    let bitset = bitset.chunks_exact_mut(4).take(8);
+    for (a, chunk) in block.iter().zip(bitset) {
+        let a = a.to_le_bytes();
+        chunk[0] = a[0];
+        chunk[1] = a[1];
+        chunk[2] = a[2];
+        chunk[3] = a[3];
+    }
+}
+
+/// Returns whether the `hash` is in the set
+/// # Panics
+/// This function panics if `bitset` is empty or its length is not a multiple of 32.
+pub fn is_in_set(bitset: &[u8], hash: u64) -> bool {
+    let block_index = hash_to_block_index(hash, bitset.len());
+    let key = hash as u32;
+
+    let mask = new_mask(key);
+    let slice = &bitset[block_index * BLOCK_SIZE..(block_index + 1) * BLOCK_SIZE];
+    let block_mask = load_block(slice);
+
+    mask.iter()
+        .zip(block_mask.iter())
+        .all(|(mask, block)| mask & block != 0)
+}
+
+/// Inserts a new hash to the set
+/// # Panics
+/// This function panics if `bitset` is empty or its length is not a multiple of 32.
+pub fn insert(bitset: &mut [u8], hash: u64) {
+    let block_index = hash_to_block_index(hash, bitset.len());
+    let key = hash as u32;
+
+    let mask = new_mask(key);
+    let slice = &mut bitset[block_index * BLOCK_SIZE..(block_index + 1) * BLOCK_SIZE];
+    let mut block_mask = load_block(slice);
+
+    for (block, mask) in block_mask.iter_mut().zip(mask.iter()) {
+        *block |= mask;
+    }
+    unload_block(block_mask, slice)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_native;
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let mut bitset = vec![0; 32];
+
+        // insert
+        for a in 0..10i64 {
+            let hash = hash_native(a);
+            insert(&mut bitset, hash);
+        }
+
+        // bloom filters are guaranteed to have no false negatives
+        for a in 0..10i64 {
+            let hash = hash_native(a);
+            assert!(is_in_set(&bitset, hash));
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let bitset = vec![0; 64];
+        assert!(!is_in_set(&bitset, hash_native(1i32)));
+    }
+}
@@ -1,4 +1,9 @@
-pub use super::parquet_bridge::Compression;
+pub use super::parquet_bridge::{
+    BrotliLevel, Compression, CompressionOptions, GzipLevel, ZstdLevel,
+};
+
+use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::error::{ParquetError, Result};
 
@@ -7,46 +12,50 @@ use crate::error::{ParquetError, Result};
 /// Note that you'll need to call `clear()` before reusing the same `output_buf`
 /// across different `compress` calls.
 pub fn compress(
-    compression: Compression,
+    compression: CompressionOptions,
     input_buf: &[u8],
     output_buf: &mut Vec<u8>,
 ) -> Result<()> {
     match compression {
         #[cfg(feature = "brotli")]
-        Compression::Brotli => {
+        CompressionOptions::Brotli(level) => {
             use std::io::Write;
             const BROTLI_DEFAULT_BUFFER_SIZE: usize = 4096;
-            const BROTLI_DEFAULT_COMPRESSION_QUALITY: u32 = 1; // supported levels 0-9
             const BROTLI_DEFAULT_LG_WINDOW_SIZE: u32 = 22; // recommended between 20-22
 
+            let level = level.unwrap_or_default();
             let mut encoder = brotli::CompressorWriter::new(
                 output_buf,
                 BROTLI_DEFAULT_BUFFER_SIZE,
-                BROTLI_DEFAULT_COMPRESSION_QUALITY,
+                level.compression_level(),
                 BROTLI_DEFAULT_LG_WINDOW_SIZE,
             );
             encoder.write_all(input_buf)?;
             encoder.flush().map_err(|e| e.into())
         }
         #[cfg(not(feature = "brotli"))]
-        Compression::Brotli => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Brotli(_) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Brotli,
             "compress to brotli".to_string(),
         )),
         #[cfg(feature = "gzip")]
-        Compression::Gzip => {
+        CompressionOptions::Gzip(level) => {
             use std::io::Write;
-            let mut encoder = flate2::write::GzEncoder::new(output_buf, Default::default());
+            let level = level.unwrap_or_default();
+            let mut encoder = flate2::write::GzEncoder::new(
+                output_buf,
+                flate2::Compression::new(level.compression_level() as u32),
+            );
             encoder.write_all(input_buf)?;
             encoder.try_finish().map_err(|e| e.into())
         }
         #[cfg(not(feature = "gzip"))]
-        Compression::Gzip => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Gzip(_) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Gzip,
             "compress to gzip".to_string(),
         )),
         #[cfg(feature = "snappy")]
-        Compression::Snappy => {
+        CompressionOptions::Snappy => {
             use snap::raw::{max_compress_len, Encoder};
 
             let output_buf_len = output_buf.len();
@@ -57,38 +66,38 @@ pub fn compress(
             Ok(())
         }
         #[cfg(not(feature = "snappy"))]
-        Compression::Snappy => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Snappy => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Snappy,
             "compress to snappy".to_string(),
         )),
         #[cfg(feature = "lz4")]
-        Compression::Lz4 => {
-            use std::io::Write;
-            const LZ4_BUFFER_SIZE: usize = 4096;
-            let mut encoder = lz4::EncoderBuilder::new().build(output_buf)?;
-            let mut from = 0;
-            loop {
-                let to = std::cmp::min(from + LZ4_BUFFER_SIZE, input_buf.len());
-                encoder.write_all(&input_buf[from..to])?;
-                from += LZ4_BUFFER_SIZE;
-                if from >= input_buf.len() {
-                    break;
-                }
-            }
-            encoder.finish().1.map_err(|e| e.into())
+        CompressionOptions::Lz4 => {
+            // SPEC: a single block, prefixed by its decompressed and compressed sizes (big-endian)
+            let header = output_buf.len();
+            output_buf.extend_from_slice(&[0; 8]);
+            let n = compress_lz4_raw(input_buf, output_buf)?;
+            output_buf[header..header + 4].copy_from_slice(&(input_buf.len() as u32).to_be_bytes());
+            output_buf[header + 4..header + 8].copy_from_slice(&(n as u32).to_be_bytes());
+            Ok(())
         }
         #[cfg(not(feature = "lz4"))]
-        Compression::Lz4 => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Lz4 => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Lz4,
             "compress to lz4".to_string(),
         )),
+        #[cfg(feature = "lz4")]
+        CompressionOptions::Lz4Raw => compress_lz4_raw(input_buf, output_buf).map(|_| ()),
+        #[cfg(not(feature = "lz4"))]
+        CompressionOptions::Lz4Raw => Err(ParquetError::FeatureNotActive(
+            crate::error::Feature::Lz4,
+            "compress to lz4_raw".to_string(),
+        )),
         #[cfg(feature = "zstd")]
-        Compression::Zstd => {
+        CompressionOptions::Zstd(level) => {
             use std::io::Write;
-            /// Compression level (1-21) for ZSTD. Choose 1 here for better compression speed.
-            const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+            let level = level.unwrap_or_default();
 
-            let mut encoder = zstd::Encoder::new(output_buf, ZSTD_COMPRESSION_LEVEL)?;
+            let mut encoder = zstd::Encoder::new(output_buf, level.compression_level())?;
             encoder.write_all(input_buf)?;
             match encoder.finish() {
                 Ok(_) => Ok(()),
@@ -96,11 +105,11 @@ pub fn compress(
             }
         }
         #[cfg(not(feature = "zstd"))]
-        Compression::Zstd => Err(ParquetError::FeatureNotActive(
+        CompressionOptions::Zstd(_) => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Zstd,
             "compress to zstd".to_string(),
         )),
-        Compression::Uncompressed => {
+        CompressionOptions::Uncompressed => {
             Err(general_err!("Compressing without compression is not valid"))
         }
         _ => Err(general_err!(
@@ -110,17 +119,34 @@ pub fn compress(
     }
 }
 
+/// Compresses `input_buf` into a single LZ4 block appended to `output_buf`, and returns its size.
+#[cfg(feature = "lz4")]
+fn compress_lz4_raw(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
+    let offset = output_buf.len();
+    let required_len = lz4::block::compress_bound(input_buf.len())?;
+    output_buf.resize(offset + required_len, 0);
+    let n = lz4::block::compress_to_buffer(input_buf, None, false, &mut output_buf[offset..])?;
+    output_buf.truncate(offset + n);
+    Ok(n)
+}
+
 /// Decompresses data stored in slice `input_buf` and writes output to `output_buf`.
-/// Returns the total number of bytes written.
+/// # Errors
+/// Errors with [`ParquetError::OutOfSpec`] if `input_buf` does not decompress to exactly
+/// `output_buf.len()` bytes, the uncompressed size declared by the page header.
+///
+/// Pages compressed with [`Compression::Lz4`] are read in any of the framings used by writers
+/// of this codec: the Hadoop framing (parquet-mr and this crate), the LZ4 frame format (previous
+/// versions of this crate) and raw LZ4 blocks (previous versions of parquet-cpp).
+/// Likewise, pages compressed with [`Compression::Lzo`] (which can only be decompressed) are read
+/// as Hadoop-framed (parquet-mr) or raw LZO1X blocks.
 pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
     match compression {
         #[cfg(feature = "brotli")]
         Compression::Brotli => {
-            use std::io::Read;
             const BROTLI_DEFAULT_BUFFER_SIZE: usize = 4096;
-            brotli::Decompressor::new(input_buf, BROTLI_DEFAULT_BUFFER_SIZE)
-                .read_exact(output_buf)
-                .map_err(|e| e.into())
+            let decoder = brotli::Decompressor::new(input_buf, BROTLI_DEFAULT_BUFFER_SIZE);
+            decompress_exact(decoder, output_buf, compression)
         }
         #[cfg(not(feature = "brotli"))]
         Compression::Brotli => Err(ParquetError::FeatureNotActive(
@@ -129,9 +155,8 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
         )),
         #[cfg(feature = "gzip")]
         Compression::Gzip => {
-            use std::io::Read;
-            let mut decoder = flate2::read::GzDecoder::new(input_buf);
-            decoder.read_exact(output_buf).map_err(|e| e.into())
+            let decoder = flate2::read::GzDecoder::new(input_buf);
+            decompress_exact(decoder, output_buf, compression)
         }
         #[cfg(not(feature = "gzip"))]
         Compression::Gzip => Err(ParquetError::FeatureNotActive(
@@ -143,7 +168,9 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
             use snap::raw::{decompress_len, Decoder};
 
             let len = decompress_len(input_buf)?;
-            assert!(len <= output_buf.len());
+            if len != output_buf.len() {
+                return Err(size_mismatch(compression, len, output_buf.len()));
+            }
             Decoder::new()
                 .decompress(input_buf, output_buf)
                 .map_err(|e| e.into())
@@ -155,21 +182,30 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
             "decompress with snappy".to_string(),
         )),
         #[cfg(feature = "lz4")]
-        Compression::Lz4 => {
-            use std::io::Read;
-            let mut decoder = lz4::Decoder::new(input_buf)?;
-            decoder.read_exact(output_buf).map_err(|e| e.into())
-        }
+        Compression::Lz4 => decompress_lz4(input_buf, output_buf),
         #[cfg(not(feature = "lz4"))]
         Compression::Lz4 => Err(ParquetError::FeatureNotActive(
             crate::error::Feature::Lz4,
             "decompress with lz4".to_string(),
         )),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4Raw => decompress_lz4_raw(input_buf, output_buf, compression),
+        #[cfg(not(feature = "lz4"))]
+        Compression::Lz4Raw => Err(ParquetError::FeatureNotActive(
+            crate::error::Feature::Lz4,
+            "decompress with lz4_raw".to_string(),
+        )),
+        #[cfg(feature = "lzo")]
+        Compression::Lzo => decompress_lzo(input_buf, output_buf),
+        #[cfg(not(feature = "lzo"))]
+        Compression::Lzo => Err(ParquetError::FeatureNotActive(
+            crate::error::Feature::Lzo,
+            "decompress with lzo".to_string(),
+        )),
         #[cfg(feature = "zstd")]
         Compression::Zstd => {
-            use std::io::Read;
-            let mut decoder = zstd::Decoder::new(input_buf)?;
-            decoder.read_exact(output_buf).map_err(|e| e.into())
+            let decoder = zstd::Decoder::new(input_buf)?;
+            decompress_exact(decoder, output_buf, compression)
         }
         #[cfg(not(feature = "zstd"))]
         Compression::Zstd => Err(ParquetError::FeatureNotActive(
@@ -179,18 +215,407 @@ pub fn decompress(compression: Compression, input_buf: &[u8], output_buf: &mut [
         Compression::Uncompressed => {
             Err(general_err!("Compressing without compression is not valid"))
         }
-        _ => Err(general_err!(
-            "Compression {:?} is not yet supported",
-            compression
-        )),
     }
 }
 
+/// A codec that compresses and decompresses the pages of a column chunk into caller-provided
+/// buffers. Implement it to replace the codec of a [`Compression`] (e.g. with a
+/// hardware-accelerated zstd), and register it in a [`CodecRegistry`].
+pub trait Codec: std::fmt::Debug + Send + Sync {
+    /// Compresses `input_buf` with `compression`, appending the result to `output_buf`.
+    fn compress(
+        &self,
+        compression: CompressionOptions,
+        input_buf: &[u8],
+        output_buf: &mut Vec<u8>,
+    ) -> Result<()>;
+
+    /// Decompresses `input_buf`, compressed with `compression`, into `output_buf`, whose length
+    /// is the decompressed size of the page.
+    fn decompress(
+        &self,
+        compression: Compression,
+        input_buf: &[u8],
+        output_buf: &mut [u8],
+    ) -> Result<()>;
+}
+
+/// The [`Codec`] of every [`Compression`] supported by this crate, i.e. [`compress`] and
+/// [`decompress`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BuiltinCodec;
+
+impl Codec for BuiltinCodec {
+    fn compress(
+        &self,
+        compression: CompressionOptions,
+        input_buf: &[u8],
+        output_buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        compress(compression, input_buf, output_buf)
+    }
+
+    fn decompress(
+        &self,
+        compression: Compression,
+        input_buf: &[u8],
+        output_buf: &mut [u8],
+    ) -> Result<()> {
+        decompress(compression, input_buf, output_buf)
+    }
+}
+
+/// The [`Codec`]s used to compress and decompress pages, per [`Compression`].
+/// Compressions without a registered codec use the [`BuiltinCodec`].
+///
+/// It is consulted by [`crate::write::Compressor`], [`crate::read::PageIterator`] and
+/// `crate::read::get_page_stream_with_codecs` (which decompress dictionary pages),
+/// [`crate::read::Decompressor`] and [`crate::read::BasicDecompressor`].
+#[derive(Debug, Clone, Default)]
+pub struct CodecRegistry {
+    codecs: HashMap<Compression, Arc<dyn Codec>>,
+}
+
+impl CodecRegistry {
+    /// Registers `codec` for `compression`, returning the codec it replaces, if any.
+    pub fn register(
+        &mut self,
+        compression: Compression,
+        codec: Arc<dyn Codec>,
+    ) -> Option<Arc<dyn Codec>> {
+        self.codecs.insert(compression, codec)
+    }
+
+    /// Returns the [`Codec`] of `compression`
+    pub fn get(&self, compression: Compression) -> &dyn Codec {
+        self.codecs
+            .get(&compression)
+            .map(|codec| codec.as_ref())
+            .unwrap_or(&BuiltinCodec)
+    }
+
+    /// Compresses `input_buf` with the codec of `compression`, appending the result to
+    /// `output_buf`.
+    pub fn compress(
+        &self,
+        compression: CompressionOptions,
+        input_buf: &[u8],
+        output_buf: &mut Vec<u8>,
+    ) -> Result<()> {
+        self.get(compression.into())
+            .compress(compression, input_buf, output_buf)
+    }
+
+    /// Decompresses `input_buf` with the codec of `compression` into `output_buf`.
+    pub fn decompress(
+        &self,
+        compression: Compression,
+        input_buf: &[u8],
+        output_buf: &mut [u8],
+    ) -> Result<()> {
+        self.get(compression)
+            .decompress(compression, input_buf, output_buf)
+    }
+}
+
+/// Returns the error of a page whose decompressed size differs from the size declared in its
+/// header.
+#[cfg(any(
+    feature = "brotli",
+    feature = "gzip",
+    feature = "snappy",
+    feature = "lz4",
+    feature = "zstd",
+    feature = "lzo"
+))]
+fn size_mismatch(compression: Compression, size: usize, declared_size: usize) -> ParquetError {
+    ParquetError::OutOfSpec(format!(
+        "A page compressed with {:?} decompressed to {} bytes but its header declares {}",
+        compression, size, declared_size
+    ))
+}
+
+/// Reads `decoder` into `output_buf`, erroring if it does not decompress to exactly
+/// `output_buf.len()` bytes.
+#[cfg(any(
+    feature = "brotli",
+    feature = "gzip",
+    feature = "lz4",
+    feature = "zstd"
+))]
+fn decompress_exact<R: std::io::Read>(
+    mut decoder: R,
+    output_buf: &mut [u8],
+    compression: Compression,
+) -> Result<()> {
+    let mut size = 0;
+    while size < output_buf.len() {
+        match decoder.read(&mut output_buf[size..]) {
+            Ok(0) => return Err(size_mismatch(compression, size, output_buf.len())),
+            Ok(n) => size += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    // the decoder must be exhausted
+    let mut remaining = [0u8; 1];
+    if decoder.read(&mut remaining)? != 0 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "A page compressed with {:?} decompressed to more bytes than the {} its header declares",
+            compression,
+            output_buf.len()
+        )));
+    }
+    Ok(())
+}
+
+/// The magic number that starts the LZ4 frame format
+#[cfg(feature = "lz4")]
+const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+#[cfg(feature = "lz4")]
+fn decompress_lz4(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+    if input_buf.starts_with(&LZ4_FRAME_MAGIC) {
+        let decoder = lz4::Decoder::new(input_buf)?;
+        return decompress_exact(decoder, output_buf, Compression::Lz4);
+    }
+    let is_hadoop = try_decompress_hadoop(input_buf, output_buf, |input, output| {
+        let size = output.len();
+        matches!(
+            lz4::block::decompress_to_buffer(input, Some(size as i32), output),
+            Ok(n) if n == size
+        )
+    });
+    if is_hadoop {
+        return Ok(());
+    }
+    decompress_lz4_raw(input_buf, output_buf, Compression::Lz4)
+}
+
+/// Decompresses Hadoop-framed blocks with `decompress_block`, returning whether `input_buf` was
+/// Hadoop-framed and decompressed exactly into `output_buf`.
+/// `decompress_block` must return whether the block decompressed exactly into its output.
+#[cfg(any(feature = "lz4", feature = "lzo"))]
+fn try_decompress_hadoop<F: Fn(&[u8], &mut [u8]) -> bool>(
+    mut input_buf: &[u8],
+    output_buf: &mut [u8],
+    decompress_block: F,
+) -> bool {
+    use std::convert::TryInto;
+
+    // SPEC: each block is prefixed by its decompressed and compressed sizes (big-endian u32)
+    let mut offset = 0;
+    while input_buf.len() >= 8 {
+        let decompressed_size = u32::from_be_bytes(input_buf[..4].try_into().unwrap()) as usize;
+        let compressed_size = u32::from_be_bytes(input_buf[4..8].try_into().unwrap()) as usize;
+        input_buf = &input_buf[8..];
+        if compressed_size > input_buf.len() || decompressed_size > output_buf.len() - offset {
+            return false;
+        }
+        let output = &mut output_buf[offset..offset + decompressed_size];
+        if !decompress_block(&input_buf[..compressed_size], output) {
+            return false;
+        }
+        offset += decompressed_size;
+        input_buf = &input_buf[compressed_size..];
+    }
+    input_buf.is_empty() && offset == output_buf.len()
+}
+
+#[cfg(feature = "lz4")]
+fn decompress_lz4_raw(
+    input_buf: &[u8],
+    output_buf: &mut [u8],
+    compression: Compression,
+) -> Result<()> {
+    let size = output_buf.len();
+    let n = lz4::block::decompress_to_buffer(input_buf, Some(size as i32), output_buf).map_err(
+        |e| {
+            ParquetError::OutOfSpec(format!(
+                "A page compressed with {:?} is invalid or larger than its header declares: {}",
+                compression, e
+            ))
+        },
+    )?;
+    if n != size {
+        return Err(size_mismatch(compression, n, size));
+    }
+    Ok(())
+}
+
+/// A decoder of an LZO1X block into a preallocated output, which never writes past its end.
+/// Its methods return `None` when the block is invalid or does not fit the output.
+#[cfg(feature = "lzo")]
+struct Lzo1xDecoder<'a> {
+    input: &'a [u8],
+    output: &'a mut [u8],
+    // the position in `input` of the next byte to read
+    input_offset: usize,
+    // the position in `output` of the next byte to write
+    output_offset: usize,
+}
+
+#[cfg(feature = "lzo")]
+impl<'a> Lzo1xDecoder<'a> {
+    fn byte(&mut self) -> Option<usize> {
+        let byte = *self.input.get(self.input_offset)?;
+        self.input_offset += 1;
+        Some(byte as usize)
+    }
+
+    fn le16(&mut self) -> Option<usize> {
+        Some(self.byte()? | (self.byte()? << 8))
+    }
+
+    /// Reads a length encoded as a run of zero bytes, each adding 255, and a non-zero byte.
+    fn length(&mut self, base: usize) -> Option<usize> {
+        let mut length = base;
+        loop {
+            match self.byte()? {
+                0 => length = length.checked_add(255)?,
+                byte => return length.checked_add(byte),
+            }
+        }
+    }
+
+    /// Copies `length` literal bytes of the input to the output.
+    fn literals(&mut self, length: usize) -> Option<()> {
+        let input_end = self.input_offset.checked_add(length)?;
+        let output_end = self.output_offset.checked_add(length)?;
+        self.output
+            .get_mut(self.output_offset..output_end)?
+            .copy_from_slice(self.input.get(self.input_offset..input_end)?);
+        self.input_offset = input_end;
+        self.output_offset = output_end;
+        Some(())
+    }
+
+    /// Copies `length` bytes of the output, starting `distance` bytes before its end. The
+    /// copied bytes may overlap the bytes being written.
+    fn copy(&mut self, distance: usize, length: usize) -> Option<()> {
+        let start = self.output_offset.checked_sub(distance)?;
+        let output_end = self.output_offset.checked_add(length)?;
+        if output_end > self.output.len() {
+            return None;
+        }
+        for i in 0..length {
+            self.output[self.output_offset + i] = self.output[start + i];
+        }
+        self.output_offset = output_end;
+        Some(())
+    }
+
+    /// Decodes the whole input, returning the number of bytes written to the output.
+    fn decode(mut self) -> Option<usize> {
+        // the number of literals copied by the last instruction (4 meaning 4 or more)
+        let mut state = 0;
+
+        let first = *self.input.first()? as usize;
+        if first >= 22 {
+            self.input_offset += 1;
+            self.literals(first - 17)?;
+            state = 4;
+        } else if first >= 18 {
+            self.input_offset += 1;
+            state = first - 17;
+            self.literals(state)?;
+        }
+
+        loop {
+            let instruction = self.byte()?;
+            let (distance, length, next_state) = if instruction >= 64 {
+                // M2: a copy of 3-8 bytes within 2kB
+                let distance = (self.byte()? << 3) + ((instruction >> 2) & 7) + 1;
+                (distance, (instruction >> 5) + 1, instruction & 3)
+            } else if instruction >= 32 {
+                // M3: a copy within 16kB
+                let length = match instruction & 31 {
+                    0 => self.length(31)?,
+                    length => length,
+                } + 2;
+                let distance = self.le16()?;
+                ((distance >> 2) + 1, length, distance & 3)
+            } else if instruction >= 16 {
+                // M4: a copy within 16-48kB, or the end of the block
+                let length = match instruction & 7 {
+                    0 => self.length(7)?,
+                    length => length,
+                } + 2;
+                let distance = self.le16()?;
+                let next_state = distance & 3;
+                let distance = ((instruction & 8) << 11) + (distance >> 2);
+                if distance == 0 {
+                    // the end of the block must be the last instruction
+                    return if length == 3 && self.input_offset == self.input.len() {
+                        Some(self.output_offset)
+                    } else {
+                        None
+                    };
+                }
+                (distance + 16384, length, next_state)
+            } else if state == 0 {
+                // M1: a run of 4 or more literals
+                let length = match instruction {
+                    0 => self.length(15)?,
+                    length => length,
+                } + 3;
+                self.literals(length)?;
+                state = 4;
+                continue;
+            } else if state < 4 {
+                // M1: a copy of 2 bytes within 1kB
+                let distance = (instruction >> 2) + (self.byte()? << 2) + 1;
+                (distance, 2, instruction & 3)
+            } else {
+                // M1: a copy of 3 bytes within 2-3kB
+                let distance = (instruction >> 2) + (self.byte()? << 2) + 2049;
+                (distance, 3, instruction & 3)
+            };
+            self.copy(distance, length)?;
+            self.literals(next_state)?;
+            state = next_state;
+        }
+    }
+}
+
+/// Decompresses the LZO1X block `input_buf` into `output_buf`, returning the number of bytes
+/// written, or `None` if the block is invalid or larger than `output_buf`.
+#[cfg(feature = "lzo")]
+fn decompress_lzo1x(input_buf: &[u8], output_buf: &mut [u8]) -> Option<usize> {
+    Lzo1xDecoder {
+        input: input_buf,
+        output: output_buf,
+        input_offset: 0,
+        output_offset: 0,
+    }
+    .decode()
+}
+
+#[cfg(feature = "lzo")]
+fn decompress_lzo(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+    let is_hadoop = try_decompress_hadoop(input_buf, output_buf, |input, output| {
+        decompress_lzo1x(input, output) == Some(output.len())
+    });
+    if is_hadoop {
+        return Ok(());
+    }
+
+    let size = decompress_lzo1x(input_buf, output_buf).ok_or_else(|| {
+        ParquetError::OutOfSpec(
+            "A page compressed with Lzo is invalid or larger than its header declares".to_string(),
+        )
+    })?;
+    if size != output_buf.len() {
+        return Err(size_mismatch(Compression::Lzo, size, output_buf.len()));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn test_roundtrip(c: Compression, data: &[u8]) {
+    fn test_roundtrip(c: CompressionOptions, data: &[u8]) {
         let offset = 2;
 
         // Compress to a buffer that already has data is possible
@@ -201,11 +626,12 @@ mod tests {
         assert!(compressed.len() - 2 < data.len());
 
         let mut decompressed = vec![0; data.len()];
-        decompress(c, &compressed[offset..], &mut decompressed).expect("Error when decompressing");
+        decompress(c.into(), &compressed[offset..], &mut decompressed)
+            .expect("Error when decompressing");
         assert_eq!(data, decompressed.as_slice());
     }
 
-    fn test_codec(c: Compression) {
+    fn test_codec(c: CompressionOptions) {
         let sizes = vec![10000, 100000];
         for size in sizes {
             let data = (0..size).map(|x| (x % 255) as u8).collect::<Vec<_>>();
@@ -215,26 +641,279 @@ mod tests {
 
     #[test]
     fn test_codec_snappy() {
-        test_codec(Compression::Snappy);
+        test_codec(CompressionOptions::Snappy);
     }
 
     #[test]
     fn test_codec_gzip() {
-        test_codec(Compression::Gzip);
+        test_codec(CompressionOptions::Gzip(None));
+    }
+
+    #[test]
+    fn test_codec_gzip_levels() {
+        for level in [1, 6, 9] {
+            test_codec(CompressionOptions::Gzip(Some(
+                GzipLevel::try_new(level).unwrap(),
+            )));
+        }
     }
 
     #[test]
     fn test_codec_brotli() {
-        test_codec(Compression::Brotli);
+        test_codec(CompressionOptions::Brotli(None));
+    }
+
+    #[test]
+    fn test_codec_brotli_levels() {
+        for level in [0, 5, 11] {
+            test_codec(CompressionOptions::Brotli(Some(
+                BrotliLevel::try_new(level).unwrap(),
+            )));
+        }
     }
 
     #[test]
     fn test_codec_lz4() {
-        test_codec(Compression::Lz4);
+        test_codec(CompressionOptions::Lz4);
+    }
+
+    #[test]
+    fn test_codec_lz4_raw() {
+        test_codec(CompressionOptions::Lz4Raw);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn lz4_framings() {
+        use std::io::Write;
+
+        let data = (0..10000).map(|x| (x % 255) as u8).collect::<Vec<_>>();
+        let raw = lz4::block::compress(&data, None, false).unwrap();
+
+        let mut frame = lz4::EncoderBuilder::new().build(vec![]).unwrap();
+        frame.write_all(&data).unwrap();
+        let frame = frame.finish().0;
+
+        // two Hadoop-framed blocks
+        let mut hadoop = vec![];
+        for chunk in data.chunks(6000) {
+            let block = lz4::block::compress(chunk, None, false).unwrap();
+            hadoop.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+            hadoop.extend_from_slice(&(block.len() as u32).to_be_bytes());
+            hadoop.extend_from_slice(&block);
+        }
+
+        for compressed in [raw, frame, hadoop] {
+            let mut decompressed = vec![0; data.len()];
+            decompress(Compression::Lz4, &compressed, &mut decompressed).unwrap();
+            assert_eq!(decompressed, data);
+        }
+
+        let mut compressed = vec![];
+        compress(CompressionOptions::Lz4, &data, &mut compressed).unwrap();
+        // written with the Hadoop framing
+        assert_eq!(compressed[..4], (data.len() as u32).to_be_bytes());
+        assert_eq!(
+            compressed[4..8],
+            ((compressed.len() - 8) as u32).to_be_bytes()
+        );
+        let mut decompressed = vec![0; data.len()];
+        decompress(Compression::Lz4, &compressed, &mut decompressed).unwrap();
+        assert_eq!(decompressed, data);
+    }
+
+    #[cfg(feature = "lzo")]
+    #[test]
+    fn lzo_framings() {
+        let data = b"parquet2 ".repeat(10);
+        // LZO1X block of `data`
+        let raw = vec![
+            26, 112, 97, 114, 113, 117, 101, 116, 50, 32, 32, 48, 32, 0, 17, 0, 0,
+        ];
+
+        let mut hadoop = vec![];
+        hadoop.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        hadoop.extend_from_slice(&(raw.len() as u32).to_be_bytes());
+        hadoop.extend_from_slice(&raw);
+
+        for compressed in [raw, hadoop] {
+            let mut decompressed = vec![0; data.len()];
+            decompress(Compression::Lzo, &compressed, &mut decompressed).unwrap();
+            assert_eq!(decompressed, data);
+
+            let mut decompressed = vec![0; data.len() + 1];
+            assert!(decompress(Compression::Lzo, &compressed, &mut decompressed).is_err());
+        }
+    }
+
+    #[cfg(feature = "lzo")]
+    #[test]
+    fn lzo_invalid() {
+        let data = b"parquet2 ".repeat(10);
+        let raw = [
+            26, 112, 97, 114, 113, 117, 101, 116, 50, 32, 32, 48, 32, 0, 17, 0, 0,
+        ];
+
+        // never decompressed past the output
+        let mut decompressed = vec![0; data.len() - 1];
+        let result = decompress(Compression::Lzo, &raw, &mut decompressed);
+        assert!(matches!(result, Err(ParquetError::OutOfSpec(_))));
+
+        let invalid: [&[u8]; 4] = [
+            // truncated
+            &raw[..raw.len() - 1],
+            // trailing bytes after the end of the block
+            &[raw.as_ref(), &[0]].concat(),
+            // a copy before the start of the output
+            &[64, 0, 17, 0, 0],
+            // no end of the block
+            &[26, 112, 97, 114, 113, 117, 101, 116, 50, 32],
+        ];
+        for compressed in invalid {
+            let mut decompressed = vec![0; data.len()];
+            let result = decompress(Compression::Lzo, compressed, &mut decompressed);
+            assert!(matches!(result, Err(ParquetError::OutOfSpec(_))));
+        }
+    }
+
+    #[cfg(not(feature = "lzo"))]
+    #[test]
+    fn lzo_not_active() {
+        let result = decompress(Compression::Lzo, &[], &mut []);
+        assert!(matches!(
+            result,
+            Err(ParquetError::FeatureNotActive(
+                crate::error::Feature::Lzo,
+                _
+            ))
+        ));
     }
 
     #[test]
     fn test_codec_zstd() {
-        test_codec(Compression::Zstd);
+        test_codec(CompressionOptions::Zstd(None));
+    }
+
+    #[test]
+    fn test_codec_zstd_levels() {
+        for level in [1, 19, 22] {
+            test_codec(CompressionOptions::Zstd(Some(
+                ZstdLevel::try_new(level).unwrap(),
+            )));
+        }
+    }
+
+    #[test]
+    fn invalid_levels() {
+        assert!(BrotliLevel::try_new(12).is_err());
+        assert!(GzipLevel::try_new(10).is_err());
+        assert!(ZstdLevel::try_new(0).is_err());
+        assert!(ZstdLevel::try_new(23).is_err());
+    }
+
+    #[test]
+    fn codec() {
+        let level = ZstdLevel::try_new(19).unwrap();
+        assert_eq!(
+            Compression::from(CompressionOptions::Zstd(Some(level))),
+            Compression::Zstd
+        );
+        assert_eq!(
+            Compression::from(CompressionOptions::Gzip(None)),
+            Compression::Gzip
+        );
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let data = (0..10000).map(|x| (x % 255) as u8).collect::<Vec<_>>();
+        let codecs = [
+            CompressionOptions::Snappy,
+            CompressionOptions::Gzip(None),
+            CompressionOptions::Brotli(None),
+            CompressionOptions::Lz4,
+            CompressionOptions::Lz4Raw,
+            CompressionOptions::Zstd(None),
+        ];
+        for codec in codecs {
+            let mut compressed = vec![];
+            compress(codec, &data, &mut compressed).unwrap();
+
+            for size in [data.len() - 1, data.len() + 1] {
+                let mut decompressed = vec![0; size];
+                let result = decompress(codec.into(), &compressed, &mut decompressed);
+                assert!(
+                    matches!(result, Err(ParquetError::OutOfSpec(_))),
+                    "{:?} {:?}",
+                    codec,
+                    result
+                );
+            }
+        }
+    }
+
+    /// A codec that reverses its input and counts its calls
+    #[derive(Debug, Default)]
+    struct ReverseCodec {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl Codec for ReverseCodec {
+        fn compress(
+            &self,
+            _: CompressionOptions,
+            input_buf: &[u8],
+            output_buf: &mut Vec<u8>,
+        ) -> Result<()> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            output_buf.extend(input_buf.iter().rev());
+            Ok(())
+        }
+
+        fn decompress(
+            &self,
+            _: Compression,
+            input_buf: &[u8],
+            output_buf: &mut [u8],
+        ) -> Result<()> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            output_buf
+                .iter_mut()
+                .zip(input_buf.iter().rev())
+                .for_each(|(x, y)| *x = *y);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registry() {
+        let codec = Arc::new(ReverseCodec::default());
+        let mut codecs = CodecRegistry::default();
+        assert!(codecs.register(Compression::Zstd, codec.clone()).is_none());
+
+        let data = b"parquet2".to_vec();
+        let mut compressed = vec![];
+        codecs
+            .compress(CompressionOptions::Zstd(None), &data, &mut compressed)
+            .unwrap();
+        assert_eq!(compressed, b"2teuqrap");
+        let mut decompressed = vec![0; data.len()];
+        codecs
+            .decompress(Compression::Zstd, &compressed, &mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, data);
+        assert_eq!(codec.calls.load(std::sync::atomic::Ordering::Relaxed), 2);
+
+        // other compressions use the builtin codecs
+        let mut compressed = vec![];
+        codecs
+            .compress(CompressionOptions::Snappy, &data, &mut compressed)
+            .unwrap();
+        let mut decompressed = vec![0; data.len()];
+        decompress(Compression::Snappy, &compressed, &mut decompressed).unwrap();
+        assert_eq!(decompressed, data);
+        assert_eq!(codec.calls.load(std::sync::atomic::Ordering::Relaxed), 2);
     }
 }
@@ -46,6 +46,44 @@ pub fn encode_pack(decompressed: [u32; BLOCK_LEN], num_bits: u8, compressed: &mu
     BitPacker1x::new().compress(&decompressed, compressed, num_bits)
 }
 
+/// Encodes `u64` values into `compressed` using `num_bits` (up to 64), least significant bits
+/// first. This is slower than [`encode`] and only needed for more than 32 bits.
+/// # Panics
+/// This function panics iff `compressed` has less than `ceil8(decompressed.len() * num_bits)` bytes.
+pub fn encode_u64(decompressed: &[u64], num_bits: usize, compressed: &mut [u8]) {
+    for (i, value) in decompressed.iter().enumerate() {
+        let mut value = *value;
+        let mut offset = i * num_bits;
+        let mut remaining = num_bits;
+        while remaining > 0 {
+            let shift = offset % 8;
+            let bits = std::cmp::min(8 - shift, remaining);
+            compressed[offset / 8] |= ((value & ((1 << bits) - 1)) as u8) << shift;
+            value = value.checked_shr(bits as u32).unwrap_or_default();
+            offset += bits;
+            remaining -= bits;
+        }
+    }
+}
+
+/// Decodes the `index`th `u64` value packed by [`encode_u64`] with `num_bits` in `compressed`.
+/// # Panics
+/// This function panics iff `compressed` is too small to contain the value.
+pub fn decode_u64(compressed: &[u8], num_bits: usize, index: usize) -> u64 {
+    let mut value = 0u64;
+    let mut offset = index * num_bits;
+    let mut decoded = 0;
+    while decoded < num_bits {
+        let shift = offset % 8;
+        let bits = std::cmp::min(8 - shift, num_bits - decoded);
+        let byte = (compressed[offset / 8] >> shift) as u64 & ((1 << bits) - 1);
+        value |= byte << decoded;
+        offset += bits;
+        decoded += bits;
+    }
+    value
+}
+
 #[derive(Debug, Clone)]
 pub struct Decoder<'a> {
     compressed_chunks: std::slice::Chunks<'a, u8>,
@@ -118,6 +156,24 @@ impl<'a> Iterator for Decoder<'a> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn u64_roundtrip() {
+        for num_bits in [1usize, 7, 33, 63, 64] {
+            let max = u64::MAX >> (64 - num_bits);
+            let data = (0..40u64)
+                .map(|x| x.wrapping_mul(0x9E37_79B9_7F4A_7C15) & max)
+                .collect::<Vec<_>>();
+
+            let mut compressed = vec![0; ceil8(data.len() * num_bits)];
+            encode_u64(&data, num_bits, &mut compressed);
+
+            let result = (0..data.len())
+                .map(|i| decode_u64(&compressed, num_bits, i))
+                .collect::<Vec<_>>();
+            assert_eq!(result, data);
+        }
+    }
+
     #[test]
     fn test_decode_rle() {
         // Test data: 0-7 with bit width 3
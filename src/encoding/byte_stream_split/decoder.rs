@@ -0,0 +1,102 @@
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+use crate::error::{ParquetError, Result};
+use crate::types::NativeType;
+
+/// Decodes [Byte Stream Split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9)-encoded
+/// values of type `T`.
+/// # Implementation
+/// This struct does not allocate on the heap.
+/// # Example
+/// ```
+/// use parquet2::encoding::byte_stream_split::Decoder;
+///
+/// let data = &[0, 0, 0, 0, 0x80, 0x40, 0x3f, 0x40];
+/// let decoder = Decoder::<f32>::try_new(data).unwrap();
+///
+/// let values = decoder.collect::<Vec<_>>();
+/// assert_eq!(values, vec![1.0, 3.0]);
+/// ```
+#[derive(Debug)]
+pub struct Decoder<'a, T: NativeType> {
+    values: &'a [u8],
+    num_values: usize,
+    index: usize,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: NativeType> Decoder<'a, T> {
+    /// Returns a new [`Decoder`] over `values`.
+    /// # Error
+    /// Errors iff the length of `values` is not a multiple of the size of `T`.
+    pub fn try_new(values: &'a [u8]) -> Result<Self> {
+        let size = std::mem::size_of::<T>();
+        let num_values = values.len() / size;
+        if num_values * size != values.len() {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The length of a byte stream split buffer ({}) must be a multiple of the size of its type ({})",
+                values.len(),
+                size
+            )));
+        }
+        Ok(Self {
+            values,
+            num_values,
+            index: 0,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a, T: NativeType> Iterator for Decoder<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.num_values {
+            return None;
+        }
+        let size = std::mem::size_of::<T>();
+
+        // the largest physical type (int96) has 12 bytes
+        let mut bytes = [0u8; 16];
+        bytes[..size]
+            .iter_mut()
+            .enumerate()
+            .for_each(|(stream, byte)| *byte = self.values[stream * self.num_values + self.index]);
+        self.index += 1;
+
+        let bytes: T::Bytes = match bytes[..size].try_into() {
+            Ok(bytes) => bytes,
+            Err(_) => unreachable!(),
+        };
+        Some(T::from_le_bytes(bytes))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_values - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+/// Decodes [Byte Stream Split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9)-encoded
+/// fixed-length values of `size` bytes each into `buffer`, as concatenated values.
+/// # Error
+/// Errors iff `size == 0` or the length of `values` is not a multiple of `size`.
+pub fn decode_fixed_len(values: &[u8], size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    let num_values = values.len().checked_div(size).unwrap_or_default();
+    if size == 0 || num_values * size != values.len() {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The length of a byte stream split buffer ({}) must be a multiple of the size of its type ({})",
+            values.len(),
+            size
+        )));
+    }
+
+    buffer.reserve(values.len());
+    for index in 0..num_values {
+        buffer.extend((0..size).map(|stream| values[stream * num_values + index]));
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::error::{ParquetError, Result};
+use crate::types::NativeType;
+
+/// Encodes `values` into `buffer` according to [Byte Stream Split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9).
+/// # Implementation
+/// The `i`th byte of every value is written to the `i`th stream, and the streams are
+/// written one after the other.
+pub fn encode<T: NativeType>(values: &[T], buffer: &mut Vec<u8>) {
+    let num_values = values.len();
+
+    let start = buffer.len();
+    buffer.resize(start + std::mem::size_of_val(values), 0);
+    let streams = &mut buffer[start..];
+
+    for (i, value) in values.iter().enumerate() {
+        for (stream, byte) in value.to_le_bytes().as_ref().iter().enumerate() {
+            streams[stream * num_values + i] = *byte;
+        }
+    }
+}
+
+/// Encodes the concatenated fixed-length `values`, of `size` bytes each, into `buffer`
+/// according to [Byte Stream Split](https://github.com/apache/parquet-format/blob/master/Encodings.md#byte-stream-split-byte_stream_split--9).
+/// # Error
+/// Errors iff `size == 0` or the length of `values` is not a multiple of `size`.
+pub fn encode_fixed_len(values: &[u8], size: usize, buffer: &mut Vec<u8>) -> Result<()> {
+    let num_values = values.len().checked_div(size).unwrap_or_default();
+    if size == 0 || num_values * size != values.len() {
+        return Err(general_err!(
+            "The length of the values to encode ({}) must be a multiple of their size ({})",
+            values.len(),
+            size
+        ));
+    }
+
+    let start = buffer.len();
+    buffer.resize(start + values.len(), 0);
+    let streams = &mut buffer[start..];
+
+    for (i, value) in values.chunks_exact(size).enumerate() {
+        for (stream, byte) in value.iter().enumerate() {
+            streams[stream * num_values + i] = *byte;
+        }
+    }
+    Ok(())
+}
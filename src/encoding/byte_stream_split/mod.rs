@@ -0,0 +1,67 @@
+mod decoder;
+mod encoder;
+
+pub use decoder::{decode_fixed_len, Decoder};
+pub use encoder::{encode, encode_fixed_len};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn f32() {
+        let data = vec![1.0f32, -2.5, 0.0, f32::MAX, f32::MIN_POSITIVE];
+
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+        assert_eq!(buffer.len(), data.len() * 4);
+
+        let result = Decoder::<f32>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn f64() {
+        let data = (0..1000).map(|x| x as f64 / 7.0).collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+
+        let result = Decoder::<f64>::try_new(&buffer)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn layout() {
+        let data = vec![0x04030201i32, 0x08070605];
+
+        let mut buffer = vec![];
+        encode(&data, &mut buffer);
+        assert_eq!(buffer, vec![1, 5, 2, 6, 3, 7, 4, 8]);
+    }
+
+    #[test]
+    fn fixed_len() {
+        let data = b"abcdefghijkl";
+
+        let mut buffer = vec![];
+        encode_fixed_len(data, 3, &mut buffer).unwrap();
+        assert_eq!(buffer, b"adgjbehkcfil");
+
+        let mut result = vec![];
+        decode_fixed_len(&buffer, 3, &mut result).unwrap();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert!(Decoder::<f64>::try_new(&[0; 12]).is_err());
+        assert!(decode_fixed_len(&[0; 5], 3, &mut vec![]).is_err());
+        assert!(encode_fixed_len(&[0; 5], 3, &mut vec![]).is_err());
+        assert!(encode_fixed_len(&[0; 5], 0, &mut vec![]).is_err());
+    }
+}
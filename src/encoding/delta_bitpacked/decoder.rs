@@ -4,6 +4,34 @@ use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
+#[derive(Debug)]
+enum MiniBlock<'a> {
+    Packed(bitpacking::Decoder<'a>),
+    // more than 32 bits per value
+    Wide {
+        values: &'a [u8],
+        num_bits: usize,
+        index: usize,
+    },
+}
+
+impl<'a> MiniBlock<'a> {
+    fn next(&mut self) -> u64 {
+        match self {
+            Self::Packed(decoder) => decoder.next().unwrap() as u64,
+            Self::Wide {
+                values,
+                num_bits,
+                index,
+            } => {
+                let value = bitpacking::decode_u64(values, *num_bits, *index);
+                *index += 1;
+                value
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 struct Block<'a> {
     // this is the minimum delta that must be added to every value.
@@ -15,7 +43,7 @@ struct Block<'a> {
     remaining: usize,     // number of elements
     current_index: usize, // invariant: < values_per_mini_block
     // None represents a relative delta of zero, in which case there is no miniblock.
-    current_miniblock: Option<bitpacking::Decoder<'a>>,
+    current_miniblock: Option<MiniBlock<'a>>,
     // number of bytes consumed.
     consumed_bytes: usize,
 }
@@ -69,7 +97,15 @@ impl<'a> Block<'a> {
             self.values = remainder;
             self.consumed_bytes += miniblock_length;
 
-            Some(bitpacking::Decoder::new(miniblock, num_bits, length))
+            Some(if num_bits <= 32 {
+                MiniBlock::Packed(bitpacking::Decoder::new(miniblock, num_bits, length))
+            } else {
+                MiniBlock::Wide {
+                    values: miniblock,
+                    num_bits: num_bits as usize,
+                    index: 0,
+                }
+            })
         } else {
             None
         };
@@ -84,12 +120,13 @@ impl<'a> Iterator for Block<'a> {
         if self.remaining == 0 {
             return None;
         }
-        let result = self.min_delta
-            + self
-                .current_miniblock
+        // SPEC: deltas wrap around
+        let result = self.min_delta.wrapping_add(
+            self.current_miniblock
                 .as_mut()
-                .map(|x| x.next().unwrap())
-                .unwrap_or(0) as i64;
+                .map(|x| x.next())
+                .unwrap_or(0) as i64,
+        );
         self.current_index += 1;
         self.remaining -= 1;
 
@@ -102,6 +139,8 @@ impl<'a> Iterator for Block<'a> {
 }
 
 /// Decoder of parquets' `DELTA_BINARY_PACKED`. Implements `Iterator<Item = i64>`.
+///
+/// Since the deltas of `INT32` columns wrap around 32 bits, their values must be cast to `i32`.
 /// # Implementation
 /// This struct does not allocate on the heap.
 #[derive(Debug)]
@@ -206,7 +245,7 @@ impl<'a> Iterator for Decoder<'a> {
             delta
         };
 
-        self.next_value += delta;
+        self.next_value = self.next_value.wrapping_add(delta);
         result
     }
 
@@ -1,74 +1,191 @@
-use crate::encoding::ceil8;
-use crate::read::levels::get_bit_width;
-
 use super::super::bitpacking;
 use super::super::uleb128;
 use super::super::zigzag_leb128;
 
-/// Encodes an iterator of `i32` according to parquet's `DELTA_BINARY_PACKED`.
-/// # Implementation
-/// * This function does not allocate on the heap.
-/// * The number of mini-blocks is always 1. This may change in the future.
-pub fn encode<I: Iterator<Item = i64>>(mut iterator: I, buffer: &mut Vec<u8>) {
-    let block_size = 128;
-    let mini_blocks = 1;
+/// The default number of values per block of [`encode`]
+pub const DEFAULT_BLOCK_SIZE: usize = 128;
+/// The default number of mini-blocks per block of [`encode`]
+pub const DEFAULT_NUM_MINI_BLOCKS: usize = 4;
 
-    let mut container = [0u8; 10];
-    let encoded_len = uleb128::encode(block_size, &mut container);
-    buffer.extend_from_slice(&container[..encoded_len]);
+/// An integer that can be encoded as `DELTA_BINARY_PACKED`, i.e. `i32` or `i64`.
+///
+/// SPEC: deltas are computed with the wrapping arithmetic of the integer's width, so that
+/// the relative deltas of a mini-block always fit in that width.
+pub trait DeltaInteger: Copy + Ord {
+    /// The delta `self - previous`, wrapping around the width of the integer.
+    fn wrapping_delta(self, previous: Self) -> Self;
 
-    let encoded_len = uleb128::encode(mini_blocks, &mut container);
-    buffer.extend_from_slice(&container[..encoded_len]);
+    /// The distance between `self` and `min`, as an unsigned integer of the integer's width.
+    fn relative_to(self, min: Self) -> u64;
 
-    let length = iterator.size_hint().1.unwrap();
-    let encoded_len = uleb128::encode(length as u64, &mut container);
-    buffer.extend_from_slice(&container[..encoded_len]);
+    /// Sign-extends `self`.
+    fn to_i64(self) -> i64;
+}
 
-    let mut values = [0i64; 128];
-    let mut deltas = [0u32; 128];
+impl DeltaInteger for i32 {
+    #[inline]
+    fn wrapping_delta(self, previous: Self) -> Self {
+        self.wrapping_sub(previous)
+    }
 
-    let first_value = iterator.next().unwrap().into();
-    let (container, encoded_len) = zigzag_leb128::encode(first_value);
-    buffer.extend_from_slice(&container[..encoded_len]);
+    #[inline]
+    fn relative_to(self, min: Self) -> u64 {
+        self.wrapping_sub(min) as u32 as u64
+    }
 
-    let mut prev = first_value;
-    let mut length = iterator.size_hint().1.unwrap();
-    while length != 0 {
-        for (i, v) in (0..128).zip(&mut iterator) {
-            let v: i64 = v.into();
-            values[i] = v - prev;
-            prev = v;
-        }
-        let consumed = std::cmp::min(length - iterator.size_hint().1.unwrap(), 128);
-        let values = &values[..consumed];
+    #[inline]
+    fn to_i64(self) -> i64 {
+        self as i64
+    }
+}
 
-        let min_delta = *values.iter().min().unwrap();
-        let max_delta = *values.iter().max().unwrap();
+impl DeltaInteger for i64 {
+    #[inline]
+    fn wrapping_delta(self, previous: Self) -> Self {
+        self.wrapping_sub(previous)
+    }
 
-        values.iter().zip(deltas.iter_mut()).for_each(|(v, d)| {
-            *d = (v - min_delta) as u32;
-        });
+    #[inline]
+    fn relative_to(self, min: Self) -> u64 {
+        self.wrapping_sub(min) as u64
+    }
 
-        // <min delta> <list of bitwidths of miniblocks> <miniblocks>
-        let (container, encoded_len) = zigzag_leb128::encode(min_delta);
-        buffer.extend_from_slice(&container[..encoded_len]);
+    #[inline]
+    fn to_i64(self) -> i64 {
+        self
+    }
+}
 
-        let num_bits = get_bit_width((max_delta - min_delta) as i16) as u8;
-        buffer.push(num_bits);
+/// Encodes an iterator of integers according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// [`DEFAULT_BLOCK_SIZE`] values and [`DEFAULT_NUM_MINI_BLOCKS`] mini-blocks.
+/// # Implementation
+/// See [`encode_with_options`].
+pub fn encode<T: DeltaInteger, I: Iterator<Item = T>>(iterator: I, buffer: &mut Vec<u8>) {
+    encode_with_options(
+        iterator,
+        DEFAULT_BLOCK_SIZE,
+        DEFAULT_NUM_MINI_BLOCKS,
+        buffer,
+    )
+}
 
-        if num_bits > 0 {
-            let start = buffer.len();
+/// Encodes an iterator of integers according to parquet's `DELTA_BINARY_PACKED`, with blocks of
+/// `block_size` values split in `num_mini_blocks` mini-blocks, each with its own bit width.
+/// # Implementation
+/// * The iterator does not need to know its length upfront: the header is written once the
+///   iterator is consumed.
+/// * This function allocates the deltas of a single block on the heap.
+/// # Panics
+/// This function panics iff `block_size` is not a multiple of 128 or the number of values per
+/// mini-block (`block_size / num_mini_blocks`) is not a multiple of 32.
+pub fn encode_with_options<T: DeltaInteger, I: Iterator<Item = T>>(
+    mut iterator: I,
+    block_size: usize,
+    num_mini_blocks: usize,
+    buffer: &mut Vec<u8>,
+) {
+    assert!(block_size > 0 && num_mini_blocks > 0);
+    assert_eq!(block_size % 128, 0);
+    let values_per_mini_block = block_size / num_mini_blocks;
+    assert_eq!(values_per_mini_block * num_mini_blocks, block_size);
+    assert_eq!(values_per_mini_block % 32, 0);
 
-            // bitpack encode all (deltas.len = 128 which is a multiple of 32)
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.resize(bytes_needed, 0);
-            bitpacking::encode(deltas.as_ref(), num_bits, &mut buffer[start..]);
+    let start = buffer.len();
 
-            let bytes_needed = start + ceil8(deltas.len() * num_bits as usize);
-            buffer.truncate(bytes_needed);
+    let mut length = 0usize;
+    let first_value = iterator.next();
+    if let Some(first_value) = first_value {
+        length += 1;
+
+        let mut deltas = Vec::with_capacity(block_size);
+        let mut relative = vec![0u64; block_size];
+        let mut prev = first_value;
+        loop {
+            deltas.clear();
+            deltas.extend((&mut iterator).take(block_size).map(|v| {
+                let delta = v.wrapping_delta(prev);
+                prev = v;
+                delta
+            }));
+            if deltas.is_empty() {
+                break;
+            }
+            length += deltas.len();
+
+            encode_block(
+                &deltas,
+                &mut relative,
+                num_mini_blocks,
+                values_per_mini_block,
+                buffer,
+            );
         }
+    }
+
+    // <block size in values> <number of miniblocks in a block> <total value count> <first value>
+    let mut header = Vec::with_capacity(40);
+    let mut container = [0u8; 10];
+    let encoded_len = uleb128::encode(block_size as u64, &mut container);
+    header.extend_from_slice(&container[..encoded_len]);
+
+    let encoded_len = uleb128::encode(num_mini_blocks as u64, &mut container);
+    header.extend_from_slice(&container[..encoded_len]);
 
-        length = iterator.size_hint().1.unwrap();
+    let encoded_len = uleb128::encode(length as u64, &mut container);
+    header.extend_from_slice(&container[..encoded_len]);
+
+    let (container, encoded_len) =
+        zigzag_leb128::encode(first_value.map(|x| x.to_i64()).unwrap_or_default());
+    header.extend_from_slice(&container[..encoded_len]);
+
+    buffer.splice(start..start, header);
+}
+
+fn encode_block<T: DeltaInteger>(
+    deltas: &[T],
+    relative: &mut [u64],
+    num_mini_blocks: usize,
+    values_per_mini_block: usize,
+    buffer: &mut Vec<u8>,
+) {
+    let min_delta = *deltas.iter().min().unwrap();
+
+    // SPEC: the padding of the last mini-block is zero
+    relative.iter_mut().for_each(|x| *x = 0);
+    deltas
+        .iter()
+        .zip(relative.iter_mut())
+        .for_each(|(delta, relative)| *relative = delta.relative_to(min_delta));
+
+    // <min delta> <list of bitwidths of miniblocks> <miniblocks>
+    let (container, encoded_len) = zigzag_leb128::encode(min_delta.to_i64());
+    buffer.extend_from_slice(&container[..encoded_len]);
+
+    // SPEC: mini-blocks without values have a bit width of zero and no body
+    let bitwidths_start = buffer.len();
+    buffer.resize(bitwidths_start + num_mini_blocks, 0);
+
+    let used_mini_blocks = deltas.chunks(values_per_mini_block);
+    for (i, (mini_block, _)) in relative
+        .chunks_exact(values_per_mini_block)
+        .zip(used_mini_blocks)
+        .enumerate()
+    {
+        let max = mini_block.iter().max().copied().unwrap_or_default();
+        let num_bits = (64 - max.leading_zeros()) as usize;
+        buffer[bitwidths_start + i] = num_bits as u8;
+
+        if num_bits == 0 {
+            continue;
+        }
+        let start = buffer.len();
+        buffer.resize(start + values_per_mini_block * num_bits / 8, 0);
+        if num_bits <= 32 {
+            let mini_block = mini_block.iter().map(|x| *x as u32).collect::<Vec<_>>();
+            bitpacking::encode(&mini_block, num_bits as u8, &mut buffer[start..]);
+        } else {
+            bitpacking::encode_u64(mini_block, num_bits, &mut buffer[start..]);
+        }
     }
 }
 
@@ -78,16 +195,16 @@ mod tests {
 
     #[test]
     fn constant_delta() {
-        // header: [128, 1, 1, 5, 2]:
+        // header: [128, 1, 4, 5, 2]:
         //  block size: 128    <=u> 128, 1
-        //  mini-blocks: 1     <=u> 1
+        //  mini-blocks: 4     <=u> 4
         //  elements: 5        <=u> 5
         //  first_value: 2     <=z> 1
         // block1: [2, 0, 0, 0, 0]
         //  min_delta: 1        <=z> 2
-        //  bitwidth: 0
+        //  bitwidths: 0, 0, 0, 0
         let data = 1..=5;
-        let expected = vec![128u8, 1, 1, 5, 2, 2, 0];
+        let expected = vec![128u8, 1, 4, 5, 2, 2, 0, 0, 0, 0];
 
         let mut buffer = vec![];
         encode(data, &mut buffer);
@@ -100,21 +217,40 @@ mod tests {
         let data = vec![1, 2, 3, 4, 5, 1];
         // header: [128, 1, 4, 6, 2]
         //  block size: 128    <=u> 128, 1
-        //  mini-blocks: 1     <=u> 1
+        //  mini-blocks: 4     <=u> 4
         //  elements: 6        <=u> 5
         //  first_value: 2     <=z> 1
-        // block1: [7, 3, 253, 255]
+        // block1: [7, 3, 0, 0, 0, 253, 255]
         //  min_delta: -4        <=z> 7
-        //  bitwidth: 3
+        //  bitwidths: 3, 0, 0, 0
         //  values: [5, 5, 5, 5, 0] <=b> [
         //      0b01101101
         //      0b00001011
         // ]
-        let mut expected = vec![128u8, 1, 1, 6, 2, 7, 3, 0b01101101, 0b00001011];
-        expected.extend(std::iter::repeat(0).take(128 * 3 / 8 - 2)); // 128 values, 3 bits, 2 already used
+        let mut expected = vec![128u8, 1, 4, 6, 2, 7, 3, 0, 0, 0, 0b01101101, 0b00001011];
+        expected.resize(expected.len() + 32 * 3 / 8 - 2, 0); // 32 values, 3 bits, 2 already used
 
         let mut buffer = vec![];
         encode(data.into_iter(), &mut buffer);
         assert_eq!(expected, buffer);
     }
+
+    #[test]
+    fn single_mini_block() {
+        // the previous encoding of this crate: a single mini-block per block
+        let data = vec![1, 2, 3, 4, 5, 1];
+        let mut expected = vec![128u8, 1, 1, 6, 2, 7, 3, 0b01101101, 0b00001011];
+        expected.resize(expected.len() + 128 * 3 / 8 - 2, 0);
+
+        let mut buffer = vec![];
+        encode_with_options(data.into_iter(), 128, 1, &mut buffer);
+        assert_eq!(expected, buffer);
+    }
+
+    #[test]
+    fn empty() {
+        let mut buffer = vec![];
+        encode(std::iter::empty::<i32>(), &mut buffer);
+        assert_eq!(buffer, vec![128u8, 1, 4, 0, 0]);
+    }
 }
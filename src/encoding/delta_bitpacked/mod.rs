@@ -2,7 +2,9 @@ mod decoder;
 mod encoder;
 
 pub use decoder::Decoder;
-pub use encoder::encode;
+pub use encoder::{
+    encode, encode_with_options, DeltaInteger, DEFAULT_BLOCK_SIZE, DEFAULT_NUM_MINI_BLOCKS,
+};
 
 #[cfg(test)]
 mod tests {
@@ -61,4 +63,61 @@ mod tests {
 
         assert_eq!(iter.consumed_bytes(), len);
     }
+
+    #[test]
+    fn mini_blocks() {
+        let data = (0..1000i64).map(|x| x * x % 97 - 40).collect::<Vec<_>>();
+
+        for (block_size, num_mini_blocks) in [(128, 1), (128, 4), (256, 8), (512, 4)] {
+            let mut buffer = vec![];
+            encode_with_options(
+                data.iter().copied(),
+                block_size,
+                num_mini_blocks,
+                &mut buffer,
+            );
+            let mut iter = Decoder::new(&buffer);
+
+            let result = iter.by_ref().collect::<Vec<_>>();
+            assert_eq!(result, data);
+            assert_eq!(iter.consumed_bytes(), buffer.len());
+        }
+    }
+
+    #[test]
+    fn i32_overflow() {
+        let data = vec![i32::MIN, i32::MAX, 0, i32::MAX, i32::MIN, -1, 1];
+
+        let mut buffer = vec![];
+        encode(data.iter().copied(), &mut buffer);
+        let iter = Decoder::new(&buffer);
+
+        let result = iter.map(|x| x as i32).collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn i64_wide_deltas() {
+        let data = vec![i64::MIN, i64::MAX, 0, 1 << 40, -(1 << 50), i64::MAX, 3];
+
+        let mut buffer = vec![];
+        encode(data.iter().copied(), &mut buffer);
+        let iter = Decoder::new(&buffer);
+
+        let result = iter.collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+
+    #[test]
+    fn without_size_hint() {
+        let data = (0..300).collect::<Vec<i64>>();
+
+        let mut buffer = vec![];
+        encode(data.iter().copied().filter(|x| x % 3 != 0), &mut buffer);
+        let iter = Decoder::new(&buffer);
+
+        let result = iter.collect::<Vec<_>>();
+        let expected = data.into_iter().filter(|x| x % 3 != 0).collect::<Vec<_>>();
+        assert_eq!(result, expected);
+    }
 }
@@ -0,0 +1,48 @@
+use crate::encoding::delta_bitpacked;
+
+/// Encodes a clonable iterator of `&[u8]` into `buffer` according to
+/// [Delta strings](https://github.com/apache/parquet-format/blob/master/Encodings.md#delta-strings-delta_byte_array--7).
+/// # Implementation
+/// Each item is split in the length of the prefix it shares with the previous item and its
+/// remaining suffix. The prefix lengths are encoded with [`delta_bitpacked::encode`] and the
+/// suffixes as [`crate::encoding::delta_length_byte_array`].
+/// This function allocates the prefix lengths on the heap.
+pub fn encode<A: AsRef<[u8]>, I: Iterator<Item = A> + Clone>(iterator: I, buffer: &mut Vec<u8>) {
+    let mut previous = vec![];
+    let prefix_lengths = iterator
+        .clone()
+        .map(|item| {
+            let item = item.as_ref();
+            let prefix_length = item
+                .iter()
+                .zip(previous.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            previous.clear();
+            previous.extend_from_slice(item);
+            prefix_length
+        })
+        .collect::<Vec<_>>();
+
+    delta_bitpacked::encode(prefix_lengths.iter().map(|x| *x as i64), buffer);
+
+    // the suffixes, encoded as delta-length byte array
+    let mut total_length = 0;
+    delta_bitpacked::encode(
+        iterator
+            .clone()
+            .zip(prefix_lengths.iter())
+            .map(|(item, prefix_length)| {
+                let length = item.as_ref().len() - prefix_length;
+                total_length += length;
+                length as i64
+            }),
+        buffer,
+    );
+    buffer.reserve(total_length);
+    iterator
+        .zip(prefix_lengths.iter())
+        .for_each(|(item, prefix_length)| {
+            buffer.extend_from_slice(&item.as_ref()[*prefix_length..])
+        });
+}
@@ -1,3 +1,59 @@
 mod decoder;
+mod encoder;
 
 pub use decoder::Decoder;
+pub use encoder::encode;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic() {
+        let data = ["Hello", "Helicopter", "Help", "World", ""];
+
+        let mut buffer = vec![];
+        encode(data.iter().map(|x| x.as_bytes()), &mut buffer);
+
+        let mut decoder = Decoder::new(&buffer);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(prefixes, vec![0, 3, 3, 0, 0]);
+
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        assert_eq!(lengths, vec![5, 7, 1, 5, 0]);
+
+        let values = decoder.into_values();
+        assert_eq!(values, b"HelloicopterpWorld".as_ref());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let data = (0..200)
+            .map(|i| format!("https://example.com/path/{}", i))
+            .collect::<Vec<_>>();
+
+        let mut buffer = vec![];
+        encode(data.iter(), &mut buffer);
+
+        let mut decoder = Decoder::new(&buffer);
+        let prefixes = decoder.by_ref().collect::<Vec<_>>();
+        let mut decoder = decoder.into_lengths();
+        let lengths = decoder.by_ref().collect::<Vec<_>>();
+        let mut suffixes = decoder.into_values();
+
+        let mut previous: Vec<u8> = vec![];
+        let result = prefixes
+            .iter()
+            .zip(lengths.iter())
+            .map(|(prefix, length)| {
+                let mut value = previous[..*prefix as usize].to_vec();
+                value.extend_from_slice(&suffixes[..*length as usize]);
+                suffixes = &suffixes[*length as usize..];
+                previous = value.clone();
+                String::from_utf8(value).unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(result, data);
+    }
+}
@@ -50,10 +50,10 @@ impl<'a> Iterator for BitmapIter<'a> {
         let value = self.current_byte & self.mask != 0;
         self.mask = self.mask.rotate_left(1);
         if self.mask == 1 {
-            // reached a new byte => try to fetch it from the iterator
-            match self.iter.next() {
-                Some(v) => self.current_byte = v,
-                None => return None,
+            // reached a new byte => fetch it from the iterator, if any (the last value may
+            // end the last byte)
+            if let Some(v) = self.iter.next() {
+                self.current_byte = v
             }
         }
         Some(value)
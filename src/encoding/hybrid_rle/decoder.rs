@@ -40,7 +40,7 @@ impl<'a> Iterator for Decoder<'a> {
             // is rle
             let run_length = indicator as usize >> 1;
             // repeated-value := value that is repeated, using a fixed-width of round-up-to-next-byte(bit-width)
-            let rle_bytes = ceil8(self.num_bits as usize);
+            let rle_bytes = std::cmp::min(ceil8(self.num_bits as usize), self.values.len());
             let result = Some(HybridEncoded::Rle(&self.values[..rle_bytes], run_length));
             self.values = &self.values[rle_bytes..];
             result
@@ -0,0 +1,394 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use crate::error::{ParquetError, Result};
+
+use super::EncryptionAlgorithm;
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const LENGTH_LEN: usize = 4;
+
+/// The type of a module, part of its additional authenticated data (AAD).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ModuleType {
+    Footer = 0,
+    ColumnMetaData = 1,
+    DataPage = 2,
+    DictionaryPage = 3,
+    DataPageHeader = 4,
+    DictionaryPageHeader = 5,
+    ColumnIndex = 6,
+    OffsetIndex = 7,
+}
+
+/// The position of a module in the file, used to build its AAD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Module {
+    pub type_: ModuleType,
+    pub row_group: i16,
+    pub column: i16,
+    pub page: i16,
+}
+
+impl Module {
+    pub fn footer() -> Self {
+        Self {
+            type_: ModuleType::Footer,
+            row_group: 0,
+            column: 0,
+            page: 0,
+        }
+    }
+
+    fn aad(&self, file_aad: &[u8]) -> Vec<u8> {
+        let mut aad = file_aad.to_vec();
+        aad.push(self.type_ as u8);
+        // SPEC: the footer AAD has no ordinals, and only (data) pages and their headers
+        // have a page ordinal.
+        if self.type_ != ModuleType::Footer {
+            aad.extend_from_slice(&self.row_group.to_le_bytes());
+            aad.extend_from_slice(&self.column.to_le_bytes());
+        }
+        if matches!(
+            self.type_,
+            ModuleType::DataPage | ModuleType::DataPageHeader
+        ) {
+            aad.extend_from_slice(&self.page.to_le_bytes());
+        }
+        aad
+    }
+}
+
+/// Encrypts and decrypts modules of a file with a single key.
+#[derive(Clone)]
+pub(crate) struct ModuleCipher {
+    algorithm: EncryptionAlgorithm,
+    key: Arc<Vec<u8>>,
+    file_aad: Arc<Vec<u8>>,
+}
+
+impl std::fmt::Debug for ModuleCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModuleCipher")
+            .field("algorithm", &self.algorithm)
+            .finish()
+    }
+}
+
+impl ModuleCipher {
+    pub fn try_new(
+        algorithm: EncryptionAlgorithm,
+        key: Vec<u8>,
+        file_aad: Arc<Vec<u8>>,
+    ) -> Result<Self> {
+        if !matches!(key.len(), 16 | 24 | 32) {
+            return Err(general_err!(
+                "Encryption keys must have 16, 24 or 32 bytes, but one has {}",
+                key.len()
+            ));
+        }
+        Ok(Self {
+            algorithm,
+            key: Arc::new(key),
+            file_aad,
+        })
+    }
+
+    fn is_ctr(&self, type_: ModuleType) -> bool {
+        self.algorithm == EncryptionAlgorithm::AesGcmCtrV1
+            && matches!(type_, ModuleType::DataPage | ModuleType::DictionaryPage)
+    }
+
+    /// Encrypts `plaintext` into a module: its length, a nonce, the ciphertext and (for GCM) a tag.
+    pub fn encrypt(&self, module: Module, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = aes::random_nonce()?;
+        let ciphertext = if self.is_ctr(module.type_) {
+            let mut data = plaintext.to_vec();
+            aes::ctr_apply(&self.key, &nonce, &mut data)?;
+            data
+        } else {
+            aes::gcm_encrypt(&self.key, &nonce, plaintext, &module.aad(&self.file_aad))?
+        };
+
+        let length = (NONCE_LEN + ciphertext.len()) as u32;
+        let mut result = Vec::with_capacity(LENGTH_LEN + length as usize);
+        result.extend_from_slice(&length.to_le_bytes());
+        result.extend_from_slice(&nonce);
+        result.extend_from_slice(&ciphertext);
+        Ok(result)
+    }
+
+    /// Decrypts a module (including its length) into its plaintext.
+    pub fn decrypt(&self, module: Module, data: &[u8]) -> Result<Vec<u8>> {
+        let min_len = LENGTH_LEN
+            + NONCE_LEN
+            + if self.is_ctr(module.type_) {
+                0
+            } else {
+                TAG_LEN
+            };
+        if data.len() < min_len {
+            return Err(ParquetError::OutOfSpec(
+                "An encrypted module is smaller than its nonce and tag".to_string(),
+            ));
+        }
+        let length = u32::from_le_bytes(data[..LENGTH_LEN].try_into().unwrap()) as usize;
+        if length != data.len() - LENGTH_LEN {
+            return Err(ParquetError::OutOfSpec(
+                "The length of an encrypted module does not match its declared length".to_string(),
+            ));
+        }
+        let nonce = &data[LENGTH_LEN..LENGTH_LEN + NONCE_LEN];
+        let ciphertext = &data[LENGTH_LEN + NONCE_LEN..];
+
+        if self.is_ctr(module.type_) {
+            let mut data = ciphertext.to_vec();
+            aes::ctr_apply(&self.key, nonce, &mut data)?;
+            Ok(data)
+        } else {
+            aes::gcm_decrypt(&self.key, nonce, ciphertext, &module.aad(&self.file_aad))
+        }
+    }
+
+    /// Returns the signature (nonce and tag) of a plaintext footer.
+    pub fn sign(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = aes::random_nonce()?;
+        self.sign_with_nonce(plaintext, &nonce)
+    }
+
+    fn sign_with_nonce(&self, plaintext: &[u8], nonce: &[u8]) -> Result<Vec<u8>> {
+        let aad = Module::footer().aad(&self.file_aad);
+        let ciphertext = aes::gcm_encrypt(&self.key, nonce, plaintext, &aad)?;
+        let mut signature = nonce.to_vec();
+        signature.extend_from_slice(&ciphertext[ciphertext.len() - TAG_LEN..]);
+        Ok(signature)
+    }
+
+    /// Verifies the `signature` (nonce and tag) of a plaintext footer.
+    pub fn verify(&self, plaintext: &[u8], signature: &[u8]) -> Result<()> {
+        if signature.len() != SIGNATURE_LEN {
+            return Err(ParquetError::OutOfSpec(
+                "The signature of a plaintext footer must have 28 bytes".to_string(),
+            ));
+        }
+        if self.sign_with_nonce(plaintext, &signature[..NONCE_LEN])? != signature {
+            return Err(general_err!(
+                "The signature of the plaintext footer is invalid"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The length of the signature appended to plaintext footers
+const SIGNATURE_LEN: usize = NONCE_LEN + TAG_LEN;
+
+/// A [`ModuleCipher`] of a column chunk, together with its position in the file.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnCipher {
+    pub cipher: ModuleCipher,
+    pub row_group: i16,
+    pub column: i16,
+}
+
+impl ColumnCipher {
+    pub fn module(&self, type_: ModuleType, page: i16) -> Module {
+        Module {
+            type_,
+            row_group: self.row_group,
+            column: self.column,
+            page,
+        }
+    }
+}
+
+/// Returns the ordinal of a row group or column, which the spec limits to 2 bytes.
+pub(crate) fn to_ordinal(index: usize) -> Result<i16> {
+    index.try_into().map_err(|_| {
+        general_err!("Encrypted files can have at most 32767 row groups, columns and pages")
+    })
+}
+
+#[cfg(feature = "encryption")]
+mod aes {
+    use aes_gcm::aead::consts::U12;
+    use aes_gcm::aead::{Aead, KeyInit, Nonce, Payload};
+    use aes_gcm::aes::{Aes128, Aes192, Aes256};
+    use aes_gcm::AesGcm;
+    use ctr::cipher::{KeyIvInit, StreamCipher};
+
+    use crate::error::{ParquetError, Result};
+
+    use super::NONCE_LEN;
+
+    fn gcm_error(_: aes_gcm::Error) -> ParquetError {
+        general_err!("Failed to encrypt or authenticate a module (wrong key or corrupted data)")
+    }
+
+    fn gcm<C: KeyInit + Aead>(
+        key: &[u8],
+        nonce: &[u8],
+        data: &[u8],
+        aad: &[u8],
+        encrypt: bool,
+    ) -> Result<Vec<u8>> {
+        let cipher = C::new_from_slice(key).map_err(|_| general_err!("Invalid key length"))?;
+        let nonce = Nonce::<C>::from_slice(nonce);
+        let payload = Payload { msg: data, aad };
+        if encrypt {
+            cipher.encrypt(nonce, payload).map_err(gcm_error)
+        } else {
+            cipher.decrypt(nonce, payload).map_err(gcm_error)
+        }
+    }
+
+    fn gcm_dyn(
+        key: &[u8],
+        nonce: &[u8],
+        data: &[u8],
+        aad: &[u8],
+        encrypt: bool,
+    ) -> Result<Vec<u8>> {
+        match key.len() {
+            16 => gcm::<AesGcm<Aes128, U12>>(key, nonce, data, aad, encrypt),
+            24 => gcm::<AesGcm<Aes192, U12>>(key, nonce, data, aad, encrypt),
+            _ => gcm::<AesGcm<Aes256, U12>>(key, nonce, data, aad, encrypt),
+        }
+    }
+
+    pub fn gcm_encrypt(key: &[u8], nonce: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        gcm_dyn(key, nonce, data, aad, true)
+    }
+
+    pub fn gcm_decrypt(key: &[u8], nonce: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        gcm_dyn(key, nonce, data, aad, false)
+    }
+
+    fn ctr<C: KeyIvInit + StreamCipher>(key: &[u8], iv: &[u8], data: &mut [u8]) -> Result<()> {
+        let mut cipher =
+            C::new_from_slices(key, iv).map_err(|_| general_err!("Invalid key length"))?;
+        cipher.apply_keystream(data);
+        Ok(())
+    }
+
+    pub fn ctr_apply(key: &[u8], nonce: &[u8], data: &mut [u8]) -> Result<()> {
+        // SPEC: the IV is the nonce followed by a 4-byte big endian counter starting at 1
+        let mut iv = [0u8; 16];
+        iv[..NONCE_LEN].copy_from_slice(nonce);
+        iv[15] = 1;
+        match key.len() {
+            16 => ctr::<ctr::Ctr32BE<Aes128>>(key, &iv, data),
+            24 => ctr::<ctr::Ctr32BE<Aes192>>(key, &iv, data),
+            _ => ctr::<ctr::Ctr32BE<Aes256>>(key, &iv, data),
+        }
+    }
+
+    pub fn random_nonce() -> Result<[u8; NONCE_LEN]> {
+        let mut nonce = [0u8; NONCE_LEN];
+        random(&mut nonce)?;
+        Ok(nonce)
+    }
+
+    pub fn random(data: &mut [u8]) -> Result<()> {
+        getrandom::getrandom(data)
+            .map_err(|e| general_err!("Failed to generate random bytes: {}", e))
+    }
+}
+
+#[cfg(not(feature = "encryption"))]
+mod aes {
+    use crate::error::{Feature, ParquetError, Result};
+
+    use super::NONCE_LEN;
+
+    fn not_active<T>() -> Result<T> {
+        Err(ParquetError::FeatureNotActive(
+            Feature::Encryption,
+            "encrypt or decrypt modules".to_string(),
+        ))
+    }
+
+    pub fn gcm_encrypt(_: &[u8], _: &[u8], _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+        not_active()
+    }
+
+    pub fn gcm_decrypt(_: &[u8], _: &[u8], _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+        not_active()
+    }
+
+    pub fn ctr_apply(_: &[u8], _: &[u8], _: &mut [u8]) -> Result<()> {
+        not_active()
+    }
+
+    pub fn random_nonce() -> Result<[u8; NONCE_LEN]> {
+        not_active()
+    }
+
+    pub fn random(_: &mut [u8]) -> Result<()> {
+        not_active()
+    }
+}
+
+pub(crate) use aes::random;
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::*;
+
+    fn cipher(algorithm: EncryptionAlgorithm) -> ModuleCipher {
+        ModuleCipher::try_new(algorithm, vec![1; 16], Arc::new(b"file".to_vec())).unwrap()
+    }
+
+    fn module(type_: ModuleType, page: i16) -> Module {
+        Module {
+            type_,
+            row_group: 1,
+            column: 2,
+            page,
+        }
+    }
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        for algorithm in [
+            EncryptionAlgorithm::AesGcmV1,
+            EncryptionAlgorithm::AesGcmCtrV1,
+        ] {
+            let cipher = cipher(algorithm);
+            for type_ in [ModuleType::DataPage, ModuleType::DataPageHeader] {
+                let data = cipher.encrypt(module(type_, 3), b"some data")?;
+                assert_eq!(cipher.decrypt(module(type_, 3), &data)?, b"some data");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn authenticates_position() -> Result<()> {
+        let cipher = cipher(EncryptionAlgorithm::AesGcmV1);
+        let data = cipher.encrypt(module(ModuleType::DataPage, 3), b"some data")?;
+        // a page moved to another position is rejected
+        assert!(cipher
+            .decrypt(module(ModuleType::DataPage, 4), &data)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sign() -> Result<()> {
+        let cipher = cipher(EncryptionAlgorithm::AesGcmV1);
+        let signature = cipher.sign(b"footer")?;
+        cipher.verify(b"footer", &signature)?;
+        assert!(cipher.verify(b"fooler", &signature).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(
+            ModuleCipher::try_new(EncryptionAlgorithm::AesGcmV1, vec![1; 10], Arc::default())
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+use parquet_format_async_temp::{
+    ColumnCryptoMetaData, ColumnMetaData, EncryptionAlgorithm as TEncryptionAlgorithm, RowGroup,
+};
+
+use crate::error::{ParquetError, Result};
+
+use super::cipher::{to_ordinal, ColumnCipher, ModuleCipher, ModuleType};
+use super::{EncryptionAlgorithm, FileDecryptionProperties};
+
+/// Decrypts the modules of a file according to [`FileDecryptionProperties`].
+pub(crate) struct FileDecryptor<'a> {
+    properties: &'a FileDecryptionProperties,
+    algorithm: EncryptionAlgorithm,
+    file_aad: Arc<Vec<u8>>,
+}
+
+impl<'a> FileDecryptor<'a> {
+    pub fn try_new(
+        properties: &'a FileDecryptionProperties,
+        algorithm: &TEncryptionAlgorithm,
+    ) -> Result<Self> {
+        let (algorithm, aad_prefix, aad_file_unique, supply_aad_prefix) = match algorithm {
+            TEncryptionAlgorithm::AESGCMV1(a) => (
+                EncryptionAlgorithm::AesGcmV1,
+                &a.aad_prefix,
+                &a.aad_file_unique,
+                a.supply_aad_prefix,
+            ),
+            TEncryptionAlgorithm::AESGCMCTRV1(a) => (
+                EncryptionAlgorithm::AesGcmCtrV1,
+                &a.aad_prefix,
+                &a.aad_file_unique,
+                a.supply_aad_prefix,
+            ),
+        };
+
+        let aad_prefix = match (&properties.aad_prefix, aad_prefix) {
+            (Some(prefix), _) => prefix.clone(),
+            (None, Some(prefix)) => prefix.clone(),
+            (None, None) if supply_aad_prefix.unwrap_or(false) => {
+                return Err(general_err!(
+                    "The file requires the AAD prefix to be supplied to decrypt it"
+                ))
+            }
+            (None, None) => vec![],
+        };
+        let mut file_aad = aad_prefix;
+        file_aad.extend_from_slice(aad_file_unique.as_deref().unwrap_or_default());
+
+        Ok(Self {
+            properties,
+            algorithm,
+            file_aad: Arc::new(file_aad),
+        })
+    }
+
+    /// Returns the [`ModuleCipher`] of the footer
+    /// # Error
+    /// Errors if the key of the footer is not available
+    pub fn footer(&self, key_metadata: Option<&[u8]>) -> Result<ModuleCipher> {
+        self.try_footer(key_metadata)?
+            .ok_or_else(|| general_err!("The key of the footer is not available"))
+    }
+
+    /// Returns the [`ModuleCipher`] of the footer, or `None` if its key is not available
+    fn try_footer(&self, key_metadata: Option<&[u8]>) -> Result<Option<ModuleCipher>> {
+        self.properties
+            .footer_key(key_metadata)?
+            .map(|key| ModuleCipher::try_new(self.algorithm, key, self.file_aad.clone()))
+            .transpose()
+    }
+
+    /// Decrypts the metadata of the encrypted column chunks of `row_groups` in place and returns
+    /// the [`ColumnCipher`] of every column chunk (`None` when not encrypted, or when its key,
+    /// of its column or of the footer, is not available, in which case the chunk is left
+    /// encrypted). Errors retrieving keys are returned.
+    pub fn decrypt_row_groups(
+        &self,
+        row_groups: &mut [RowGroup],
+        footer_key_metadata: Option<&[u8]>,
+    ) -> Result<Vec<Vec<Option<ColumnCipher>>>> {
+        row_groups
+            .iter_mut()
+            .enumerate()
+            .map(|(row_group_ordinal, row_group)| {
+                let row_group_ordinal = match row_group.ordinal {
+                    Some(ordinal) => ordinal,
+                    None => to_ordinal(row_group_ordinal)?,
+                };
+                row_group
+                    .columns
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(column_ordinal, column)| {
+                        let cipher = match &column.crypto_metadata {
+                            None => return Ok(None),
+                            // readers may not have the keys of every column, nor of the footer
+                            // when it is plaintext
+                            Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => {
+                                match self.try_footer(footer_key_metadata)? {
+                                    Some(cipher) => cipher,
+                                    None => return Ok(None),
+                                }
+                            }
+                            Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(c)) => {
+                                let key = match self
+                                    .properties
+                                    .column_key(&c.path_in_schema, c.key_metadata.as_deref())?
+                                {
+                                    Some(key) => key,
+                                    None => return Ok(None),
+                                };
+                                ModuleCipher::try_new(self.algorithm, key, self.file_aad.clone())?
+                            }
+                        };
+                        let cipher = ColumnCipher {
+                            cipher,
+                            row_group: row_group_ordinal,
+                            column: to_ordinal(column_ordinal)?,
+                        };
+
+                        if let Some(encrypted) = &column.encrypted_column_metadata {
+                            let module = cipher.module(ModuleType::ColumnMetaData, 0);
+                            let data = cipher.cipher.decrypt(module, encrypted)?;
+                            let mut prot = TCompactInputProtocol::new(Cursor::new(data));
+                            column.meta_data =
+                                Some(ColumnMetaData::read_from_in_protocol(&mut prot)?);
+                        }
+                        Ok(Some(cipher))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
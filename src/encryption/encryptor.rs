@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_async_temp::{
+    AesGcmCtrV1, AesGcmV1, ColumnCryptoMetaData, EncryptionAlgorithm as TEncryptionAlgorithm,
+    EncryptionWithColumnKey, EncryptionWithFooterKey, FileCryptoMetaData, RowGroup,
+};
+
+use crate::error::Result;
+
+use super::cipher::{random, to_ordinal, ColumnCipher, ModuleCipher, ModuleType};
+use super::{EncryptionAlgorithm, FileEncryptionProperties};
+
+/// A [`ColumnCipher`] used to write a column chunk, together with how it was encrypted.
+pub(crate) struct ColumnEncryptor {
+    pub cipher: ColumnCipher,
+    pub crypto_metadata: ColumnCryptoMetaData,
+}
+
+/// A [`FileEncryptor`] of a row group.
+#[derive(Clone, Copy)]
+pub(crate) struct RowGroupEncryptor<'a> {
+    file: &'a FileEncryptor,
+    pub ordinal: usize,
+}
+
+impl<'a> RowGroupEncryptor<'a> {
+    /// Returns the [`ColumnEncryptor`] of the column at `path`, or `None` if it is not encrypted.
+    pub fn column(&self, path: &[String], column: usize) -> Result<Option<ColumnEncryptor>> {
+        self.file.column(path, self.ordinal, column)
+    }
+}
+
+/// Encrypts the modules of a file according to [`FileEncryptionProperties`].
+#[derive(Debug)]
+pub(crate) struct FileEncryptor {
+    properties: FileEncryptionProperties,
+    aad_file_unique: Vec<u8>,
+    file_aad: Arc<Vec<u8>>,
+    footer: ModuleCipher,
+}
+
+impl FileEncryptor {
+    pub fn try_new(properties: FileEncryptionProperties) -> Result<Self> {
+        let mut aad_file_unique = vec![0; 8];
+        random(&mut aad_file_unique)?;
+
+        let mut file_aad = properties.aad_prefix.clone().unwrap_or_default();
+        file_aad.extend_from_slice(&aad_file_unique);
+        let file_aad = Arc::new(file_aad);
+
+        let footer = ModuleCipher::try_new(
+            properties.algorithm,
+            properties.footer_key.clone(),
+            file_aad.clone(),
+        )?;
+        Ok(Self {
+            properties,
+            aad_file_unique,
+            file_aad,
+            footer,
+        })
+    }
+
+    pub fn plaintext_footer(&self) -> bool {
+        self.properties.plaintext_footer
+    }
+
+    pub fn footer(&self) -> &ModuleCipher {
+        &self.footer
+    }
+
+    pub fn footer_key_metadata(&self) -> Option<Vec<u8>> {
+        self.properties.footer_key_metadata.clone()
+    }
+
+    /// The algorithm, as declared in the file
+    pub fn algorithm(&self) -> TEncryptionAlgorithm {
+        let (aad_prefix, supply_aad_prefix) = match &self.properties.aad_prefix {
+            Some(prefix) if self.properties.store_aad_prefix => (Some(prefix.clone()), None),
+            Some(_) => (None, Some(true)),
+            None => (None, None),
+        };
+        let aad_file_unique = Some(self.aad_file_unique.clone());
+        match self.properties.algorithm {
+            EncryptionAlgorithm::AesGcmV1 => TEncryptionAlgorithm::AESGCMV1(AesGcmV1 {
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            }),
+            EncryptionAlgorithm::AesGcmCtrV1 => TEncryptionAlgorithm::AESGCMCTRV1(AesGcmCtrV1 {
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            }),
+        }
+    }
+
+    pub fn crypto_metadata(&self) -> FileCryptoMetaData {
+        FileCryptoMetaData {
+            encryption_algorithm: self.algorithm(),
+            key_metadata: self.footer_key_metadata(),
+        }
+    }
+
+    /// Returns the [`ColumnEncryptor`] of the column at `path`, or `None` if it is not encrypted.
+    pub fn column(
+        &self,
+        path: &[String],
+        row_group: usize,
+        column: usize,
+    ) -> Result<Option<ColumnEncryptor>> {
+        let (key, crypto_metadata) = match &self.properties.columns {
+            None => (
+                None,
+                ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(EncryptionWithFooterKey {}),
+            ),
+            Some(columns) => {
+                let properties = columns.iter().find(|column| column.path == path);
+                match properties {
+                    None => return Ok(None),
+                    Some(properties) => match &properties.key {
+                        None => (
+                            None,
+                            ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(
+                                EncryptionWithFooterKey {},
+                            ),
+                        ),
+                        Some(key) => (
+                            Some(key.clone()),
+                            ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(
+                                EncryptionWithColumnKey {
+                                    path_in_schema: path.to_vec(),
+                                    key_metadata: properties.key_metadata.clone(),
+                                },
+                            ),
+                        ),
+                    },
+                }
+            }
+        };
+        let cipher = match key {
+            Some(key) => {
+                ModuleCipher::try_new(self.properties.algorithm, key, self.file_aad.clone())?
+            }
+            None => self.footer.clone(),
+        };
+        Ok(Some(ColumnEncryptor {
+            cipher: ColumnCipher {
+                cipher,
+                row_group: to_ordinal(row_group)?,
+                column: to_ordinal(column)?,
+            },
+            crypto_metadata,
+        }))
+    }
+
+    /// Returns the [`RowGroupEncryptor`] of the row group at `ordinal`
+    pub fn row_group(&self, ordinal: usize) -> RowGroupEncryptor<'_> {
+        RowGroupEncryptor {
+            file: self,
+            ordinal,
+        }
+    }
+
+    /// Encrypts the metadata of the encrypted column chunks of `row_groups`.
+    ///
+    /// SPEC: with a plaintext footer, the metadata of every encrypted column is encrypted and
+    /// its plaintext copy stripped of statistics. With an encrypted footer, only the metadata of
+    /// columns encrypted with their own key is encrypted (the remaining is encrypted with the footer).
+    pub fn encrypt_column_metadata(&self, row_groups: &mut [RowGroup]) -> Result<()> {
+        for (row_group_ordinal, row_group) in row_groups.iter_mut().enumerate() {
+            for (column_ordinal, column) in row_group.columns.iter_mut().enumerate() {
+                let with_column_key = match &column.crypto_metadata {
+                    None => continue,
+                    Some(ColumnCryptoMetaData::ENCRYPTIONWITHFOOTERKEY(_)) => false,
+                    Some(ColumnCryptoMetaData::ENCRYPTIONWITHCOLUMNKEY(_)) => true,
+                };
+                if !with_column_key && !self.plaintext_footer() {
+                    continue;
+                }
+                let meta_data = if let Some(meta_data) = column.meta_data.as_mut() {
+                    meta_data
+                } else {
+                    continue;
+                };
+
+                let encryptor = self
+                    .column(&meta_data.path_in_schema, row_group_ordinal, column_ordinal)?
+                    .unwrap();
+
+                let mut buffer = vec![];
+                let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+                meta_data.write_to_out_protocol(&mut protocol)?;
+                let module = encryptor.cipher.module(ModuleType::ColumnMetaData, 0);
+                column.encrypted_column_metadata =
+                    Some(encryptor.cipher.cipher.encrypt(module, &buffer)?);
+
+                if self.plaintext_footer() {
+                    meta_data.statistics = None;
+                } else {
+                    column.meta_data = None;
+                }
+            }
+        }
+        Ok(())
+    }
+}
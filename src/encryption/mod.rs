@@ -0,0 +1,21 @@
+//! Parquet [modular encryption](https://github.com/apache/parquet-format/blob/master/Encryption.md).
+//!
+//! Files are encrypted by passing [`FileEncryptionProperties`] to
+//! [`FileWriter::with_encryption`](crate::write::FileWriter::with_encryption) and decrypted by
+//! passing [`FileDecryptionProperties`] to
+//! [`read_metadata_with_decryption`](crate::read::read_metadata_with_decryption); pages of
+//! encrypted columns are then transparently decrypted by [`crate::read::PageIterator`].
+//!
+//! Encrypting and decrypting requires the `encryption` feature.
+mod cipher;
+mod decryptor;
+mod encryptor;
+mod properties;
+
+pub(crate) use cipher::{ColumnCipher, Module, ModuleType};
+pub(crate) use decryptor::FileDecryptor;
+pub(crate) use encryptor::{ColumnEncryptor, FileEncryptor, RowGroupEncryptor};
+pub use properties::{
+    ColumnEncryptionProperties, EncryptionAlgorithm, FileDecryptionProperties,
+    FileEncryptionProperties, KeyRetriever,
+};
@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::Result;
+
+/// The algorithm used to encrypt the modules of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EncryptionAlgorithm {
+    /// All modules are encrypted with AES-GCM
+    AesGcmV1,
+    /// Pages are encrypted with AES-CTR, all other modules with AES-GCM
+    AesGcmCtrV1,
+}
+
+/// A retriever of keys from the key metadata stored in the file (e.g. a key id).
+/// Implement it to fetch keys from a key management service.
+pub trait KeyRetriever: Send + Sync {
+    /// Returns the key identified by `key_metadata`
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// How a column is encrypted.
+#[derive(Clone)]
+pub struct ColumnEncryptionProperties {
+    /// The path of the column in the schema
+    pub path: Vec<String>,
+    /// The key of this column (16, 24 or 32 bytes). When `None`, the footer key is used.
+    pub key: Option<Vec<u8>>,
+    /// Metadata stored in the file to retrieve the key of this column (see [`KeyRetriever`])
+    pub key_metadata: Option<Vec<u8>>,
+}
+
+impl std::fmt::Debug for ColumnEncryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColumnEncryptionProperties")
+            .field("path", &self.path)
+            .field("key_metadata", &self.key_metadata)
+            .finish()
+    }
+}
+
+/// How a file is encrypted.
+#[derive(Clone)]
+pub struct FileEncryptionProperties {
+    /// The algorithm to encrypt the file with
+    pub algorithm: EncryptionAlgorithm,
+    /// The key used to encrypt the footer (16, 24 or 32 bytes)
+    pub footer_key: Vec<u8>,
+    /// Metadata stored in the file to retrieve the footer key (see [`KeyRetriever`])
+    pub footer_key_metadata: Option<Vec<u8>>,
+    /// Whether the footer is written in plaintext (and signed with the footer key), so that
+    /// readers without keys can read the metadata and the non-encrypted columns.
+    pub plaintext_footer: bool,
+    /// The encrypted columns. When `None`, all columns are encrypted with the footer key.
+    /// Columns not declared here are not encrypted.
+    pub columns: Option<Vec<ColumnEncryptionProperties>>,
+    /// A prefix of the additional authenticated data (AAD) of every module (e.g. the file name)
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether `aad_prefix` is stored in the file. When `false`, readers must supply it.
+    pub store_aad_prefix: bool,
+}
+
+impl FileEncryptionProperties {
+    /// Returns new [`FileEncryptionProperties`] that encrypt the footer and all columns with
+    /// `footer_key`.
+    pub fn new(algorithm: EncryptionAlgorithm, footer_key: Vec<u8>) -> Self {
+        Self {
+            algorithm,
+            footer_key,
+            footer_key_metadata: None,
+            plaintext_footer: false,
+            columns: None,
+            aad_prefix: None,
+            store_aad_prefix: false,
+        }
+    }
+}
+
+impl std::fmt::Debug for FileEncryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileEncryptionProperties")
+            .field("algorithm", &self.algorithm)
+            .field("footer_key_metadata", &self.footer_key_metadata)
+            .field("plaintext_footer", &self.plaintext_footer)
+            .field("columns", &self.columns)
+            .field("aad_prefix", &self.aad_prefix)
+            .field("store_aad_prefix", &self.store_aad_prefix)
+            .finish()
+    }
+}
+
+/// How to decrypt a file.
+///
+/// Keys are taken from `footer_key` and `column_keys` when declared, and from `key_retriever`
+/// otherwise.
+#[derive(Clone, Default)]
+pub struct FileDecryptionProperties {
+    /// The key of the footer
+    pub footer_key: Option<Vec<u8>>,
+    /// The keys of columns encrypted with their own key, by their path in the schema
+    pub column_keys: HashMap<Vec<String>, Vec<u8>>,
+    /// A retriever of keys from the key metadata stored in the file
+    pub key_retriever: Option<Arc<dyn KeyRetriever>>,
+    /// The prefix of the additional authenticated data, for files that do not store it
+    pub aad_prefix: Option<Vec<u8>>,
+    /// Whether to verify the signature of plaintext footers
+    pub verify_plaintext_footer: bool,
+}
+
+impl FileDecryptionProperties {
+    /// Returns new [`FileDecryptionProperties`] with a footer key
+    pub fn new(footer_key: Vec<u8>) -> Self {
+        Self {
+            footer_key: Some(footer_key),
+            verify_plaintext_footer: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns new [`FileDecryptionProperties`] whose keys are fetched from `key_retriever`
+    pub fn with_key_retriever(key_retriever: Arc<dyn KeyRetriever>) -> Self {
+        Self {
+            key_retriever: Some(key_retriever),
+            verify_plaintext_footer: true,
+            ..Default::default()
+        }
+    }
+
+    /// Retrieves the key identified by `key_metadata`, or returns `None` if there is no
+    /// retriever or no key metadata. Errors of the retriever are returned.
+    fn retrieve(&self, key_metadata: Option<&[u8]>) -> Result<Option<Vec<u8>>> {
+        match (&self.key_retriever, key_metadata) {
+            (Some(retriever), Some(key_metadata)) => retriever.retrieve_key(key_metadata).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns the key of the footer, or `None` if it is not available
+    pub(crate) fn footer_key(&self, key_metadata: Option<&[u8]>) -> Result<Option<Vec<u8>>> {
+        if let Some(key) = &self.footer_key {
+            return Ok(Some(key.clone()));
+        }
+        self.retrieve(key_metadata)
+    }
+
+    /// Returns the key of the column at `path`, or `None` if it is not available
+    pub(crate) fn column_key(
+        &self,
+        path: &[String],
+        key_metadata: Option<&[u8]>,
+    ) -> Result<Option<Vec<u8>>> {
+        if let Some(key) = self.column_keys.get(path) {
+            return Ok(Some(key.clone()));
+        }
+        self.retrieve(key_metadata)
+    }
+}
+
+impl std::fmt::Debug for FileDecryptionProperties {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileDecryptionProperties")
+            .field("aad_prefix", &self.aad_prefix)
+            .field("verify_plaintext_footer", &self.verify_plaintext_footer)
+            .finish()
+    }
+}
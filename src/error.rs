@@ -10,6 +10,8 @@ pub enum Feature {
     Gzip,
     Lz4,
     Zstd,
+    Lzo,
+    Encryption,
 }
 
 /// Errors generated by this crate
@@ -0,0 +1,279 @@
+use std::any::Any;
+use std::convert::TryInto;
+
+use parquet_format_async_temp::ColumnIndex;
+
+use crate::error::{ParquetError, Result};
+use crate::schema::types::PhysicalType;
+use crate::types::{decode, NativeType};
+
+use super::BoundaryOrder;
+
+/// Trait object representing a [`ColumnIndex`] in Rust's native format.
+///
+/// See [`NativeIndex`], [`ByteIndex`], [`FixedLenByteIndex`] and [`BooleanIndex`] for concrete
+/// implementations. Match the [`Index::physical_type`] to each type and downcast accordingly.
+pub trait Index: Send + Sync + std::fmt::Debug {
+    fn as_any(&self) -> &dyn Any;
+
+    fn physical_type(&self) -> &PhysicalType;
+}
+
+/// The index of a page, containing the min and max values of the page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageIndex<T> {
+    /// The minimum value in the page. It is None when all values are null
+    pub min: Option<T>,
+    /// The maximum value in the page. It is None when all values are null
+    pub max: Option<T>,
+    /// The number of null values in the page
+    pub null_count: Option<i64>,
+}
+
+/// An index of a column of [`NativeType`] physical representation
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeIndex<T: NativeType> {
+    /// The indexes, one item per page
+    pub indexes: Vec<PageIndex<T>>,
+    /// the order
+    pub boundary_order: BoundaryOrder,
+}
+
+impl<T: NativeType> NativeIndex<T> {
+    /// Creates a new [`NativeIndex`]
+    pub(crate) fn try_new(index: ColumnIndex) -> Result<Self> {
+        check_lengths(&index)?;
+        let len = index.min_values.len();
+
+        let null_counts = index
+            .null_counts
+            .map(|x| x.into_iter().map(Some).collect::<Vec<_>>())
+            .unwrap_or_else(|| vec![None; len]);
+
+        let indexes = index
+            .min_values
+            .iter()
+            .zip(index.max_values.iter())
+            .zip(index.null_pages)
+            .zip(null_counts)
+            .map(|(((min, max), is_null), null_count)| {
+                let (min, max) = if is_null {
+                    (None, None)
+                } else {
+                    check_plain::<T>(min, max)?;
+                    (Some(decode::<T>(min)), Some(decode::<T>(max)))
+                };
+                Ok(PageIndex {
+                    min,
+                    max,
+                    null_count,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            indexes,
+            boundary_order: index.boundary_order.try_into()?,
+        })
+    }
+}
+
+impl<T: NativeType> Index for NativeIndex<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn physical_type(&self) -> &PhysicalType {
+        &T::TYPE
+    }
+}
+
+/// An index of a column of binary physical type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ByteIndex {
+    /// The indexes, one item per page
+    pub indexes: Vec<PageIndex<Vec<u8>>>,
+    /// the order
+    pub boundary_order: BoundaryOrder,
+}
+
+impl ByteIndex {
+    /// Creates a new [`ByteIndex`]
+    pub(crate) fn try_new(index: ColumnIndex) -> Result<Self> {
+        check_lengths(&index)?;
+        Ok(Self {
+            boundary_order: index.boundary_order.try_into()?,
+            indexes: binary_indexes(index),
+        })
+    }
+}
+
+impl Index for ByteIndex {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn physical_type(&self) -> &PhysicalType {
+        &PhysicalType::ByteArray
+    }
+}
+
+/// An index of a column of fixed len byte physical type
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedLenByteIndex {
+    /// The physical type of this column
+    pub type_: PhysicalType,
+    /// The indexes, one item per page
+    pub indexes: Vec<PageIndex<Vec<u8>>>,
+    /// the order
+    pub boundary_order: BoundaryOrder,
+}
+
+impl FixedLenByteIndex {
+    /// Creates a new [`FixedLenByteIndex`]
+    pub(crate) fn try_new(index: ColumnIndex, size: i32) -> Result<Self> {
+        check_lengths(&index)?;
+        let size = size as usize;
+        let any_invalid = index
+            .min_values
+            .iter()
+            .zip(index.max_values.iter())
+            .zip(index.null_pages.iter())
+            .any(|((min, max), is_null)| !is_null && (min.len() != size || max.len() != size));
+        if any_invalid {
+            return Err(ParquetError::OutOfSpec(
+                "The min_values and max_values of a column index MUST be plain encoded".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            type_: PhysicalType::FixedLenByteArray(size as i32),
+            boundary_order: index.boundary_order.try_into()?,
+            indexes: binary_indexes(index),
+        })
+    }
+}
+
+impl Index for FixedLenByteIndex {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn physical_type(&self) -> &PhysicalType {
+        &self.type_
+    }
+}
+
+/// An index of a column of boolean physical type
+#[derive(Debug, Clone, PartialEq)]
+pub struct BooleanIndex {
+    /// The indexes, one item per page
+    pub indexes: Vec<PageIndex<bool>>,
+    /// the order
+    pub boundary_order: BoundaryOrder,
+}
+
+impl BooleanIndex {
+    /// Creates a new [`BooleanIndex`]
+    pub(crate) fn try_new(index: ColumnIndex) -> Result<Self> {
+        check_lengths(&index)?;
+        let len = index.min_values.len();
+
+        let null_counts = index
+            .null_counts
+            .map(|x| x.into_iter().map(Some).collect::<Vec<_>>())
+            .unwrap_or_else(|| vec![None; len]);
+
+        let indexes = index
+            .min_values
+            .iter()
+            .zip(index.max_values.iter())
+            .zip(index.null_pages)
+            .zip(null_counts)
+            .map(|(((min, max), is_null), null_count)| {
+                let (min, max) = if is_null {
+                    (None, None)
+                } else {
+                    if min.len() != 1 || max.len() != 1 {
+                        return Err(ParquetError::OutOfSpec(
+                            "The min_values and max_values of a column index MUST be plain encoded"
+                                .to_string(),
+                        ));
+                    }
+                    (Some(min[0] != 0), Some(max[0] != 0))
+                };
+                Ok(PageIndex {
+                    min,
+                    max,
+                    null_count,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            indexes,
+            boundary_order: index.boundary_order.try_into()?,
+        })
+    }
+}
+
+impl Index for BooleanIndex {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn physical_type(&self) -> &PhysicalType {
+        &PhysicalType::Boolean
+    }
+}
+
+/// Checks that `index` has one min, max, null page and null count (when present) per page
+fn check_lengths(index: &ColumnIndex) -> Result<()> {
+    let len = index.min_values.len();
+    let null_counts_len = index.null_counts.as_ref().map_or(len, |x| x.len());
+    if index.max_values.len() != len || index.null_pages.len() != len || null_counts_len != len {
+        return Err(ParquetError::OutOfSpec(
+            "A column index must have as many min_values, max_values, null_pages and null_counts"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_plain<T: NativeType>(min: &[u8], max: &[u8]) -> Result<()> {
+    if min.len() != std::mem::size_of::<T>() || max.len() != std::mem::size_of::<T>() {
+        return Err(ParquetError::OutOfSpec(
+            "The min_values and max_values of a column index MUST be plain encoded".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn binary_indexes(index: ColumnIndex) -> Vec<PageIndex<Vec<u8>>> {
+    let len = index.min_values.len();
+
+    let null_counts = index
+        .null_counts
+        .map(|x| x.into_iter().map(Some).collect::<Vec<_>>())
+        .unwrap_or_else(|| vec![None; len]);
+
+    index
+        .min_values
+        .into_iter()
+        .zip(index.max_values)
+        .zip(index.null_pages)
+        .zip(null_counts)
+        .map(|(((min, max), is_null), null_count)| {
+            let (min, max) = if is_null {
+                (None, None)
+            } else {
+                (Some(min), Some(max))
+            };
+            PageIndex {
+                min,
+                max,
+                null_count,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,188 @@
+use std::convert::TryInto;
+
+use parquet_format_async_temp::PageLocation;
+
+use crate::error::{ParquetError, Result};
+
+/// An interval of rows, `[start, start + length)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    /// Its start
+    pub start: usize,
+    /// Its length
+    pub length: usize,
+}
+
+impl Interval {
+    /// Creates a new [`Interval`]
+    pub fn new(start: usize, length: usize) -> Self {
+        Self { start, length }
+    }
+}
+
+/// A page selected to be read, together with the rows selected from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilteredPage {
+    /// Location of the page in the file
+    pub start: u64,
+    /// The length of the page in the file, including its header
+    pub length: usize,
+    /// The rows selected from this page, relative to the first row of the page
+    pub selected_rows: Vec<Interval>,
+}
+
+/// Returns the intersection between `a` and `b`, or `None` if they do not overlap.
+fn intersect(a: Interval, b: Interval) -> Option<Interval> {
+    let start = a.start.max(b.start);
+    // saturating, so that an interval may extend to the end, e.g. `Interval::new(5, usize::MAX)`
+    let end = a
+        .start
+        .saturating_add(a.length)
+        .min(b.start.saturating_add(b.length));
+    (start < end).then(|| Interval::new(start, end - start))
+}
+
+/// Returns the row intervals covered by each page in `locations`, in a column chunk
+/// with `num_rows` rows.
+pub fn compute_rows(locations: &[PageLocation], num_rows: usize) -> Result<Vec<Interval>> {
+    let starts = locations
+        .iter()
+        .map(|location| {
+            location.first_row_index.try_into().map_err(|_| {
+                ParquetError::OutOfSpec(
+                    "The first row index of a page must be positive".to_string(),
+                )
+            })
+        })
+        .collect::<Result<Vec<usize>>>()?;
+
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain(std::iter::once(&num_rows)))
+        .map(|(&start, &end)| {
+            if end < start {
+                Err(ParquetError::OutOfSpec(
+                    "The first row index of the pages must be monotonically increasing".to_string(),
+                ))
+            } else {
+                Ok(Interval::new(start, end - start))
+            }
+        })
+        .collect()
+}
+
+/// Returns the pages of a column chunk with `num_rows` rows and pages at `locations` that
+/// contain at least one row of `intervals`, together with the rows selected from each of them.
+pub fn select_pages(
+    intervals: &[Interval],
+    locations: &[PageLocation],
+    num_rows: usize,
+) -> Result<Vec<FilteredPage>> {
+    let page_rows = compute_rows(locations, num_rows)?;
+
+    locations
+        .iter()
+        .zip(page_rows)
+        .filter_map(|(location, page)| {
+            let selected_rows = intervals
+                .iter()
+                .filter_map(|interval| intersect(*interval, page))
+                .map(|interval| Interval::new(interval.start - page.start, interval.length))
+                .collect::<Vec<_>>();
+            if selected_rows.is_empty() {
+                return None;
+            }
+            Some(to_filtered_page(location, selected_rows))
+        })
+        .collect()
+}
+
+fn to_filtered_page(location: &PageLocation, selected_rows: Vec<Interval>) -> Result<FilteredPage> {
+    let start = location.offset.try_into().map_err(|_| {
+        ParquetError::OutOfSpec("The offset of a page must be positive".to_string())
+    })?;
+    let length = location.compressed_page_size.try_into().map_err(|_| {
+        ParquetError::OutOfSpec("The compressed size of a page must be positive".to_string())
+    })?;
+    Ok(FilteredPage {
+        start,
+        length,
+        selected_rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(offset: i64, compressed_page_size: i32, first_row_index: i64) -> PageLocation {
+        PageLocation {
+            offset,
+            compressed_page_size,
+            first_row_index,
+        }
+    }
+
+    #[test]
+    fn select() -> Result<()> {
+        let locations = vec![location(4, 10, 0), location(14, 20, 5), location(34, 5, 10)];
+
+        // rows 3..7 span the first two pages; the last page is not selected
+        let pages = select_pages(&[Interval::new(3, 4)], &locations, 12)?;
+        assert_eq!(
+            pages,
+            vec![
+                FilteredPage {
+                    start: 4,
+                    length: 10,
+                    selected_rows: vec![Interval::new(3, 2)],
+                },
+                FilteredPage {
+                    start: 14,
+                    length: 20,
+                    selected_rows: vec![Interval::new(0, 2)],
+                },
+            ]
+        );
+
+        let pages = select_pages(&[Interval::new(0, 1), Interval::new(11, 5)], &locations, 12)?;
+        assert_eq!(
+            pages,
+            vec![
+                FilteredPage {
+                    start: 4,
+                    length: 10,
+                    selected_rows: vec![Interval::new(0, 1)],
+                },
+                FilteredPage {
+                    start: 34,
+                    length: 5,
+                    selected_rows: vec![Interval::new(1, 1)],
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn select_to_the_end() -> Result<()> {
+        let locations = vec![location(4, 10, 0), location(14, 20, 5)];
+
+        let pages = select_pages(&[Interval::new(7, usize::MAX)], &locations, 12)?;
+        assert_eq!(
+            pages,
+            vec![FilteredPage {
+                start: 14,
+                length: 20,
+                selected_rows: vec![Interval::new(2, 5)],
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_locations() {
+        let locations = vec![location(4, 10, 5), location(14, 20, 0)];
+        assert!(select_pages(&[Interval::new(0, 1)], &locations, 12).is_err());
+    }
+}
@@ -3,8 +3,11 @@
 
 #[macro_use]
 pub mod error;
+pub mod bloom_filter;
 pub mod compression;
 pub mod encoding;
+pub mod encryption;
+pub mod indexes;
 pub mod metadata;
 pub mod page;
 mod parquet_bridge;
@@ -19,6 +22,8 @@ pub use streaming_decompression::FallibleStreamingIterator;
 
 const FOOTER_SIZE: u64 = 8;
 const PARQUET_MAGIC: [u8; 4] = [b'P', b'A', b'R', b'1'];
+/// The magic of files whose footer is encrypted
+const PARQUET_MAGIC_ENCRYPTED: [u8; 4] = [b'P', b'A', b'R', b'E'];
 
 /// The number of bytes read at the end of the parquet file on first read
 const DEFAULT_FOOTER_READ_SIZE: u64 = 64 * 1024;
@@ -27,9 +32,19 @@ const DEFAULT_FOOTER_READ_SIZE: u64 = 64 * 1024;
 mod tests {
     use std::path::PathBuf;
 
+    use crate::metadata::{ColumnDescriptor, SchemaDescriptor};
+
     pub fn get_path() -> PathBuf {
         let dir = env!("CARGO_MANIFEST_DIR");
 
         PathBuf::from(dir).join("testing/parquet-testing/data")
     }
+
+    /// Returns the descriptor of the `column`th column of the schema `message`
+    pub fn descriptor(message: &str, column: usize) -> ColumnDescriptor {
+        SchemaDescriptor::try_from_message(message)
+            .unwrap()
+            .columns()[column]
+            .clone()
+    }
 }
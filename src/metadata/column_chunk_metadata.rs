@@ -1,21 +1,34 @@
 use std::convert::TryInto;
 use std::sync::Arc;
 
-use parquet_format_async_temp::{ColumnChunk, ColumnMetaData, Encoding};
+use parquet_format_async_temp::{ColumnChunk, ColumnMetaData, Encoding, PageEncodingStats};
 
 use super::column_descriptor::ColumnDescriptor;
+use super::column_order::ColumnOrder;
+use crate::encryption::ColumnCipher;
 use crate::error::Result;
 use crate::schema::types::{ParquetType, PhysicalType};
-use crate::statistics::{deserialize_statistics, Statistics};
+use crate::statistics::{
+    deserialize_statistics_with_order, HyperLogLog, Statistics, DISTINCT_SKETCH_KEY,
+};
 use crate::{compression::Compression, schema::types::Type};
 
 /// Metadata for a column chunk.
+///
+/// The metadata of an encrypted column chunk is only available when it was read with the key of
+/// its column (see [`ColumnChunkMetaData::has_metadata`]). Otherwise, its accessors panic, except
+/// [`ColumnChunkMetaData::statistics`] and [`ColumnChunkMetaData::distinct_sketch`], which
+/// return `None`.
 // This contains the `ColumnDescriptor` associated with the chunk so that deserializers have
 // access to the descriptor (e.g. physical, converted, logical).
 #[derive(Debug, Clone)]
 pub struct ColumnChunkMetaData {
     column_chunk: ColumnChunk,
     column_descr: ColumnDescriptor,
+    // the cipher of encrypted column chunks read with decryption properties
+    cipher: Option<ColumnCipher>,
+    // the order of the column declared by the file, used to decode its statistics
+    column_order: ColumnOrder,
 }
 
 /// Represents common operations for a column chunk.
@@ -25,6 +38,8 @@ impl ColumnChunkMetaData {
         Self {
             column_chunk,
             column_descr,
+            cipher: None,
+            column_order: ColumnOrder::Undefined,
         }
     }
 
@@ -45,6 +60,12 @@ impl ColumnChunkMetaData {
         self.column_chunk.meta_data.as_ref().unwrap()
     }
 
+    /// Whether the metadata of this column chunk is available, i.e. it is either not encrypted,
+    /// or its footer is plaintext, or it was decrypted with the key of its column.
+    pub fn has_metadata(&self) -> bool {
+        self.column_chunk.meta_data.is_some()
+    }
+
     /// Type of this column. Must be primitive.
     pub fn type_(&self) -> &Type {
         &self.column_metadata().type_
@@ -65,12 +86,32 @@ impl ColumnChunkMetaData {
         }
     }
 
-    /// Decodes the raw statistics into a statistics
+    /// Decodes the raw statistics into a statistics.
+    /// See [`deserialize_statistics_with_order`] for how deprecated min and max values are read.
     pub fn statistics(&self) -> Option<Result<Arc<dyn Statistics>>> {
-        self.column_metadata()
+        self.column_chunk
+            .meta_data
+            .as_ref()?
             .statistics
             .as_ref()
-            .map(|x| deserialize_statistics(x, self.descriptor().clone()))
+            .map(|x| {
+                deserialize_statistics_with_order(x, self.descriptor().clone(), self.column_order)
+            })
+    }
+
+    /// Decodes the sketch of the distinct values of this column chunk, written to its key-value
+    /// metadata under [`DISTINCT_SKETCH_KEY`], if any. Sketches of the same column can be merged
+    /// with [`HyperLogLog::merge`] to estimate its number of distinct values across row groups.
+    pub fn distinct_sketch(&self) -> Option<Result<HyperLogLog>> {
+        self.column_chunk
+            .meta_data
+            .as_ref()?
+            .key_value_metadata
+            .as_ref()?
+            .iter()
+            .find(|x| x.key == DISTINCT_SKETCH_KEY)
+            .and_then(|x| x.value.as_deref())
+            .map(HyperLogLog::try_from_hex)
     }
 
     /// Total number of values in this column chunk.
@@ -113,11 +154,41 @@ impl ColumnChunkMetaData {
         self.column_metadata().dictionary_page_offset
     }
 
+    /// Returns the offset of the bloom filter of this column chunk, if any.
+    pub fn bloom_filter_offset(&self) -> Option<i64> {
+        self.column_metadata().bloom_filter_offset
+    }
+
     /// Returns the encoding for this column
     pub fn column_encoding(&self) -> &Vec<Encoding> {
         &self.column_metadata().encodings
     }
 
+    /// Returns the number of pages of each page type and encoding of this column chunk, if known
+    pub fn page_encoding_stats(&self) -> Option<&Vec<PageEncodingStats>> {
+        self.column_metadata().encoding_stats.as_ref()
+    }
+
+    /// Returns the offset of the [`ColumnIndex`](crate::indexes::Index) of this column chunk, if any.
+    pub fn column_index_offset(&self) -> Option<i64> {
+        self.column_chunk.column_index_offset
+    }
+
+    /// Returns the length in bytes of the [`ColumnIndex`](crate::indexes::Index) of this column chunk, if any.
+    pub fn column_index_length(&self) -> Option<i32> {
+        self.column_chunk.column_index_length
+    }
+
+    /// Returns the offset of the offset index of this column chunk, if any.
+    pub fn offset_index_offset(&self) -> Option<i64> {
+        self.column_chunk.offset_index_offset
+    }
+
+    /// Returns the length in bytes of the offset index of this column chunk, if any.
+    pub fn offset_index_length(&self) -> Option<i32> {
+        self.column_chunk.offset_index_length
+    }
+
     /// Returns the offset and length in bytes of the column chunk within the file
     pub fn byte_range(&self) -> (u64, u64) {
         let col_start = if let Some(dict_page_offset) = self.dictionary_page_offset() {
@@ -141,9 +212,29 @@ impl ColumnChunkMetaData {
         Ok(Self {
             column_chunk,
             column_descr,
+            cipher: None,
+            column_order: ColumnOrder::Undefined,
         })
     }
 
+    /// Whether this column chunk is encrypted, regardless of whether it can be decrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.column_chunk.crypto_metadata.is_some()
+    }
+
+    /// The cipher used to decrypt the pages of this column chunk, if it is encrypted
+    pub(crate) fn cipher(&self) -> Option<&ColumnCipher> {
+        self.cipher.as_ref()
+    }
+
+    pub(crate) fn set_cipher(&mut self, cipher: Option<ColumnCipher>) {
+        self.cipher = cipher;
+    }
+
+    pub(crate) fn set_column_order(&mut self, column_order: ColumnOrder) {
+        self.column_order = column_order;
+    }
+
     /// Method to convert to Thrift.
     pub fn into_thrift(self) -> ColumnChunk {
         self.column_chunk
@@ -1,4 +1,4 @@
-use parquet_format_async_temp::RowGroup;
+use parquet_format_async_temp::{RowGroup, SortingColumn};
 
 use super::{column_chunk_metadata::ColumnChunkMetaData, schema_descriptor::SchemaDescriptor};
 use crate::{error::Result, write::ColumnOffsetsMetadata};
@@ -9,6 +9,7 @@ pub struct RowGroupMetaData {
     columns: Vec<ColumnChunkMetaData>,
     num_rows: i64,
     total_byte_size: i64,
+    sorting_columns: Option<Vec<SortingColumn>>,
 }
 
 impl RowGroupMetaData {
@@ -22,6 +23,7 @@ impl RowGroupMetaData {
             columns,
             num_rows,
             total_byte_size,
+            sorting_columns: None,
         }
     }
 
@@ -40,6 +42,10 @@ impl RowGroupMetaData {
         &self.columns
     }
 
+    pub(crate) fn columns_mut(&mut self) -> &mut [ColumnChunkMetaData] {
+        &mut self.columns
+    }
+
     /// Number of rows in this row group.
     pub fn num_rows(&self) -> i64 {
         self.num_rows
@@ -50,6 +56,12 @@ impl RowGroupMetaData {
         self.total_byte_size
     }
 
+    /// The columns by which the rows of this row group are sorted, if declared by the writer.
+    /// The first column is the primary sort key.
+    pub fn sorting_columns(&self) -> Option<&[SortingColumn]> {
+        self.sorting_columns.as_deref()
+    }
+
     /// Total size of all compressed column data in this row group.
     pub fn compressed_size(&self) -> i64 {
         self.columns.iter().map(|c| c.compressed_size()).sum()
@@ -63,6 +75,7 @@ impl RowGroupMetaData {
         assert_eq!(schema_descr.num_columns(), rg.columns.len());
         let total_byte_size = rg.total_byte_size;
         let num_rows = rg.num_rows;
+        let sorting_columns = rg.sorting_columns;
         let mut columns = vec![];
         for (cc, d) in rg.columns.into_iter().zip(schema_descr.columns()) {
             let cc = ColumnChunkMetaData::try_from_thrift(d.clone(), cc)?;
@@ -72,6 +85,7 @@ impl RowGroupMetaData {
             columns,
             num_rows,
             total_byte_size,
+            sorting_columns,
         })
     }
 
@@ -89,7 +103,7 @@ impl RowGroupMetaData {
             columns: self.columns.into_iter().map(|v| v.into_thrift()).collect(),
             total_byte_size: self.total_byte_size,
             num_rows: self.num_rows,
-            sorting_columns: None,
+            sorting_columns: self.sorting_columns,
             file_offset: file_offset,
             total_compressed_size: None,
             ordinal: None,
@@ -1,6 +1,8 @@
 use parquet_format_async_temp::LogicalType;
 
-use crate::schema::types::{PhysicalType, PrimitiveConvertedType};
+use crate::schema::types::{ParquetType, PhysicalType, PrimitiveConvertedType};
+
+use super::ColumnDescriptor;
 
 /// Sort order for page and column statistics.
 ///
@@ -35,6 +37,19 @@ pub fn get_sort_order(
     get_physical_sort_order(physical_type)
 }
 
+/// Returns sort order of the type of the column `descriptor`.
+pub fn get_column_sort_order(descriptor: &ColumnDescriptor) -> SortOrder {
+    match descriptor.type_() {
+        ParquetType::PrimitiveType {
+            logical_type,
+            converted_type,
+            physical_type,
+            ..
+        } => get_sort_order(logical_type, converted_type, physical_type),
+        ParquetType::GroupType { .. } => SortOrder::Undefined,
+    }
+}
+
 fn get_logical_sort_order(logical_type: &LogicalType) -> SortOrder {
     // TODO: Should this take converted and logical type, for compatibility?
     use LogicalType::*;
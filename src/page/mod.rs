@@ -10,11 +10,14 @@ pub use parquet_format_async_temp::{
 pub use crate::parquet_bridge::{DataPageHeaderExt, PageType};
 
 use crate::compression::Compression;
+use crate::encoding::hybrid_rle::HybridRleDecoder;
 use crate::encoding::{get_length, Encoding};
 use crate::error::Result;
+use crate::indexes::Interval;
 use crate::metadata::ColumnDescriptor;
+use crate::read::levels::get_bit_width;
 
-use crate::statistics::{deserialize_statistics, Statistics};
+use crate::statistics::{deserialize_statistics, HyperLogLog, Statistics};
 
 /// A [`CompressedDataPage`] is compressed, encoded representation of a Parquet data page.
 /// It holds actual data and thus cloning it is expensive.
@@ -26,6 +29,12 @@ pub struct CompressedDataPage {
     uncompressed_page_size: usize,
     pub(crate) dictionary_page: Option<Arc<dyn DictPage>>,
     pub(crate) descriptor: ColumnDescriptor,
+    // The number of rows in this page, when known.
+    pub(crate) num_rows: Option<usize>,
+    // The rows of this page selected to be read, when the page was read from a row selection.
+    pub(crate) selected_rows: Option<Vec<Interval>>,
+    // The sketch of the distinct values of this page, when written with one.
+    pub(crate) distinct_sketch: Option<Box<HyperLogLog>>,
 }
 
 impl CompressedDataPage {
@@ -37,6 +46,7 @@ impl CompressedDataPage {
         dictionary_page: Option<Arc<dyn DictPage>>,
         descriptor: ColumnDescriptor,
     ) -> Self {
+        let num_rows = header.num_rows(&descriptor);
         Self {
             header,
             buffer,
@@ -44,6 +54,9 @@ impl CompressedDataPage {
             uncompressed_page_size,
             dictionary_page,
             descriptor,
+            num_rows,
+            selected_rows: None,
+            distinct_sketch: None,
         }
     }
 
@@ -67,18 +80,27 @@ impl CompressedDataPage {
         self.header.num_values()
     }
 
+    /// Returns the number of rows in this page, if known.
+    /// This is only unknown for v1 pages of repeated columns that were not compressed by this crate.
+    pub fn num_rows(&self) -> Option<usize> {
+        self.num_rows
+    }
+
+    /// Returns the rows of this page that were selected to be read, relative to its first row.
+    /// This is `None` when the whole page was selected, e.g. when read by [`crate::read::PageIterator`].
+    pub fn selected_rows(&self) -> Option<&[Interval]> {
+        self.selected_rows.as_deref()
+    }
+
     /// Decodes the raw statistics into a statistics
     pub fn statistics(&self) -> Option<Result<Arc<dyn Statistics>>> {
-        match &self.header {
-            DataPageHeader::V1(d) => d
-                .statistics
-                .as_ref()
-                .map(|x| deserialize_statistics(x, self.descriptor().clone())),
-            DataPageHeader::V2(d) => d
-                .statistics
-                .as_ref()
-                .map(|x| deserialize_statistics(x, self.descriptor().clone())),
-        }
+        self.header.statistics(self.descriptor())
+    }
+
+    /// Returns the sketch of the distinct values of this page, if any.
+    /// See [`DataPage::set_distinct_sketch`].
+    pub fn distinct_sketch(&self) -> Option<&HyperLogLog> {
+        self.distinct_sketch.as_deref()
     }
 
     pub fn descriptor(&self) -> &ColumnDescriptor {
@@ -99,6 +121,35 @@ impl DataPageHeader {
             DataPageHeader::V2(d) => d.num_values as usize,
         }
     }
+
+    /// Decodes the raw statistics of a page of the column `descriptor` into a statistics
+    pub fn statistics(&self, descriptor: &ColumnDescriptor) -> Option<Result<Arc<dyn Statistics>>> {
+        match &self {
+            DataPageHeader::V1(d) => d
+                .statistics
+                .as_ref()
+                .map(|x| deserialize_statistics(x, descriptor.clone())),
+            DataPageHeader::V2(d) => d
+                .statistics
+                .as_ref()
+                .map(|x| deserialize_statistics(x, descriptor.clone())),
+        }
+    }
+
+    /// Returns the number of rows of the page, if it can be derived from the header alone.
+    /// Only v1 pages of repeated columns require decoding the repetition levels.
+    pub(crate) fn num_rows(&self, descriptor: &ColumnDescriptor) -> Option<usize> {
+        match &self {
+            DataPageHeader::V1(d) => {
+                if descriptor.max_rep_level() == 0 {
+                    Some(d.num_values as usize)
+                } else {
+                    None
+                }
+            }
+            DataPageHeader::V2(d) => Some(d.num_rows as usize),
+        }
+    }
 }
 
 /// A [`DataPage`] is an uncompressed, encoded representation of a Parquet data page. It holds actual data
@@ -109,6 +160,8 @@ pub struct DataPage {
     pub(super) buffer: Vec<u8>,
     pub(super) dictionary_page: Option<Arc<dyn DictPage>>,
     pub(super) descriptor: ColumnDescriptor,
+    pub(crate) selected_rows: Option<Vec<Interval>>,
+    pub(crate) distinct_sketch: Option<Box<HyperLogLog>>,
 }
 
 impl DataPage {
@@ -123,6 +176,8 @@ impl DataPage {
             buffer,
             dictionary_page,
             descriptor,
+            selected_rows: None,
+            distinct_sketch: None,
         }
     }
 
@@ -148,6 +203,30 @@ impl DataPage {
         self.header.num_values()
     }
 
+    /// Returns the rows of this page that were selected to be read, relative to its first row.
+    /// This is `None` when the whole page was selected.
+    pub fn selected_rows(&self) -> Option<&[Interval]> {
+        self.selected_rows.as_deref()
+    }
+
+    /// Returns the number of rows in this page, i.e. the number of repetition levels equal to zero.
+    /// Returns `None` if the repetition levels are not RLE-encoded.
+    pub fn num_rows(&self) -> Option<usize> {
+        if let Some(num_rows) = self.header.num_rows(&self.descriptor) {
+            return Some(num_rows);
+        }
+        if self.repetition_level_encoding() != Encoding::Rle {
+            return None;
+        }
+        let (rep_levels, _, _) = split_buffer(self, &self.descriptor);
+        let num_bits = get_bit_width(self.descriptor.max_rep_level());
+        Some(
+            HybridRleDecoder::new(rep_levels, num_bits, self.num_values())
+                .filter(|rep| *rep == 0)
+                .count(),
+        )
+    }
+
     pub fn encoding(&self) -> Encoding {
         match &self.header {
             DataPageHeader::V1(d) => d.encoding(),
@@ -171,16 +250,21 @@ impl DataPage {
 
     /// Decodes the raw statistics into a statistics
     pub fn statistics(&self) -> Option<Result<Arc<dyn Statistics>>> {
-        match &self.header {
-            DataPageHeader::V1(d) => d
-                .statistics
-                .as_ref()
-                .map(|x| deserialize_statistics(x, self.descriptor().clone())),
-            DataPageHeader::V2(d) => d
-                .statistics
-                .as_ref()
-                .map(|x| deserialize_statistics(x, self.descriptor().clone())),
-        }
+        self.header.statistics(self.descriptor())
+    }
+
+    /// Returns the sketch of the distinct values of this page, if any.
+    pub fn distinct_sketch(&self) -> Option<&HyperLogLog> {
+        self.distinct_sketch.as_deref()
+    }
+
+    /// Sets the sketch of the distinct values of this page. When every data page of a column
+    /// chunk has a sketch, the writer merges them to write the estimated `distinct_count` of the
+    /// column chunk's statistics. The merged sketch is also written to the key-value metadata of
+    /// the column chunk under [`crate::statistics::DISTINCT_SKETCH_KEY`] when
+    /// [`crate::write::WriteOptions::write_distinct_count_sketch`] is set.
+    pub fn set_distinct_sketch(&mut self, sketch: Option<HyperLogLog>) {
+        self.distinct_sketch = sketch.map(Box::new);
     }
 
     pub fn descriptor(&self) -> &ColumnDescriptor {
@@ -8,7 +8,7 @@ pub use primitive::PrimitivePageDict;
 
 use std::{any::Any, sync::Arc};
 
-use crate::compression::{decompress, Compression};
+use crate::compression::{CodecRegistry, Compression};
 use crate::error::{ParquetError, Result};
 use crate::schema::types::PhysicalType;
 
@@ -50,15 +50,33 @@ impl CompressedDictPage {
     }
 }
 
+/// Decompresses and deserializes a dictionary page.
 pub fn read_dict_page(
     page: &EncodedDictPage,
     compression: (Compression, usize),
     is_sorted: bool,
     physical_type: &PhysicalType,
+) -> Result<Arc<dyn DictPage>> {
+    read_dict_page_with_codecs(
+        page,
+        compression,
+        is_sorted,
+        physical_type,
+        &CodecRegistry::default(),
+    )
+}
+
+/// Decompresses (with the codecs of `codecs`) and deserializes a dictionary page.
+pub fn read_dict_page_with_codecs(
+    page: &EncodedDictPage,
+    compression: (Compression, usize),
+    is_sorted: bool,
+    physical_type: &PhysicalType,
+    codecs: &CodecRegistry,
 ) -> Result<Arc<dyn DictPage>> {
     if compression.0 != Compression::Uncompressed {
         let mut decompressed = vec![0; compression.1];
-        decompress(compression.0, &page.buffer, &mut decompressed)?;
+        codecs.decompress(compression.0, &page.buffer, &mut decompressed)?;
         deserialize(&decompressed, page.num_values, is_sorted, physical_type)
     } else {
         deserialize(&page.buffer, page.num_values, is_sorted, physical_type)
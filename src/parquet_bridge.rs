@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 
 use crate::error::ParquetError;
+use parquet_format_async_temp::BoundaryOrder as ParquetBoundaryOrder;
 use parquet_format_async_temp::CompressionCodec;
 use parquet_format_async_temp::DataPageHeader;
 use parquet_format_async_temp::DataPageHeaderV2;
@@ -47,10 +48,17 @@ pub enum Compression {
     Gzip,
     Lzo,
     Brotli,
+    /// LZ4 with the Hadoop framing, as written by parquet-mr (deprecated by the spec).
+    /// See [`crate::compression::decompress`] for the framings it reads.
     Lz4,
     Zstd,
+    /// LZ4 blocks without framing
+    Lz4Raw,
 }
 
+// SPEC: LZ4_RAW = 7. Not part of the thrift definitions this crate is generated from.
+const LZ4_RAW: CompressionCodec = CompressionCodec(7);
+
 impl TryFrom<CompressionCodec> for Compression {
     type Error = ParquetError;
 
@@ -63,6 +71,7 @@ impl TryFrom<CompressionCodec> for Compression {
             CompressionCodec::BROTLI => Compression::Brotli,
             CompressionCodec::LZ4 => Compression::Lz4,
             CompressionCodec::ZSTD => Compression::Zstd,
+            LZ4_RAW => Compression::Lz4Raw,
             _ => return Err(ParquetError::OutOfSpec("Thrift out of range".to_string())),
         })
     }
@@ -78,10 +87,153 @@ impl From<Compression> for CompressionCodec {
             Compression::Brotli => CompressionCodec::BROTLI,
             Compression::Lz4 => CompressionCodec::LZ4,
             Compression::Zstd => CompressionCodec::ZSTD,
+            Compression::Lz4Raw => LZ4_RAW,
+        }
+    }
+}
+
+/// The compression of the pages written by this crate: a [`Compression`] together with the
+/// level of the codecs that support one, or `None` to use their default level.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum CompressionOptions {
+    Uncompressed,
+    Snappy,
+    Gzip(Option<GzipLevel>),
+    Lzo,
+    Brotli(Option<BrotliLevel>),
+    Lz4,
+    Zstd(Option<ZstdLevel>),
+    Lz4Raw,
+}
+
+impl From<CompressionOptions> for Compression {
+    fn from(value: CompressionOptions) -> Self {
+        match value {
+            CompressionOptions::Uncompressed => Compression::Uncompressed,
+            CompressionOptions::Snappy => Compression::Snappy,
+            CompressionOptions::Gzip(_) => Compression::Gzip,
+            CompressionOptions::Lzo => Compression::Lzo,
+            CompressionOptions::Brotli(_) => Compression::Brotli,
+            CompressionOptions::Lz4 => Compression::Lz4,
+            CompressionOptions::Zstd(_) => Compression::Zstd,
+            CompressionOptions::Lz4Raw => Compression::Lz4Raw,
         }
     }
 }
 
+impl From<CompressionOptions> for CompressionCodec {
+    fn from(codec: CompressionOptions) -> Self {
+        Compression::from(codec).into()
+    }
+}
+
+/// A compression level, valid between `MINIMUM_LEVEL` and `MAXIMUM_LEVEL` (inclusive).
+trait CompressionLevel<T: std::fmt::Display + std::cmp::PartialOrd> {
+    const MINIMUM_LEVEL: T;
+    const MAXIMUM_LEVEL: T;
+
+    fn is_valid_level(level: T) -> Result<(), ParquetError> {
+        if (Self::MINIMUM_LEVEL..=Self::MAXIMUM_LEVEL).contains(&level) {
+            Ok(())
+        } else {
+            Err(ParquetError::General(format!(
+                "The compression level {} is not between {} and {}",
+                level,
+                Self::MINIMUM_LEVEL,
+                Self::MAXIMUM_LEVEL
+            )))
+        }
+    }
+}
+
+/// A valid Brotli compression level (quality), between 0 and 11. Defaults to 1.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct BrotliLevel(u32);
+
+impl Default for BrotliLevel {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl CompressionLevel<u32> for BrotliLevel {
+    const MINIMUM_LEVEL: u32 = 0;
+    const MAXIMUM_LEVEL: u32 = 11;
+}
+
+impl BrotliLevel {
+    /// Returns a [`BrotliLevel`].
+    /// # Error
+    /// Errors iff `level` is not between 0 and 11.
+    pub fn try_new(level: u32) -> Result<Self, ParquetError> {
+        Self::is_valid_level(level).map(|_| Self(level))
+    }
+
+    /// The compression level
+    pub fn compression_level(&self) -> u32 {
+        self.0
+    }
+}
+
+/// A valid Gzip compression level, between 0 and 9. Defaults to 6.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct GzipLevel(u8);
+
+impl Default for GzipLevel {
+    fn default() -> Self {
+        Self(6)
+    }
+}
+
+impl CompressionLevel<u8> for GzipLevel {
+    const MINIMUM_LEVEL: u8 = 0;
+    const MAXIMUM_LEVEL: u8 = 9;
+}
+
+impl GzipLevel {
+    /// Returns a [`GzipLevel`].
+    /// # Error
+    /// Errors iff `level` is not between 0 and 9.
+    pub fn try_new(level: u8) -> Result<Self, ParquetError> {
+        Self::is_valid_level(level).map(|_| Self(level))
+    }
+
+    /// The compression level
+    pub fn compression_level(&self) -> u8 {
+        self.0
+    }
+}
+
+/// A valid Zstd compression level, between 1 and 22. Defaults to 1, which favours speed;
+/// higher levels favour the compression ratio.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct ZstdLevel(i32);
+
+impl Default for ZstdLevel {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl CompressionLevel<i32> for ZstdLevel {
+    const MINIMUM_LEVEL: i32 = 1;
+    const MAXIMUM_LEVEL: i32 = 22;
+}
+
+impl ZstdLevel {
+    /// Returns a [`ZstdLevel`].
+    /// # Error
+    /// Errors iff `level` is not between 1 and 22.
+    pub fn try_new(level: i32) -> Result<Self, ParquetError> {
+        Self::is_valid_level(level).map(|_| Self(level))
+    }
+
+    /// The compression level
+    pub fn compression_level(&self) -> i32 {
+        self.0
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
 pub enum PageType {
     DataPage,
@@ -192,6 +344,38 @@ impl From<Encoding> for ParquetEncoding {
     }
 }
 
+/// Enum to annotate whether lists of min/max elements inside ColumnIndex
+/// are ordered and if so, in which direction.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum BoundaryOrder {
+    Unordered,
+    Ascending,
+    Descending,
+}
+
+impl TryFrom<ParquetBoundaryOrder> for BoundaryOrder {
+    type Error = ParquetError;
+
+    fn try_from(parquet_order: ParquetBoundaryOrder) -> Result<Self, Self::Error> {
+        Ok(match parquet_order {
+            ParquetBoundaryOrder::UNORDERED => BoundaryOrder::Unordered,
+            ParquetBoundaryOrder::ASCENDING => BoundaryOrder::Ascending,
+            ParquetBoundaryOrder::DESCENDING => BoundaryOrder::Descending,
+            _ => return Err(ParquetError::OutOfSpec("Thrift out of range".to_string())),
+        })
+    }
+}
+
+impl From<BoundaryOrder> for ParquetBoundaryOrder {
+    fn from(order: BoundaryOrder) -> Self {
+        match order {
+            BoundaryOrder::Unordered => ParquetBoundaryOrder::UNORDERED,
+            BoundaryOrder::Ascending => ParquetBoundaryOrder::ASCENDING,
+            BoundaryOrder::Descending => ParquetBoundaryOrder::DESCENDING,
+        }
+    }
+}
+
 pub trait DataPageHeaderExt {
     fn encoding(&self) -> Encoding;
     fn repetition_level_encoding(&self) -> Encoding;
@@ -1,15 +1,22 @@
+use std::convert::TryInto;
+
 use parquet_format_async_temp::DataPageHeaderV2;
 use streaming_decompression;
 
-use crate::compression::{self, Compression};
+use crate::compression::{CodecRegistry, Compression};
 use crate::error::{ParquetError, Result};
 use crate::page::{CompressedDataPage, DataPage, DataPageHeader};
 use crate::FallibleStreamingIterator;
 
 use super::PageIterator;
 
-fn decompress_v1(compressed: &[u8], compression: Compression, buffer: &mut [u8]) -> Result<()> {
-    compression::decompress(compression, compressed, buffer)
+fn decompress_v1(
+    compressed: &[u8],
+    compression: Compression,
+    buffer: &mut [u8],
+    codecs: &CodecRegistry,
+) -> Result<()> {
+    codecs.decompress(compression, compressed, buffer)
 }
 
 fn decompress_v2(
@@ -17,6 +24,7 @@ fn decompress_v2(
     page_header: &DataPageHeaderV2,
     compression: Compression,
     buffer: &mut [u8],
+    codecs: &CodecRegistry,
 ) -> Result<()> {
     // When processing data page v2, depending on enabled compression for the
     // page, we should account for uncompressed data ('offset') of
@@ -24,16 +32,32 @@ fn decompress_v2(
     //
     // We always use 0 offset for other pages other than v2, `true` flag means
     // that compression will be applied if decompressor is defined
-    let offset = (page_header.definition_levels_byte_length
-        + page_header.repetition_levels_byte_length) as usize;
+    let offset = page_header.definition_levels_byte_length as i64
+        + page_header.repetition_levels_byte_length as i64;
+    let offset: usize = offset
+        .try_into()
+        .ok()
+        .filter(|offset| *offset <= compressed.len().min(buffer.len()))
+        .ok_or_else(|| {
+            ParquetError::OutOfSpec(
+                "The levels of a data page (v2) are larger than the page".to_string(),
+            )
+        })?;
     // When is_compressed flag is missing the page is considered compressed
     let can_decompress = page_header.is_compressed.unwrap_or(true);
 
     if can_decompress {
         (&mut buffer[..offset]).copy_from_slice(&compressed[..offset]);
 
-        compression::decompress(compression, &compressed[offset..], &mut buffer[offset..])?;
+        codecs.decompress(compression, &compressed[offset..], &mut buffer[offset..])?;
     } else {
+        if buffer.len() != compressed.len() {
+            return Err(ParquetError::OutOfSpec(format!(
+                "An uncompressed data page (v2) has {} bytes but its header declares {}",
+                compressed.len(),
+                buffer.len()
+            )));
+        }
         buffer.copy_from_slice(compressed);
     }
     Ok(())
@@ -42,15 +66,41 @@ fn decompress_v2(
 /// decompresses a [`CompressedDataPage`] into `buffer`.
 /// If the page is un-compressed, `buffer` is swapped instead.
 /// Returns whether the page was decompressed.
+///
+/// The uncompressed size declared by the page is allocated without bound: use
+/// [`decompress_buffer_with_codecs`] with a `max_page_size` for pages of untrusted files.
 pub fn decompress_buffer(
     compressed_page: &mut CompressedDataPage,
     buffer: &mut Vec<u8>,
+) -> Result<bool> {
+    decompress_buffer_with_codecs(compressed_page, buffer, &CodecRegistry::default(), None)
+}
+
+/// Decompresses a [`CompressedDataPage`] into `buffer` with the codecs of `codecs`, like
+/// [`decompress_buffer`].
+/// # Errors
+/// Errors if the uncompressed size of the page is larger than `max_page_size`, before
+/// allocating it.
+pub fn decompress_buffer_with_codecs(
+    compressed_page: &mut CompressedDataPage,
+    buffer: &mut Vec<u8>,
+    codecs: &CodecRegistry,
+    max_page_size: Option<usize>,
 ) -> Result<bool> {
     if compressed_page.compression() != Compression::Uncompressed {
         let compressed_buffer = &compressed_page.buffer;
 
         // prepare the compression buffer
         let read_size = compressed_page.uncompressed_size();
+        match max_page_size {
+            Some(max_page_size) if read_size > max_page_size => {
+                return Err(ParquetError::OutOfSpec(format!(
+                    "The uncompressed size of a page ({}) is larger than the maximum of {}",
+                    read_size, max_page_size
+                )))
+            }
+            _ => {}
+        }
         if read_size > buffer.len() {
             // dealloc and ignore region, replacing it by a new region
             *buffer = vec![0; read_size]
@@ -58,14 +108,18 @@ pub fn decompress_buffer(
             buffer.truncate(read_size);
         }
         match compressed_page.header() {
-            DataPageHeader::V1(_) => {
-                decompress_v1(compressed_buffer, compressed_page.compression(), buffer)?
-            }
+            DataPageHeader::V1(_) => decompress_v1(
+                compressed_buffer,
+                compressed_page.compression(),
+                buffer,
+                codecs,
+            )?,
             DataPageHeader::V2(header) => decompress_v2(
                 compressed_buffer,
                 header,
                 compressed_page.compression(),
                 buffer,
+                codecs,
             )?,
         }
         Ok(true)
@@ -80,17 +134,33 @@ pub fn decompress_buffer(
 /// Decompresses the page, using `buffer` for decompression.
 /// If `page.buffer.len() == 0`, there was no decompression and the buffer was moved.
 /// Else, decompression took place.
-pub fn decompress(
+///
+/// Like [`decompress_buffer`], this does not bound the uncompressed size of the page (see
+/// [`decompress_with_codecs`]).
+pub fn decompress(compressed_page: CompressedDataPage, buffer: &mut Vec<u8>) -> Result<DataPage> {
+    decompress_with_codecs(compressed_page, buffer, &CodecRegistry::default(), None)
+}
+
+/// Decompresses the page with the codecs of `codecs`, using `buffer` for decompression.
+/// See [`decompress`].
+/// # Errors
+/// Errors if the uncompressed size of the page is larger than `max_page_size`, before
+/// allocating it.
+pub fn decompress_with_codecs(
     mut compressed_page: CompressedDataPage,
     buffer: &mut Vec<u8>,
+    codecs: &CodecRegistry,
+    max_page_size: Option<usize>,
 ) -> Result<DataPage> {
-    decompress_buffer(&mut compressed_page, buffer)?;
-    Ok(DataPage::new(
+    decompress_buffer_with_codecs(&mut compressed_page, buffer, codecs, max_page_size)?;
+    let mut page = DataPage::new(
         compressed_page.header,
         std::mem::take(buffer),
         compressed_page.dictionary_page,
         compressed_page.descriptor,
-    ))
+    );
+    page.selected_rows = compressed_page.selected_rows;
+    Ok(page)
 }
 
 fn decompress_reuse<R: std::io::Read>(
@@ -98,14 +168,20 @@ fn decompress_reuse<R: std::io::Read>(
     iterator: &mut PageIterator<R>,
     buffer: &mut Vec<u8>,
 ) -> Result<(DataPage, bool)> {
-    let was_decompressed = decompress_buffer(&mut compressed_page, buffer)?;
+    let was_decompressed = decompress_buffer_with_codecs(
+        &mut compressed_page,
+        buffer,
+        iterator.codecs(),
+        iterator.max_page_size(),
+    )?;
 
-    let new_page = DataPage::new(
+    let mut new_page = DataPage::new(
         compressed_page.header,
         std::mem::take(buffer),
         compressed_page.dictionary_page,
         compressed_page.descriptor,
     );
+    new_page.selected_rows = compressed_page.selected_rows;
 
     if was_decompressed {
         iterator.reuse_buffer(compressed_page.buffer)
@@ -114,6 +190,9 @@ fn decompress_reuse<R: std::io::Read>(
 }
 
 /// Decompressor that allows re-using the page buffer of [`PageIterator`].
+/// Pages are decompressed with the codecs of the [`PageIterator`] (see
+/// [`PageIterator::with_codecs`]), up to its maximum page size (see
+/// [`PageIterator::with_max_page_size`]).
 /// # Implementation
 /// The implementation depends on whether a page is compressed or not.
 /// > `PageIterator(a)`, `CompressedPage(b)`, `Decompressor(c)`, `DecompressedPage(d)`
@@ -199,10 +278,12 @@ impl<R: std::io::Read> FallibleStreamingIterator for Decompressor<R> {
     }
 }
 
+type DecompressFn = Box<dyn Fn(CompressedDataPage, &mut Vec<u8>) -> Result<DataPage> + Send + Sync>;
+
 type _Decompressor<I> = streaming_decompression::Decompressor<
     CompressedDataPage,
     DataPage,
-    fn(CompressedDataPage, &mut Vec<u8>) -> Result<DataPage>,
+    DecompressFn,
     ParquetError,
     I,
 >;
@@ -235,7 +316,24 @@ where
     I: Iterator<Item = Result<CompressedDataPage>>,
 {
     /// Returns a new [`BasicDecompressor`].
+    /// The uncompressed size of the pages is not bounded; pages from a [`PageIterator`] with a
+    /// maximum page size should use [`BasicDecompressor::new_with_codecs`] or
+    /// [`Decompressor`] instead.
     pub fn new(iter: I, buffer: Vec<u8>) -> Self {
+        Self::new_with_codecs(iter, buffer, CodecRegistry::default(), None)
+    }
+
+    /// Returns a new [`BasicDecompressor`] that decompresses pages with the codecs of `codecs`,
+    /// erroring on pages whose uncompressed size is larger than `max_page_size`.
+    pub fn new_with_codecs(
+        iter: I,
+        buffer: Vec<u8>,
+        codecs: CodecRegistry,
+        max_page_size: Option<usize>,
+    ) -> Self {
+        let decompress: DecompressFn = Box::new(move |page, buffer| {
+            decompress_with_codecs(page, buffer, &codecs, max_page_size)
+        });
         Self {
             iter: _Decompressor::new(iter, buffer, decompress),
         }
@@ -0,0 +1,144 @@
+use std::convert::TryFrom;
+
+use crate::encoding::{delta_bitpacked, get_length, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer, BinaryPageDict, DataPage};
+use crate::schema::types::PhysicalType;
+
+use super::{
+    check_physical_type, delta_bitpacked_length, dict_indices, index_out_of_bounds,
+    missing_dictionary, not_supported, validity, zip_validity,
+};
+
+fn decode_plain(values: &[u8], num_values: usize) -> Result<Vec<Vec<u8>>> {
+    let mut values = values;
+    (0..num_values)
+        .map(|_| {
+            // SPEC: each value is prefixed by its length, as a 4-byte little-endian integer
+            if values.len() < 4 {
+                return Err(ParquetError::OutOfSpec(
+                    "The page has less values than its definition levels declare".to_string(),
+                ));
+            }
+            let length = get_length(values) as usize;
+            if length > values.len() - 4 {
+                return Err(ParquetError::OutOfSpec(
+                    "The length of a value exceeds the page".to_string(),
+                ));
+            }
+            let value = values[4..4 + length].to_vec();
+            values = &values[4 + length..];
+            Ok(value)
+        })
+        .collect()
+}
+
+fn decode_dict(page: &DataPage, values: &[u8], num_values: usize) -> Result<Vec<Vec<u8>>> {
+    let dict = page
+        .dictionary_page()
+        .and_then(|dict| dict.as_any().downcast_ref::<BinaryPageDict>())
+        .ok_or_else(missing_dictionary)?;
+    let dict_values = dict.values();
+    let offsets = dict.offsets();
+
+    dict_indices(values, num_values)?
+        .map(|index| {
+            let index = index as usize;
+            if index + 1 >= offsets.len() {
+                return Err(index_out_of_bounds(index as u32));
+            }
+            Ok(dict_values[offsets[index] as usize..offsets[index + 1] as usize].to_vec())
+        })
+        .collect()
+}
+
+/// Splits `values` into slices of `lengths`
+fn split_lengths<I: Iterator<Item = usize>>(values: &[u8], lengths: I) -> Result<Vec<Vec<u8>>> {
+    let mut values = values;
+    lengths
+        .map(|length| {
+            if length > values.len() {
+                return Err(ParquetError::OutOfSpec(
+                    "The lengths of the page exceed its values".to_string(),
+                ));
+            }
+            let (value, remaining) = values.split_at(length);
+            values = remaining;
+            Ok(value.to_vec())
+        })
+        .collect()
+}
+
+/// Decodes the `DELTA_BINARY_PACKED`-encoded lengths at the start of `values` and returns them
+/// and the remaining of `values`
+fn decode_lengths(values: &[u8]) -> Result<(Vec<usize>, &[u8])> {
+    let consumed = delta_bitpacked_length(values)?;
+    let lengths = delta_bitpacked::Decoder::new(values)
+        .map(|length| {
+            usize::try_from(length).map_err(|_| {
+                ParquetError::OutOfSpec(format!("A length cannot be negative, got {}", length))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((lengths, &values[consumed..]))
+}
+
+/// Decodes `DELTA_LENGTH_BYTE_ARRAY`-encoded values
+fn decode_delta_lengths(values: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let (lengths, values) = decode_lengths(values)?;
+    split_lengths(values, lengths.into_iter())
+}
+
+/// Decodes `DELTA_BYTE_ARRAY`-encoded values
+pub(super) fn decode_delta_byte_array(values: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let (prefixes, values) = decode_lengths(values)?;
+    let suffixes = decode_delta_lengths(values)?;
+    if prefixes.len() != suffixes.len() {
+        return Err(ParquetError::OutOfSpec(
+            "A DELTA_BYTE_ARRAY page must have as many prefixes as suffixes".to_string(),
+        ));
+    }
+
+    // SPEC: each value is the prefix of the previous value followed by its suffix
+    let mut result: Vec<Vec<u8>> = Vec::with_capacity(prefixes.len());
+    for (prefix, suffix) in prefixes.into_iter().zip(suffixes) {
+        let previous = result.last().map(|x| x.as_slice()).unwrap_or_default();
+        if prefix > previous.len() {
+            return Err(ParquetError::OutOfSpec(
+                "A DELTA_BYTE_ARRAY prefix is longer than the previous value".to_string(),
+            ));
+        }
+        let mut value = Vec::with_capacity(prefix + suffix.len());
+        value.extend_from_slice(&previous[..prefix]);
+        value.extend_from_slice(&suffix);
+        result.push(value);
+    }
+    Ok(result)
+}
+
+/// Deserializes a page of a column of physical type `ByteArray`.
+/// # Error
+/// Errors iff the physical type of `descriptor` is not `ByteArray`, the page's encoding is not
+/// supported, or the page is out of spec.
+pub fn deserialize_binary(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    check_physical_type(descriptor, &PhysicalType::ByteArray)?;
+
+    let (_, def_levels, values) = split_buffer(page, descriptor);
+    let validity = validity(page, descriptor, def_levels)?;
+    let num_values = validity.iter().filter(|x| **x).count();
+
+    let values = match page.encoding() {
+        Encoding::Plain => decode_plain(values, num_values)?,
+        Encoding::PlainDictionary | Encoding::RleDictionary => {
+            decode_dict(page, values, num_values)?
+        }
+        Encoding::DeltaLengthByteArray => decode_delta_lengths(values)?,
+        Encoding::DeltaByteArray => decode_delta_byte_array(values)?,
+        _ => return Err(not_supported(page, descriptor)),
+    };
+    zip_validity(&validity, values)
+}
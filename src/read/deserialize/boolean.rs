@@ -0,0 +1,51 @@
+use crate::encoding::hybrid_rle::{BitmapIter, HybridRleDecoder};
+use crate::encoding::{get_length, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer, DataPage};
+use crate::schema::types::PhysicalType;
+
+use super::{check_physical_type, not_supported, validity, zip_validity};
+
+fn out_of_spec() -> ParquetError {
+    ParquetError::OutOfSpec(
+        "The page has less values than its definition levels declare".to_string(),
+    )
+}
+
+/// Deserializes a page of a column of physical type `Boolean`.
+/// # Error
+/// Errors iff the physical type of `descriptor` is not `Boolean`, the page's encoding is not
+/// supported, or the page is out of spec.
+pub fn deserialize_boolean(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+) -> Result<Vec<Option<bool>>> {
+    check_physical_type(descriptor, &PhysicalType::Boolean)?;
+
+    let (_, def_levels, values) = split_buffer(page, descriptor);
+    let validity = validity(page, descriptor, def_levels)?;
+    let num_values = validity.iter().filter(|x| **x).count();
+
+    let values = match page.encoding() {
+        // SPEC: bit-packed, LSB first
+        Encoding::Plain => {
+            if values.len() * 8 < num_values {
+                return Err(out_of_spec());
+            }
+            BitmapIter::new(values, 0, num_values).collect()
+        }
+        // SPEC: the length of the RLE-hybrid-encoded values prefixes them
+        Encoding::Rle => {
+            if values.len() < 4 || values.len() - 4 < get_length(values) as usize {
+                return Err(out_of_spec());
+            }
+            let length = get_length(values) as usize;
+            HybridRleDecoder::new(&values[4..4 + length], 1, num_values)
+                .map(|x| x == 1)
+                .collect()
+        }
+        _ => return Err(not_supported(page, descriptor)),
+    };
+    zip_validity(&validity, values)
+}
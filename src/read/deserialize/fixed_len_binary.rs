@@ -0,0 +1,79 @@
+use crate::encoding::{byte_stream_split, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer, DataPage, FixedLenByteArrayPageDict};
+use crate::schema::types::PhysicalType;
+
+use super::binary::decode_delta_byte_array;
+use super::{
+    dict_indices, index_out_of_bounds, missing_dictionary, not_supported, validity, zip_validity,
+};
+
+fn decode_plain(values: &[u8], size: usize, num_values: usize) -> Result<Vec<Vec<u8>>> {
+    if values.len() < num_values * size {
+        return Err(ParquetError::OutOfSpec(
+            "The page has less values than its definition levels declare".to_string(),
+        ));
+    }
+    Ok(values
+        .chunks_exact(size)
+        .take(num_values)
+        .map(|x| x.to_vec())
+        .collect())
+}
+
+fn decode_dict(page: &DataPage, values: &[u8], num_values: usize) -> Result<Vec<Vec<u8>>> {
+    let dict = page
+        .dictionary_page()
+        .and_then(|dict| dict.as_any().downcast_ref::<FixedLenByteArrayPageDict>())
+        .ok_or_else(missing_dictionary)?;
+    let size = dict.size();
+    let dict_values = dict.values();
+
+    dict_indices(values, num_values)?
+        .map(|index| {
+            let start = index as usize * size;
+            dict_values
+                .get(start..start + size)
+                .map(|x| x.to_vec())
+                .ok_or_else(|| index_out_of_bounds(index))
+        })
+        .collect()
+}
+
+/// Deserializes a page of a column of physical type `FixedLenByteArray`.
+/// # Error
+/// Errors iff the physical type of `descriptor` is not `FixedLenByteArray`, the page's encoding
+/// is not supported, or the page is out of spec.
+pub fn deserialize_fixed_len_binary(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let size = if let PhysicalType::FixedLenByteArray(size) = descriptor.physical_type() {
+        *size as usize
+    } else {
+        return Err(general_err!(
+            "The column's physical type {:?} is not FixedLenByteArray",
+            descriptor.physical_type()
+        ));
+    };
+
+    let (_, def_levels, values) = split_buffer(page, descriptor);
+    let validity = validity(page, descriptor, def_levels)?;
+    let num_values = validity.iter().filter(|x| **x).count();
+
+    let values = match page.encoding() {
+        Encoding::Plain => decode_plain(values, size, num_values)?,
+        Encoding::PlainDictionary | Encoding::RleDictionary => {
+            decode_dict(page, values, num_values)?
+        }
+        Encoding::ByteStreamSplit => {
+            let mut buffer = vec![];
+            byte_stream_split::decode_fixed_len(values, size, &mut buffer)?;
+            decode_plain(&buffer, size, num_values)?
+        }
+        Encoding::DeltaByteArray => decode_delta_byte_array(values)?,
+        _ => return Err(not_supported(page, descriptor)),
+    };
+    zip_validity(&validity, values)
+}
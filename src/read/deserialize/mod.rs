@@ -0,0 +1,533 @@
+//! Deserialization of [`DataPage`]s into native Rust vectors.
+//!
+//! Every function returns one item per value of the page: `Some(value)` when its definition
+//! level is the column's maximum definition level and `None` otherwise. For repeated columns,
+//! the rows and nesting of the values are described by the page's repetition and definition
+//! levels, that these functions do not interpret.
+mod binary;
+mod boolean;
+mod fixed_len_binary;
+mod primitive;
+
+pub use binary::deserialize_binary;
+pub use boolean::deserialize_boolean;
+pub use fixed_len_binary::deserialize_fixed_len_binary;
+pub use primitive::deserialize_primitive;
+
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::encoding::{ceil8, uleb128, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::DataPage;
+use crate::schema::types::PhysicalType;
+use crate::FallibleStreamingIterator;
+
+use super::levels::get_bit_width;
+
+/// The deserialized values of a column, one variant per physical type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    Boolean(Vec<Option<bool>>),
+    Int32(Vec<Option<i32>>),
+    Int64(Vec<Option<i64>>),
+    Int96(Vec<Option<[u32; 3]>>),
+    Float(Vec<Option<f32>>),
+    Double(Vec<Option<f64>>),
+    Binary(Vec<Option<Vec<u8>>>),
+    FixedLenBinary(Vec<Option<Vec<u8>>>),
+}
+
+impl Values {
+    /// Returns an empty [`Values`] of the physical type `physical_type`.
+    pub fn new(physical_type: &PhysicalType) -> Self {
+        match physical_type {
+            PhysicalType::Boolean => Values::Boolean(vec![]),
+            PhysicalType::Int32 => Values::Int32(vec![]),
+            PhysicalType::Int64 => Values::Int64(vec![]),
+            PhysicalType::Int96 => Values::Int96(vec![]),
+            PhysicalType::Float => Values::Float(vec![]),
+            PhysicalType::Double => Values::Double(vec![]),
+            PhysicalType::ByteArray => Values::Binary(vec![]),
+            PhysicalType::FixedLenByteArray(_) => Values::FixedLenBinary(vec![]),
+        }
+    }
+
+    /// The number of values, including nulls.
+    pub fn len(&self) -> usize {
+        match self {
+            Values::Boolean(values) => values.len(),
+            Values::Int32(values) => values.len(),
+            Values::Int64(values) => values.len(),
+            Values::Int96(values) => values.len(),
+            Values::Float(values) => values.len(),
+            Values::Double(values) => values.len(),
+            Values::Binary(values) => values.len(),
+            Values::FixedLenBinary(values) => values.len(),
+        }
+    }
+
+    /// Whether there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends the values of `other` to `self`.
+    /// # Error
+    /// Errors iff `other` is of a different physical type.
+    pub fn extend(&mut self, other: Values) -> Result<()> {
+        match (self, other) {
+            (Values::Boolean(values), Values::Boolean(other)) => values.extend(other),
+            (Values::Int32(values), Values::Int32(other)) => values.extend(other),
+            (Values::Int64(values), Values::Int64(other)) => values.extend(other),
+            (Values::Int96(values), Values::Int96(other)) => values.extend(other),
+            (Values::Float(values), Values::Float(other)) => values.extend(other),
+            (Values::Double(values), Values::Double(other)) => values.extend(other),
+            (Values::Binary(values), Values::Binary(other)) => values.extend(other),
+            (Values::FixedLenBinary(values), Values::FixedLenBinary(other)) => values.extend(other),
+            _ => {
+                return Err(general_err!(
+                    "Cannot extend values of different physical types"
+                ))
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Deserializes `page` into [`Values`] of the physical type of `descriptor`.
+pub fn deserialize(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<Values> {
+    Ok(match descriptor.physical_type() {
+        PhysicalType::Boolean => Values::Boolean(deserialize_boolean(page, descriptor)?),
+        PhysicalType::Int32 => Values::Int32(deserialize_primitive(page, descriptor)?),
+        PhysicalType::Int64 => Values::Int64(deserialize_primitive(page, descriptor)?),
+        PhysicalType::Int96 => Values::Int96(deserialize_primitive(page, descriptor)?),
+        PhysicalType::Float => Values::Float(deserialize_primitive(page, descriptor)?),
+        PhysicalType::Double => Values::Double(deserialize_primitive(page, descriptor)?),
+        PhysicalType::ByteArray => Values::Binary(deserialize_binary(page, descriptor)?),
+        PhysicalType::FixedLenByteArray(_) => {
+            Values::FixedLenBinary(deserialize_fixed_len_binary(page, descriptor)?)
+        }
+    })
+}
+
+/// Deserializes all `pages` of a column chunk (e.g. from a [`super::BasicDecompressor`]) into
+/// [`Values`] of the physical type of `descriptor`.
+pub fn deserialize_column<I>(mut pages: I, descriptor: &ColumnDescriptor) -> Result<Values>
+where
+    I: FallibleStreamingIterator<Item = DataPage, Error = ParquetError>,
+{
+    let mut values = Values::new(descriptor.physical_type());
+    while let Some(page) = pages.next()? {
+        values.extend(deserialize(page, descriptor)?)?;
+    }
+    Ok(values)
+}
+
+fn check_physical_type(descriptor: &ColumnDescriptor, expected: &PhysicalType) -> Result<()> {
+    if descriptor.physical_type() != expected {
+        return Err(general_err!(
+            "The column's physical type {:?} is not {:?}",
+            descriptor.physical_type(),
+            expected
+        ));
+    }
+    Ok(())
+}
+
+fn not_supported(page: &DataPage, descriptor: &ColumnDescriptor) -> ParquetError {
+    general_err!(
+        "Decoding {:?}-encoded pages of physical type {:?} is not supported",
+        page.encoding(),
+        descriptor.physical_type()
+    )
+}
+
+/// Returns whether each value of `page` is non-null, given its encoded definition levels
+fn validity(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+    def_levels: &[u8],
+) -> Result<Vec<bool>> {
+    let max_def_level = descriptor.max_def_level();
+    if max_def_level == 0 {
+        return Ok(vec![true; page.num_values()]);
+    }
+    match page.definition_level_encoding() {
+        Encoding::Rle => {
+            let num_bits = get_bit_width(max_def_level);
+            Ok(
+                HybridRleDecoder::new(def_levels, num_bits, page.num_values())
+                    .map(|def| def == max_def_level as u32)
+                    .collect(),
+            )
+        }
+        other => Err(general_err!(
+            "Decoding definition levels encoded as {:?} is not supported",
+            other
+        )),
+    }
+}
+
+/// Assigns the non-null `values` to the slots of `validity`
+fn zip_validity<T>(validity: &[bool], values: Vec<T>) -> Result<Vec<Option<T>>> {
+    let mut values = values.into_iter();
+    validity
+        .iter()
+        .map(|is_valid| {
+            if *is_valid {
+                values.next().map(Some).ok_or_else(|| {
+                    ParquetError::OutOfSpec(
+                        "The page has less values than its definition levels declare".to_string(),
+                    )
+                })
+            } else {
+                Ok(None)
+            }
+        })
+        .collect()
+}
+
+/// Returns an iterator of the `num_values` dictionary indices of an `RLE_DICTIONARY`-encoded
+/// `values`
+fn dict_indices(values: &[u8], num_values: usize) -> Result<HybridRleDecoder<'_>> {
+    let (bit_width, values) = values.split_first().ok_or_else(|| {
+        ParquetError::OutOfSpec("A dictionary-encoded page must have a bit width".to_string())
+    })?;
+    if *bit_width > 32 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The bit width of dictionary indices must be at most 32, got {}",
+            bit_width
+        )));
+    }
+    Ok(HybridRleDecoder::new(values, *bit_width as u32, num_values))
+}
+
+/// Decodes the ULEB128-encoded integer at the start of `values` and returns it and the number
+/// of bytes it takes
+fn decode_uleb128(values: &[u8]) -> Result<(u64, usize)> {
+    // an u64 takes at most 10 bytes, of which only the last has its most significant bit unset
+    match values
+        .iter()
+        .take(10)
+        .position(|byte| byte & 0b1000_0000 == 0)
+    {
+        Some(9) if values[9] > 1 => Err(ParquetError::OutOfSpec(
+            "A ULEB128-encoded integer overflows 64 bits".to_string(),
+        )),
+        Some(_) => Ok(uleb128::decode(values)),
+        None => Err(ParquetError::OutOfSpec(
+            "A ULEB128-encoded integer is truncated".to_string(),
+        )),
+    }
+}
+
+/// Returns the number of bytes of the `DELTA_BINARY_PACKED`-encoded integers at the start of
+/// `values`, so that they can be decoded by [`crate::encoding::delta_bitpacked::Decoder`]
+/// (which panics on out of spec values).
+/// # Error
+/// Errors iff the integers are out of spec or exceed `values`.
+fn delta_bitpacked_length(values: &[u8]) -> Result<usize> {
+    let truncated =
+        || ParquetError::OutOfSpec("A DELTA_BINARY_PACKED page is truncated".to_string());
+
+    // header: block size, number of mini blocks, number of values and first value
+    let mut header = [0; 4];
+    let mut offset = 0;
+    for item in header.iter_mut() {
+        let (value, consumed) = decode_uleb128(&values[offset..])?;
+        *item = value;
+        offset += consumed;
+    }
+    let [block_size, num_mini_blocks, num_values, _] = header;
+
+    let values_per_mini_block = block_size.checked_div(num_mini_blocks).unwrap_or(0);
+    if block_size % 128 != 0 || values_per_mini_block == 0 || values_per_mini_block % 8 != 0 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "A DELTA_BINARY_PACKED block of {} values cannot have {} mini blocks",
+            block_size, num_mini_blocks
+        )));
+    }
+    let num_mini_blocks = num_mini_blocks as usize;
+    let values_per_mini_block = values_per_mini_block as usize;
+
+    // the values after the first are deltas, stored in blocks
+    let mut remaining = (num_values as usize).saturating_sub(1);
+    while remaining > 0 {
+        // min delta and bit width of every mini block
+        let (_, consumed) = decode_uleb128(&values[offset..])?;
+        offset += consumed;
+        if values.len() - offset < num_mini_blocks {
+            return Err(truncated());
+        }
+        let bit_widths = &values[offset..offset + num_mini_blocks];
+        offset += num_mini_blocks;
+
+        // only the mini blocks with values are read
+        let length = remaining.min(num_mini_blocks * values_per_mini_block);
+        let mut block_remaining = length;
+        for num_bits in bit_widths {
+            if block_remaining == 0 {
+                break;
+            }
+            if *num_bits > 64 {
+                return Err(ParquetError::OutOfSpec(format!(
+                    "The bit width of a DELTA_BINARY_PACKED mini block must be at most 64, got {}",
+                    num_bits
+                )));
+            }
+            let bytes = values_per_mini_block
+                .checked_mul(*num_bits as usize)
+                .map(ceil8)
+                .ok_or_else(truncated)?;
+            if values.len() - offset < bytes {
+                return Err(truncated());
+            }
+            offset += bytes;
+            block_remaining = block_remaining.saturating_sub(values_per_mini_block);
+        }
+        remaining -= length;
+    }
+    Ok(offset)
+}
+
+fn index_out_of_bounds(index: u32) -> ParquetError {
+    ParquetError::OutOfSpec(format!(
+        "The dictionary index {} is out of bounds of the dictionary",
+        index
+    ))
+}
+
+fn missing_dictionary() -> ParquetError {
+    ParquetError::OutOfSpec(
+        "A dictionary-encoded page requires a dictionary page of the column's physical type"
+            .to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    use crate::encoding::{byte_stream_split, delta_bitpacked, delta_byte_array};
+    use crate::encoding::{delta_length_byte_array, hybrid_rle};
+    use crate::page::{
+        DataPageHeader, DataPageHeaderV1, DictPage, FixedLenByteArrayPageDict, PrimitivePageDict,
+    };
+    use crate::tests::descriptor;
+
+    fn page(
+        descriptor: &ColumnDescriptor,
+        num_values: usize,
+        encoding: Encoding,
+        buffer: Vec<u8>,
+        dict: Option<Arc<dyn DictPage>>,
+    ) -> DataPage {
+        let header = DataPageHeaderV1 {
+            num_values: num_values as i32,
+            encoding: encoding.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: None,
+        };
+        DataPage::new(DataPageHeader::V1(header), buffer, dict, descriptor.clone())
+    }
+
+    /// The v1 definition levels of an optional column with `validity`
+    fn def_levels(validity: &[bool]) -> Vec<u8> {
+        let mut buffer = vec![0; 4];
+        hybrid_rle::encode_bool(&mut buffer, validity.iter().copied()).unwrap();
+        let length = (buffer.len() - 4) as u32;
+        buffer[..4].copy_from_slice(&length.to_le_bytes());
+        buffer
+    }
+
+    #[test]
+    fn boolean() -> Result<()> {
+        let descriptor = descriptor("message schema { OPTIONAL BOOLEAN col; }", 0);
+        let mut buffer = def_levels(&[true, false, true, true]);
+        buffer.push(0b00000101);
+        let page = page(&descriptor, 4, Encoding::Plain, buffer, None);
+
+        let expected = vec![Some(true), None, Some(false), Some(true)];
+        assert_eq!(deserialize_boolean(&page, &descriptor)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn boolean_rle() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED BOOLEAN col; }", 0);
+        let mut values = vec![];
+        hybrid_rle::encode_bool(&mut values, [false, true, true].iter().copied()).unwrap();
+        let mut buffer = (values.len() as u32).to_le_bytes().to_vec();
+        buffer.extend(values);
+        let page = page(&descriptor, 3, Encoding::Rle, buffer, None);
+
+        let expected = vec![Some(false), Some(true), Some(true)];
+        assert_eq!(deserialize(&page, &descriptor)?, Values::Boolean(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn int32_delta() -> Result<()> {
+        let descriptor = descriptor("message schema { OPTIONAL INT32 col; }", 0);
+        let data = [i32::MAX, i32::MIN, -1];
+        let mut buffer = def_levels(&[true, true, false, true]);
+        delta_bitpacked::encode(data.iter().copied(), &mut buffer);
+        let page = page(&descriptor, 4, Encoding::DeltaBinaryPacked, buffer, None);
+
+        let expected = vec![Some(i32::MAX), Some(i32::MIN), None, Some(-1)];
+        assert_eq!(deserialize_primitive::<i32>(&page, &descriptor)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn double_byte_stream_split() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED DOUBLE col; }", 0);
+        let data = [1.5f64, -2.0, 3.25];
+        let mut buffer = vec![];
+        byte_stream_split::encode(&data, &mut buffer);
+        let page = page(&descriptor, 3, Encoding::ByteStreamSplit, buffer, None);
+
+        let expected = data.iter().copied().map(Some).collect();
+        assert_eq!(deserialize(&page, &descriptor)?, Values::Double(expected));
+        Ok(())
+    }
+
+    #[test]
+    fn int64_dict() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED INT64 col; }", 0);
+        let dict = Arc::new(PrimitivePageDict::new(vec![10i64, 20]));
+        let mut buffer = vec![1];
+        hybrid_rle::encode_u32(&mut buffer, [1, 0, 1].iter().copied(), 1).unwrap();
+        let page = page(&descriptor, 3, Encoding::RleDictionary, buffer, Some(dict));
+
+        let expected = vec![Some(20), Some(10), Some(20)];
+        assert_eq!(deserialize_primitive::<i64>(&page, &descriptor)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn dict_out_of_bounds() {
+        let descriptor = descriptor("message schema { REQUIRED INT64 col; }", 0);
+        let dict = Arc::new(PrimitivePageDict::new(vec![10i64]));
+        let mut buffer = vec![2];
+        hybrid_rle::encode_u32(&mut buffer, [0, 2].iter().copied(), 2).unwrap();
+        let page = page(&descriptor, 2, Encoding::RleDictionary, buffer, Some(dict));
+
+        assert!(deserialize(&page, &descriptor).is_err());
+    }
+
+    #[test]
+    fn binary_delta() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED BYTE_ARRAY col; }", 0);
+        let data = ["Hello", "Helicopter", "World"];
+
+        let mut buffer = vec![];
+        delta_length_byte_array::encode(data.iter(), &mut buffer);
+        let page_ = page(&descriptor, 3, Encoding::DeltaLengthByteArray, buffer, None);
+        let expected = data
+            .iter()
+            .map(|x| Some(x.as_bytes().to_vec()))
+            .collect::<Vec<_>>();
+        assert_eq!(deserialize_binary(&page_, &descriptor)?, expected);
+
+        let mut buffer = vec![];
+        delta_byte_array::encode(data.iter(), &mut buffer);
+        let page_ = page(&descriptor, 3, Encoding::DeltaByteArray, buffer, None);
+        assert_eq!(deserialize_binary(&page_, &descriptor)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_len_binary() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema { OPTIONAL FIXED_LEN_BYTE_ARRAY(2) col; }",
+            0,
+        );
+        let expected = vec![Some(vec![1, 2]), None, Some(vec![3, 4])];
+
+        let mut buffer = def_levels(&[true, false, true]);
+        buffer.extend_from_slice(&[1, 2, 3, 4]);
+        let page_ = page(&descriptor, 3, Encoding::Plain, buffer, None);
+        assert_eq!(deserialize_fixed_len_binary(&page_, &descriptor)?, expected);
+
+        let mut buffer = def_levels(&[true, false, true]);
+        byte_stream_split::encode_fixed_len(&[1, 2, 3, 4], 2, &mut buffer)?;
+        let page_ = page(&descriptor, 3, Encoding::ByteStreamSplit, buffer, None);
+        assert_eq!(deserialize_fixed_len_binary(&page_, &descriptor)?, expected);
+
+        let dict = Arc::new(FixedLenByteArrayPageDict::new(
+            vec![3, 4, 1, 2],
+            PhysicalType::FixedLenByteArray(2),
+            2,
+        ));
+        let mut buffer = def_levels(&[true, false, true]);
+        buffer.push(1);
+        hybrid_rle::encode_u32(&mut buffer, [1, 0].iter().copied(), 1).unwrap();
+        let page_ = page(&descriptor, 3, Encoding::RleDictionary, buffer, Some(dict));
+        assert_eq!(
+            deserialize(&page_, &descriptor)?,
+            Values::FixedLenBinary(expected)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let descriptor = descriptor("message schema { REQUIRED INT32 col; }", 0);
+        let page_ = page(&descriptor, 2, Encoding::Plain, vec![0; 4], None);
+        // wrong physical type
+        assert!(deserialize_primitive::<i64>(&page_, &descriptor).is_err());
+        // less values than declared
+        assert!(deserialize_primitive::<i32>(&page_, &descriptor).is_err());
+
+        // unsupported encoding
+        let page_ = page(&descriptor, 1, Encoding::DeltaByteArray, vec![0; 4], None);
+        assert!(deserialize(&page_, &descriptor).is_err());
+    }
+
+    #[test]
+    fn malformed_primitive() {
+        let descriptor = descriptor("message schema { REQUIRED INT32 col; }", 0);
+        let dict = Arc::new(PrimitivePageDict::new(vec![10i32]));
+        let page_ = page(
+            &descriptor,
+            1,
+            Encoding::RleDictionary,
+            vec![33, 2, 0],
+            Some(dict),
+        );
+        assert!(deserialize(&page_, &descriptor).is_err());
+
+        let mut buffer = vec![];
+        delta_bitpacked::encode([1, 10, 100].iter().copied(), &mut buffer);
+        buffer.truncate(buffer.len() - 2);
+        let page_ = page(&descriptor, 3, Encoding::DeltaBinaryPacked, buffer, None);
+        assert!(deserialize(&page_, &descriptor).is_err());
+    }
+
+    #[test]
+    fn malformed_binary() {
+        let descriptor = descriptor("message schema { REQUIRED BYTE_ARRAY col; }", 0);
+        let page_ = page(&descriptor, 1, Encoding::Plain, vec![5, 0, 0, 0, 1], None);
+        assert!(deserialize(&page_, &descriptor).is_err());
+        let page_ = page(&descriptor, 1, Encoding::Plain, vec![5, 0], None);
+        assert!(deserialize(&page_, &descriptor).is_err());
+
+        let data = ["Hello", "World"];
+        let mut buffer = vec![];
+        delta_byte_array::encode(data.iter(), &mut buffer);
+        for length in 0..buffer.len() {
+            let page_ = page(
+                &descriptor,
+                2,
+                Encoding::DeltaByteArray,
+                buffer[..length].to_vec(),
+                None,
+            );
+            assert!(deserialize(&page_, &descriptor).is_err());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::convert::TryInto;
+
+use crate::encoding::{byte_stream_split, delta_bitpacked, Encoding};
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer, DataPage, PrimitivePageDict};
+use crate::schema::types::PhysicalType;
+use crate::types::NativeType;
+
+use super::{
+    check_physical_type, delta_bitpacked_length, dict_indices, index_out_of_bounds,
+    missing_dictionary, not_supported, validity, zip_validity,
+};
+
+fn from_le_bytes<T: NativeType>(bytes: &[u8]) -> T {
+    match bytes.try_into() {
+        Ok(bytes) => T::from_le_bytes(bytes),
+        // infalible by construction: `bytes` has the size of `T`
+        Err(_) => unreachable!(),
+    }
+}
+
+fn decode_plain<T: NativeType>(values: &[u8], num_values: usize) -> Result<Vec<T>> {
+    let size = std::mem::size_of::<T>();
+    if values.len() < num_values * size {
+        return Err(ParquetError::OutOfSpec(
+            "The page has less values than its definition levels declare".to_string(),
+        ));
+    }
+    Ok(values
+        .chunks_exact(size)
+        .take(num_values)
+        .map(from_le_bytes)
+        .collect())
+}
+
+fn decode_dict<T: NativeType>(page: &DataPage, values: &[u8], num_values: usize) -> Result<Vec<T>> {
+    let dict = page
+        .dictionary_page()
+        .and_then(|dict| dict.as_any().downcast_ref::<PrimitivePageDict<T>>())
+        .ok_or_else(missing_dictionary)?;
+    let dict = dict.values();
+
+    dict_indices(values, num_values)?
+        .map(|index| {
+            dict.get(index as usize)
+                .copied()
+                .ok_or_else(|| index_out_of_bounds(index))
+        })
+        .collect()
+}
+
+/// Deserializes a page of a column whose physical type is `T`.
+/// # Error
+/// Errors iff the physical type of `descriptor` is not `T`, the page's encoding is not
+/// supported for `T`, or the page is out of spec.
+pub fn deserialize_primitive<T: NativeType>(
+    page: &DataPage,
+    descriptor: &ColumnDescriptor,
+) -> Result<Vec<Option<T>>> {
+    check_physical_type(descriptor, &T::TYPE)?;
+
+    let (_, def_levels, values) = split_buffer(page, descriptor);
+    let validity = validity(page, descriptor, def_levels)?;
+    let num_values = validity.iter().filter(|x| **x).count();
+
+    let values = match (page.encoding(), T::TYPE) {
+        (Encoding::Plain, _) => decode_plain(values, num_values)?,
+        (Encoding::PlainDictionary, _) | (Encoding::RleDictionary, _) => {
+            decode_dict(page, values, num_values)?
+        }
+        (Encoding::ByteStreamSplit, _) => byte_stream_split::Decoder::<T>::try_new(values)?
+            .take(num_values)
+            .collect(),
+        (Encoding::DeltaBinaryPacked, PhysicalType::Int32)
+        | (Encoding::DeltaBinaryPacked, PhysicalType::Int64) => {
+            // the first bytes of a little-endian i64 are the (wrapped) i32
+            let size = std::mem::size_of::<T>();
+            delta_bitpacked_length(values)?;
+            delta_bitpacked::Decoder::new(values)
+                .take(num_values)
+                .map(|value| from_le_bytes(&value.to_le_bytes()[..size]))
+                .collect()
+        }
+        _ => return Err(not_supported(page, descriptor)),
+    };
+    zip_validity(&validity, values)
+}
@@ -0,0 +1,227 @@
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
+
+use crate::compression::{CodecRegistry, Compression};
+use crate::error::{ParquetError, Result};
+use crate::indexes::FilteredPage;
+use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
+use crate::page::{CompressedDataPage, DictPage, PageType, ParquetPageHeader};
+
+use super::page_iterator::{finish_page, validate_page_sizes, FinishedPage};
+
+/// A fallible [`Iterator`] of [`CompressedDataPage`] that only reads the pages of a column chunk
+/// selected via its offset index, seeking over all the others.
+///
+/// The dictionary page, when it exists, is always read. Every page of this iterator has
+/// [`Some`] [`CompressedDataPage::selected_rows`].
+pub struct IndexedPageReader<R: Read + Seek> {
+    // The source
+    reader: R,
+
+    compression: Compression,
+
+    descriptor: ColumnDescriptor,
+
+    // The offset of the dictionary page, when it may exist. It is read before the first page.
+    dictionary_offset: Option<u64>,
+
+    // Arc: it will be shared between multiple pages and pages should be Send + Sync.
+    current_dictionary: Option<Arc<dyn DictPage>>,
+
+    // The pages still to be read
+    pages: VecDeque<FilteredPage>,
+
+    // The codecs used to decompress the dictionary page.
+    codecs: CodecRegistry,
+
+    // The maximum compressed and uncompressed size of a page, if any.
+    max_page_size: Option<usize>,
+
+    // The currently allocated buffer.
+    buffer: Vec<u8>,
+}
+
+fn read_page_header<R: Read>(reader: &mut R) -> Result<ParquetPageHeader> {
+    let mut prot = TCompactInputProtocol::new(reader);
+    Ok(ParquetPageHeader::read_from_in_protocol(&mut prot)?)
+}
+
+/// Reads `page` into `buffer` and returns its header.
+/// # Error
+/// Errors iff the page is out of spec or its header and data do not have the length declared by
+/// the offset index.
+fn read_next_page<R: Read + Seek>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    page: &FilteredPage,
+    max_page_size: Option<usize>,
+) -> Result<ParquetPageHeader> {
+    reader.seek(SeekFrom::Start(page.start))?;
+    let page_header = read_page_header(reader)?;
+    validate_page_sizes(&page_header, max_page_size)?;
+
+    let header_size = reader.stream_position()? - page.start;
+    let read_size = page_header.compressed_page_size as usize;
+    if header_size + read_size as u64 != page.length as u64 {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The page at offset {} has {} bytes, but the offset index declares {}",
+            page.start,
+            header_size + read_size as u64,
+            page.length
+        )));
+    }
+
+    if read_size > buffer.len() {
+        // dealloc and ignore region, replacing it by a new region
+        *buffer = vec![0; read_size]
+    } else {
+        buffer.truncate(read_size);
+    }
+    reader.read_exact(buffer)?;
+    Ok(page_header)
+}
+
+impl<R: Read + Seek> IndexedPageReader<R> {
+    /// Returns a new [`IndexedPageReader`] that reads the `pages` of `column_chunk` from `reader`.
+    /// `pages` are usually computed via [`crate::indexes::select_pages`].
+    /// # Error
+    /// Errors iff `column_chunk` is encrypted, as its pages cannot be read via the offset index.
+    pub fn new(
+        reader: R,
+        column_chunk: &ColumnChunkMetaData,
+        pages: Vec<FilteredPage>,
+        buffer: Vec<u8>,
+    ) -> Result<Self> {
+        if column_chunk.is_encrypted() {
+            return Err(ParquetError::General(
+                "Reading the pages of an encrypted column chunk via its offset index is not supported"
+                    .to_string(),
+            ));
+        }
+
+        // the dictionary page, when it exists, is the first page of the column chunk and
+        // thus precedes all data pages.
+        let (column_start, _) = column_chunk.byte_range();
+        let dictionary_offset = column_chunk
+            .dictionary_page_offset()
+            .map(|offset| offset as u64)
+            .or_else(|| {
+                let first_page = pages.first().map(|page| page.start);
+                if first_page > Some(column_start) {
+                    Some(column_start)
+                } else {
+                    None
+                }
+            });
+
+        Ok(Self {
+            reader,
+            compression: column_chunk.compression(),
+            descriptor: column_chunk.descriptor().clone(),
+            dictionary_offset,
+            current_dictionary: None,
+            pages: pages.into(),
+            codecs: CodecRegistry::default(),
+            max_page_size: None,
+            buffer,
+        })
+    }
+
+    /// Sets the [`CodecRegistry`] used to decompress the dictionary page of this column chunk.
+    pub fn with_codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Sets the maximum compressed and uncompressed size of the pages of this column chunk.
+    /// See [`crate::read::PageIterator::with_max_page_size`].
+    pub fn with_max_page_size(mut self, max_page_size: usize) -> Self {
+        self.max_page_size = Some(max_page_size);
+        self
+    }
+
+    fn read_dictionary(&mut self, offset: u64) -> Result<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        let page_header = read_page_header(&mut self.reader)?;
+        validate_page_sizes(&page_header, self.max_page_size)?;
+        if !matches!(page_header.type_.try_into(), Ok(PageType::DictionaryPage)) {
+            // the column chunk has no dictionary page
+            return Ok(());
+        }
+
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let read_size = page_header.compressed_page_size as usize;
+        buffer.resize(read_size, 0);
+        self.reader.read_exact(&mut buffer)?;
+
+        let result = finish_page(
+            page_header,
+            &mut buffer,
+            self.compression,
+            &None,
+            &self.descriptor,
+            &self.codecs,
+        );
+        self.buffer = buffer;
+        if let FinishedPage::Dict(dict) = result? {
+            self.current_dictionary = Some(dict);
+        }
+        Ok(())
+    }
+
+    fn read_page(&mut self, page: FilteredPage) -> Result<CompressedDataPage> {
+        let mut buffer = std::mem::take(&mut self.buffer);
+        let page_header = read_next_page(&mut self.reader, &mut buffer, &page, self.max_page_size)?;
+
+        let result = finish_page(
+            page_header,
+            &mut buffer,
+            self.compression,
+            &self.current_dictionary,
+            &self.descriptor,
+            &self.codecs,
+        )?;
+
+        match result {
+            FinishedPage::Data(mut data_page) => {
+                data_page.selected_rows = Some(page.selected_rows);
+                Ok(data_page)
+            }
+            _ => {
+                // take back the buffer, as it is not owned by any page
+                self.buffer = buffer;
+                Err(ParquetError::OutOfSpec(
+                    "The offset index of a column chunk must only point to data pages".to_string(),
+                ))
+            }
+        }
+    }
+
+    pub fn reuse_buffer(&mut self, buffer: Vec<u8>) {
+        self.buffer = buffer;
+    }
+
+    pub fn into_inner(self) -> (R, Vec<u8>) {
+        (self.reader, self.buffer)
+    }
+}
+
+impl<R: Read + Seek> Iterator for IndexedPageReader<R> {
+    type Item = Result<CompressedDataPage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let page = self.pages.pop_front()?;
+
+        if let Some(offset) = self.dictionary_offset.take() {
+            if let Err(e) = self.read_dictionary(offset) {
+                return Some(Err(e));
+            }
+        }
+
+        Some(self.read_page(page))
+    }
+}
@@ -0,0 +1,96 @@
+use parquet_format_async_temp::ColumnIndex;
+
+use crate::error::Result;
+use crate::indexes::{BooleanIndex, ByteIndex, FixedLenByteIndex, Index, NativeIndex};
+use crate::schema::types::PhysicalType;
+
+/// Deserializes a [`ColumnIndex`] into its typed representation based on `physical_type`.
+pub fn deserialize(index: ColumnIndex, physical_type: &PhysicalType) -> Result<Box<dyn Index>> {
+    Ok(match physical_type {
+        PhysicalType::Boolean => Box::new(BooleanIndex::try_new(index)?),
+        PhysicalType::Int32 => Box::new(NativeIndex::<i32>::try_new(index)?),
+        PhysicalType::Int64 => Box::new(NativeIndex::<i64>::try_new(index)?),
+        PhysicalType::Int96 => Box::new(NativeIndex::<[u32; 3]>::try_new(index)?),
+        PhysicalType::Float => Box::new(NativeIndex::<f32>::try_new(index)?),
+        PhysicalType::Double => Box::new(NativeIndex::<f64>::try_new(index)?),
+        PhysicalType::ByteArray => Box::new(ByteIndex::try_new(index)?),
+        PhysicalType::FixedLenByteArray(size) => {
+            Box::new(FixedLenByteIndex::try_new(index, *size)?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::indexes::{BoundaryOrder, PageIndex};
+
+    use parquet_format_async_temp::BoundaryOrder as ParquetBoundaryOrder;
+
+    #[test]
+    fn native() -> Result<()> {
+        let index = ColumnIndex {
+            null_pages: vec![false, true],
+            min_values: vec![1i32.to_le_bytes().to_vec(), vec![]],
+            max_values: vec![10i32.to_le_bytes().to_vec(), vec![]],
+            boundary_order: ParquetBoundaryOrder::ASCENDING,
+            null_counts: Some(vec![0, 5]),
+        };
+
+        let index = deserialize(index, &PhysicalType::Int32)?;
+        let index = index.as_any().downcast_ref::<NativeIndex<i32>>().unwrap();
+
+        assert_eq!(index.boundary_order, BoundaryOrder::Ascending);
+        assert_eq!(
+            index.indexes,
+            vec![
+                PageIndex {
+                    min: Some(1),
+                    max: Some(10),
+                    null_count: Some(0)
+                },
+                PageIndex {
+                    min: None,
+                    max: None,
+                    null_count: Some(5)
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_plain() {
+        let index = ColumnIndex {
+            null_pages: vec![false],
+            min_values: vec![vec![1]],
+            max_values: vec![vec![2]],
+            boundary_order: ParquetBoundaryOrder::UNORDERED,
+            null_counts: None,
+        };
+
+        assert!(deserialize(index, &PhysicalType::Int64).is_err());
+    }
+
+    #[test]
+    fn invalid_lengths() {
+        let index = ColumnIndex {
+            null_pages: vec![false, true],
+            min_values: vec![vec![1]],
+            max_values: vec![vec![2]],
+            boundary_order: ParquetBoundaryOrder::UNORDERED,
+            null_counts: Some(vec![0]),
+        };
+        for physical_type in [PhysicalType::Boolean, PhysicalType::ByteArray] {
+            assert!(deserialize(index.clone(), &physical_type).is_err());
+        }
+
+        let index = ColumnIndex {
+            null_pages: vec![false],
+            null_counts: Some(vec![0, 1]),
+            ..index
+        };
+        assert!(deserialize(index, &PhysicalType::FixedLenByteArray(1)).is_err());
+    }
+}
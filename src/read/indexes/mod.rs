@@ -0,0 +1,4 @@
+mod deserialize;
+mod read;
+
+pub use read::{read_columns_indexes, read_pages_locations};
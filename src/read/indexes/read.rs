@@ -0,0 +1,153 @@
+use std::convert::TryInto;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use parquet_format_async_temp::{
+    thrift::protocol::TCompactInputProtocol, ColumnIndex, OffsetIndex, PageLocation,
+};
+
+use crate::encryption::ModuleType;
+use crate::error::{ParquetError, Result};
+use crate::indexes::Index;
+use crate::metadata::ColumnChunkMetaData;
+
+use super::deserialize::deserialize;
+
+/// Returns the region `(start, length)` covering all `ranges`, or `None` if none exists.
+fn covering_range(ranges: &[Option<(u64, u64)>]) -> Option<(u64, u64)> {
+    let start = ranges.iter().flatten().map(|(start, _)| *start).min()?;
+    let end = ranges
+        .iter()
+        .flatten()
+        .map(|(start, length)| start + length)
+        .max()?;
+    Some((start, end - start))
+}
+
+fn to_range(offset: Option<i64>, length: Option<i32>) -> Result<Option<(u64, u64)>> {
+    offset
+        .zip(length)
+        .map(|(offset, length)| {
+            let offset: u64 = offset.try_into().map_err(|_| {
+                ParquetError::OutOfSpec("The offset of an index must be positive".to_string())
+            })?;
+            let length: u64 = length.try_into().map_err(|_| {
+                ParquetError::OutOfSpec("The length of an index must be positive".to_string())
+            })?;
+            offset.checked_add(length).ok_or_else(|| {
+                ParquetError::OutOfSpec("The end of an index must fit in 64 bits".to_string())
+            })?;
+            Ok((offset, length))
+        })
+        .transpose()
+}
+
+/// Reads the region of `reader` covering all `ranges` in a single read, and returns
+/// one slice per range.
+/// # Error
+/// Errors if the region is not within `reader`, as the ranges are read from the file.
+fn read_ranges<R: Read + Seek>(
+    reader: &mut R,
+    ranges: &[Option<(u64, u64)>],
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let (start, length) = if let Some(range) = covering_range(ranges) {
+        range
+    } else {
+        return Ok(vec![None; ranges.len()]);
+    };
+
+    // validated before allocating the region
+    let file_length = reader.seek(SeekFrom::End(0))?;
+    if start + length > file_length {
+        return Err(ParquetError::OutOfSpec(format!(
+            "The indexes end at byte {}, but the file has {} bytes",
+            start + length,
+            file_length
+        )));
+    }
+
+    reader.seek(SeekFrom::Start(start))?;
+    let mut data = vec![0; length as usize];
+    reader.read_exact(&mut data)?;
+
+    Ok(ranges
+        .iter()
+        .map(|range| {
+            range.map(|(offset, length)| {
+                let offset = (offset - start) as usize;
+                data[offset..offset + length as usize].to_vec()
+            })
+        })
+        .collect())
+}
+
+/// Decrypts the index `data` of `chunk`, a module of `type_`, when the chunk is encrypted.
+fn decrypt_index(data: Vec<u8>, chunk: &ColumnChunkMetaData, type_: ModuleType) -> Result<Vec<u8>> {
+    match chunk.cipher() {
+        Some(cipher) => cipher.cipher.decrypt(cipher.module(type_, 0), &data),
+        None if chunk.is_encrypted() => Err(general_err!(
+            "The indexes of an encrypted column chunk can only be read with its key"
+        )),
+        None => Ok(data),
+    }
+}
+
+/// Reads the column indexes of each of the column chunks `chunks`.
+/// The column indexes of a row group are usually contiguous and are thus read in a single
+/// operation.
+/// The result has one item per chunk, which is `None` when the chunk has no column index.
+/// Indexes of encrypted column chunks are decrypted, which requires the metadata to have been
+/// read with [`crate::read::read_metadata_with_decryption`].
+pub fn read_columns_indexes<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Box<dyn Index>>>> {
+    let ranges = chunks
+        .iter()
+        .map(|chunk| to_range(chunk.column_index_offset(), chunk.column_index_length()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let data = read_ranges(reader, &ranges)?;
+
+    data.into_iter()
+        .zip(chunks.iter())
+        .map(|(data, chunk)| {
+            data.map(|data| {
+                let data = decrypt_index(data, chunk, ModuleType::ColumnIndex)?;
+                let mut prot = TCompactInputProtocol::new(Cursor::new(data));
+                let index = ColumnIndex::read_from_in_protocol(&mut prot)?;
+                deserialize(index, &chunk.physical_type())
+            })
+            .transpose()
+        })
+        .collect()
+}
+
+/// Reads the page locations (from the offset index) of each of the column chunks `chunks`.
+/// The offset indexes of a row group are usually contiguous and are thus read in a single
+/// operation.
+/// The result has one item per chunk, which is `None` when the chunk has no offset index.
+/// Indexes of encrypted column chunks are decrypted, as in [`read_columns_indexes`].
+pub fn read_pages_locations<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[ColumnChunkMetaData],
+) -> Result<Vec<Option<Vec<PageLocation>>>> {
+    let ranges = chunks
+        .iter()
+        .map(|chunk| to_range(chunk.offset_index_offset(), chunk.offset_index_length()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let data = read_ranges(reader, &ranges)?;
+
+    data.into_iter()
+        .zip(chunks.iter())
+        .map(|(data, chunk)| {
+            data.map(|data| {
+                let data = decrypt_index(data, chunk, ModuleType::OffsetIndex)?;
+                let mut prot = TCompactInputProtocol::new(Cursor::new(data));
+                let offset = OffsetIndex::read_from_in_protocol(&mut prot)?;
+                Ok(offset.page_locations)
+            })
+            .transpose()
+        })
+        .collect()
+}
@@ -22,13 +22,18 @@ use std::{
 };
 
 use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
-use parquet_format_async_temp::{ColumnOrder as TColumnOrder, FileMetaData as TFileMetaData};
+use parquet_format_async_temp::{
+    ColumnOrder as TColumnOrder, FileCryptoMetaData, FileMetaData as TFileMetaData,
+};
 
 use super::super::metadata::get_sort_order;
 use super::super::metadata::ColumnOrder;
 use super::super::metadata::SchemaDescriptor;
-use super::super::{metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC};
+use super::super::{
+    metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED,
+};
 
+use crate::encryption::{ColumnCipher, FileDecryptionProperties, FileDecryptor, Module};
 use crate::error::{ParquetError, Result};
 use crate::schema::types::ParquetType;
 
@@ -51,6 +56,84 @@ fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error>
 }
 
 /// Reads a file's metadata.
+///
+/// Files whose footer is encrypted must be read with [`read_metadata_with_decryption`].
+pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+    let (metadata, encrypted_footer) = read_metadata_bytes(reader)?;
+    if encrypted_footer {
+        return Err(general_err!(
+            "The footer of this file is encrypted; it must be read with decryption properties"
+        ));
+    }
+
+    let mut prot = TCompactInputProtocol::new(Cursor::new(metadata));
+    let t_file_metadata = TFileMetaData::read_from_in_protocol(&mut prot)
+        .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?;
+    from_thrift(t_file_metadata, None)
+}
+
+/// Reads the metadata of a file that may be encrypted, decrypting it with `properties`.
+///
+/// The ciphers of the encrypted column chunks are attached to the returned metadata, so that
+/// the pages read via [`super::get_page_iterator`] are decrypted.
+/// Column chunks whose key is not available in `properties` are left encrypted: their pages and
+/// indexes cannot be read and, when the footer is encrypted, neither can their metadata.
+/// This requires the `encryption` feature.
+pub fn read_metadata_with_decryption<R: Read + Seek>(
+    reader: &mut R,
+    properties: &FileDecryptionProperties,
+) -> Result<FileMetaData> {
+    let (metadata, encrypted_footer) = read_metadata_bytes(reader)?;
+    let mut reader = Cursor::new(metadata);
+
+    let parse_error = |e| ParquetError::General(format!("Could not parse metadata: {}", e));
+
+    let (mut t_file_metadata, decryptor, key_metadata) = if encrypted_footer {
+        // SPEC: the crypto metadata is followed by the encrypted metadata
+        let mut prot = TCompactInputProtocol::new(&mut reader);
+        let crypto_metadata =
+            FileCryptoMetaData::read_from_in_protocol(&mut prot).map_err(parse_error)?;
+        let decryptor = FileDecryptor::try_new(properties, &crypto_metadata.encryption_algorithm)?;
+
+        let start = reader.position() as usize;
+        let metadata = decryptor
+            .footer(crypto_metadata.key_metadata.as_deref())?
+            .decrypt(Module::footer(), &reader.get_ref()[start..])?;
+
+        let mut prot = TCompactInputProtocol::new(Cursor::new(metadata));
+        let t_file_metadata =
+            TFileMetaData::read_from_in_protocol(&mut prot).map_err(parse_error)?;
+        (t_file_metadata, decryptor, crypto_metadata.key_metadata)
+    } else {
+        let mut prot = TCompactInputProtocol::new(&mut reader);
+        let t_file_metadata =
+            TFileMetaData::read_from_in_protocol(&mut prot).map_err(parse_error)?;
+        let algorithm = if let Some(algorithm) = &t_file_metadata.encryption_algorithm {
+            algorithm
+        } else {
+            // not encrypted
+            return from_thrift(t_file_metadata, None);
+        };
+        let decryptor = FileDecryptor::try_new(properties, algorithm)?;
+        let key_metadata = t_file_metadata.footer_signing_key_metadata.clone();
+
+        if properties.verify_plaintext_footer {
+            // SPEC: the plaintext metadata is followed by its signature
+            let end = reader.position() as usize;
+            let (metadata, signature) = reader.get_ref().split_at(end);
+            decryptor
+                .footer(key_metadata.as_deref())?
+                .verify(metadata, signature)?;
+        }
+        (t_file_metadata, decryptor, key_metadata)
+    };
+
+    let ciphers =
+        decryptor.decrypt_row_groups(&mut t_file_metadata.row_groups, key_metadata.as_deref())?;
+    from_thrift(t_file_metadata, Some(ciphers))
+}
+
+/// Returns the (serialized) metadata of the file and whether its footer is encrypted.
 // Layout of Parquet file
 // +---------------------------+-----+---+
 // |      Rest of file         |  B  | A |
@@ -59,7 +142,7 @@ fn stream_len(seek: &mut impl Seek) -> std::result::Result<u64, std::io::Error>
 //
 // The reader first reads DEFAULT_FOOTER_SIZE bytes from the end of the file.
 // If it is not enough according to the length indicated in the footer, it reads more bytes.
-pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
+fn read_metadata_bytes<R: Read + Seek>(reader: &mut R) -> Result<(Vec<u8>, bool)> {
     // check file is large enough to hold footer
     let file_size = stream_len(reader)?;
     if file_size < FOOTER_SIZE {
@@ -75,9 +158,14 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
     reader.read_exact(&mut default_len_end_buf)?;
 
     // check this is indeed a parquet file
-    if default_len_end_buf[default_end_len - 4..] != PARQUET_MAGIC {
+    let magic = &default_len_end_buf[default_end_len - 4..];
+    let encrypted_footer = if magic == PARQUET_MAGIC {
+        false
+    } else if magic == PARQUET_MAGIC_ENCRYPTED {
+        true
+    } else {
         return Err(general_err!("Invalid Parquet file. Corrupt footer"));
-    }
+    };
 
     let metadata_len = metadata_len(&default_len_end_buf, default_end_len);
 
@@ -89,41 +177,59 @@ pub fn read_metadata<R: Read + Seek>(reader: &mut R) -> Result<FileMetaData> {
     }
     let footer_metadata_len = FOOTER_SIZE + metadata_len as u64;
 
-    let t_file_metadata = if footer_metadata_len > file_size {
+    let metadata = if footer_metadata_len > file_size {
         return Err(general_err!(
             "Invalid Parquet file. Metadata start is less than zero ({})",
             file_size as i64 - footer_metadata_len as i64
         ));
     } else if footer_metadata_len < DEFAULT_FOOTER_READ_SIZE {
         // the whole metadata is in the bytes we already read
-        // build up the reader covering the entire metadata
-        let mut reader = Cursor::new(default_len_end_buf);
-        reader.seek(SeekFrom::End(-(footer_metadata_len as i64)))?;
-
-        let mut prot = TCompactInputProtocol::new(reader);
-        TFileMetaData::read_from_in_protocol(&mut prot)
+        let start = default_end_len - footer_metadata_len as usize;
+        default_len_end_buf.truncate(default_end_len - FOOTER_SIZE as usize);
+        default_len_end_buf.drain(..start);
+        default_len_end_buf
     } else {
         // the end of file read by default is not long enough, read again including all metadata.
         reader.seek(SeekFrom::End(-(footer_metadata_len as i64)))?;
+        let mut metadata = vec![0; metadata_len as usize];
+        reader.read_exact(&mut metadata)?;
+        metadata
+    };
+    Ok((metadata, encrypted_footer))
+}
 
-        let mut prot = TCompactInputProtocol::new(reader);
-        TFileMetaData::read_from_in_protocol(&mut prot)
-    }
-    .map_err(|e| ParquetError::General(format!("Could not parse metadata: {}", e)))?;
-
+fn from_thrift(
+    t_file_metadata: TFileMetaData,
+    ciphers: Option<Vec<Vec<Option<ColumnCipher>>>>,
+) -> Result<FileMetaData> {
     let schema = t_file_metadata.schema.iter().collect::<Vec<_>>();
     let schema_descr = SchemaDescriptor::try_from_thrift(&schema)?;
 
-    let row_groups = t_file_metadata
+    let mut row_groups = t_file_metadata
         .row_groups
         .into_iter()
         .map(|rg| RowGroupMetaData::try_from_thrift(&schema_descr, rg))
         .collect::<Result<Vec<_>>>()?;
 
+    if let Some(ciphers) = ciphers {
+        for (row_group, ciphers) in row_groups.iter_mut().zip(ciphers) {
+            for (column, cipher) in row_group.columns_mut().iter_mut().zip(ciphers) {
+                column.set_cipher(cipher);
+            }
+        }
+    }
+
     // compute and cache column orders
     let column_orders = t_file_metadata
         .column_orders
         .map(|orders| parse_column_orders(&orders, &schema_descr));
+    if let Some(column_orders) = &column_orders {
+        for row_group in row_groups.iter_mut() {
+            for (column, order) in row_group.columns_mut().iter_mut().zip(column_orders) {
+                column.set_column_order(*order);
+            }
+        }
+    }
 
     Ok(FileMetaData::new(
         t_file_metadata.version,
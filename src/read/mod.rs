@@ -1,9 +1,14 @@
 mod compression;
+pub mod deserialize;
+mod indexed_reader;
+mod indexes;
 pub mod levels;
 mod metadata;
+pub mod nested;
 mod page_iterator;
 #[cfg(feature = "stream")]
 mod page_stream;
+pub mod predicate;
 #[cfg(feature = "stream")]
 mod stream;
 
@@ -11,17 +16,24 @@ use std::io::{Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::vec::IntoIter;
 
-pub use compression::{decompress, BasicDecompressor, Decompressor};
-pub use metadata::read_metadata;
+pub use compression::{
+    decompress, decompress_buffer, decompress_buffer_with_codecs, decompress_with_codecs,
+    BasicDecompressor, Decompressor,
+};
+pub use indexed_reader::IndexedPageReader;
+pub use indexes::{read_columns_indexes, read_pages_locations};
+pub use metadata::{read_metadata, read_metadata_with_decryption};
 pub use page_iterator::{PageFilter, PageIterator};
 #[cfg(feature = "stream")]
-pub use page_stream::get_page_stream;
+pub use page_stream::{get_page_stream, get_page_stream_with_codecs};
 #[cfg(feature = "stream")]
 pub use stream::read_metadata as read_metadata_async;
 
 use crate::error::ParquetError;
+use crate::indexes::FilteredPage;
 use crate::metadata::{ColumnChunkMetaData, RowGroupMetaData};
 use crate::page::CompressedDataPage;
+use crate::read::predicate::Predicate;
 use crate::schema::types::ParquetType;
 use crate::{error::Result, metadata::FileMetaData};
 
@@ -42,13 +54,36 @@ pub fn filter_row_groups(
     metadata
 }
 
+/// Filters row group metadata to only those row groups whose statistics do not rule out
+/// that `predicate` is true for some of their rows
+/// # Errors
+/// Errors if the predicate cannot be evaluated, see [`Predicate::evaluate`].
+pub fn prune_row_groups(metadata: &FileMetaData, predicate: &Predicate) -> Result<FileMetaData> {
+    let mut filtered_row_groups = Vec::<RowGroupMetaData>::new();
+    for row_group_metadata in metadata.row_groups.iter() {
+        if predicate.evaluate(metadata, row_group_metadata)? != Some(false) {
+            filtered_row_groups.push(row_group_metadata.clone());
+        }
+    }
+    let mut metadata = metadata.clone();
+    metadata.row_groups = filtered_row_groups;
+    Ok(metadata)
+}
+
 /// Returns a new [`PageIterator`] by seeking `reader` to the begining of `column_chunk`.
+/// # Error
+/// Errors if `column_chunk` is encrypted and its key was not available when reading the metadata.
 pub fn get_page_iterator<R: Read + Seek>(
     column_chunk: &ColumnChunkMetaData,
     mut reader: R,
     pages_filter: Option<PageFilter>,
     buffer: Vec<u8>,
 ) -> Result<PageIterator<R>> {
+    if column_chunk.is_encrypted() && column_chunk.cipher().is_none() {
+        return Err(general_err!(
+            "The pages of an encrypted column chunk can only be read with its key"
+        ));
+    }
     let pages_filter = pages_filter.unwrap_or_else(|| Arc::new(|_, _| true));
 
     let (col_start, _) = column_chunk.byte_range();
@@ -60,7 +95,25 @@ pub fn get_page_iterator<R: Read + Seek>(
         column_chunk.descriptor().clone(),
         pages_filter,
         buffer,
-    ))
+    )
+    .with_cipher(column_chunk.cipher().cloned()))
+}
+
+/// Returns a new [`IndexedPageReader`] that only reads the `pages` of `column_chunk`,
+/// seeking `reader` directly to each of them.
+///
+/// `pages` can be computed from a set of row intervals and the column chunk's page locations
+/// (see [`read_pages_locations`]) via [`crate::indexes::select_pages`].
+/// The dictionary page, when it exists, is always read.
+/// # Error
+/// Errors iff the column chunk is encrypted, which is not supported.
+pub fn get_indexed_page_iterator<R: Read + Seek>(
+    column_chunk: &ColumnChunkMetaData,
+    reader: R,
+    pages: Vec<FilteredPage>,
+    buffer: Vec<u8>,
+) -> Result<IndexedPageReader<R>> {
+    IndexedPageReader::new(reader, column_chunk, pages, buffer)
 }
 
 /// Returns an [`Iterator`] of [`ColumnChunkMetaData`] corresponding to the columns
@@ -0,0 +1,647 @@
+//! Reconstruction of the nesting of a column (Dremel assembly).
+//!
+//! A leaf column of a nested field stores one repetition and one definition level per value.
+//! [`reconstruct`] converts these levels into the offsets and validity of every nesting level
+//! between the top-level field of the column and its leaf, following the Parquet spec on
+//! [nested types](https://github.com/apache/parquet-format/blob/master/LogicalTypes.md#nested-types):
+//! * a `LIST`-annotated group (in its 3-level or legacy 2-level layout), a `MAP`-annotated group
+//!   and a repeated field become a [`Nested::List`];
+//! * the `key_value` group of a map, the elements of a repeated group and any other group
+//!   become a [`Nested::Struct`];
+//! * the leaf becomes a [`Nested::Primitive`].
+//!
+//! The non-null values of the leaf are the `Some` values returned by
+//! [`super::deserialize::deserialize`] for the same page(s), in order.
+use crate::encoding::hybrid_rle::HybridRleDecoder;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{split_buffer, DataPage};
+use crate::schema::types::{GroupConvertedType, LogicalType, ParquetType};
+use crate::schema::Repetition;
+
+use super::levels::get_bit_width;
+
+/// A nesting level of a column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nested {
+    /// A list whose `i`th slot contains the items `offsets[i]..offsets[i + 1]` of the next level.
+    List {
+        offsets: Vec<i64>,
+        validity: Option<Vec<bool>>,
+    },
+    /// A struct with `length` slots. Its children have as many slots.
+    Struct {
+        length: usize,
+        validity: Option<Vec<bool>>,
+    },
+    /// The leaf, with `length` slots.
+    Primitive {
+        length: usize,
+        validity: Option<Vec<bool>>,
+    },
+}
+
+impl Nested {
+    /// The number of slots of this level.
+    pub fn len(&self) -> usize {
+        match self {
+            Nested::List { offsets, .. } => offsets.len().saturating_sub(1),
+            Nested::Struct { length, .. } => *length,
+            Nested::Primitive { length, .. } => *length,
+        }
+    }
+
+    /// Whether this level has no slots.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether each slot of this level is non-null, or `None` if the level is not nullable.
+    pub fn validity(&self) -> Option<&[bool]> {
+        match self {
+            Nested::List { validity, .. } => validity.as_deref(),
+            Nested::Struct { validity, .. } => validity.as_deref(),
+            Nested::Primitive { validity, .. } => validity.as_deref(),
+        }
+    }
+}
+
+/// A node on the path from the top-level field of a column to its leaf.
+struct Node<'a> {
+    type_: &'a ParquetType,
+    /// the number of repeated nodes above this node
+    rep_level: u32,
+    /// the definition level of this node's nearest repeated ancestor (0 if none)
+    parent_def_level: u32,
+    /// the definition level at which this node is defined (i.e. non-null or non-empty)
+    def_level: u32,
+    /// the number of slots (non-repeated nodes)
+    length: usize,
+    /// the validity of each slot (optional nodes)
+    validity: Vec<bool>,
+    /// the number of items of each slot (repeated nodes)
+    lengths: Vec<usize>,
+}
+
+impl<'a> Node<'a> {
+    fn repetition(&self) -> &Repetition {
+        self.type_.get_basic_info().repetition()
+    }
+
+    fn validity(&self) -> Option<Vec<bool>> {
+        match self.repetition() {
+            Repetition::Optional => Some(self.validity.clone()),
+            _ => None,
+        }
+    }
+
+    fn offsets(&self) -> Vec<i64> {
+        let mut offsets = Vec::with_capacity(self.lengths.len() + 1);
+        offsets.push(0);
+        let mut offset = 0;
+        for length in &self.lengths {
+            offset += *length as i64;
+            offsets.push(offset);
+        }
+        offsets
+    }
+
+    /// The items of a repeated node
+    fn items(&self) -> Nested {
+        item(self.type_, self.lengths.iter().sum(), None)
+    }
+
+    fn push(&mut self, rep: u32, def: u32) -> Result<()> {
+        let is_repeated = self.repetition() == &Repetition::Repeated;
+        if rep <= self.rep_level {
+            // a new slot of this node, iff its parent has one
+            if def >= self.parent_def_level {
+                if is_repeated {
+                    self.lengths.push((def >= self.def_level) as usize);
+                } else {
+                    self.length += 1;
+                    self.validity.push(def >= self.def_level);
+                }
+            }
+        } else if is_repeated && rep == self.rep_level + 1 {
+            // a new item of the current slot of this node
+            let length = self.lengths.last_mut().ok_or_else(|| {
+                ParquetError::OutOfSpec(
+                    "The first repetition level of a column must be 0".to_string(),
+                )
+            })?;
+            *length += 1;
+        }
+        Ok(())
+    }
+}
+
+fn item(type_: &ParquetType, length: usize, validity: Option<Vec<bool>>) -> Nested {
+    match type_ {
+        ParquetType::GroupType { .. } => Nested::Struct { length, validity },
+        ParquetType::PrimitiveType { .. } => Nested::Primitive { length, validity },
+    }
+}
+
+fn is_list(type_: &ParquetType) -> bool {
+    matches!(
+        type_,
+        ParquetType::GroupType {
+            converted_type: Some(GroupConvertedType::List),
+            ..
+        } | ParquetType::GroupType {
+            logical_type: Some(LogicalType::LIST(_)),
+            ..
+        }
+    )
+}
+
+fn is_map(type_: &ParquetType) -> bool {
+    matches!(
+        type_,
+        ParquetType::GroupType {
+            converted_type: Some(GroupConvertedType::Map),
+            ..
+        } | ParquetType::GroupType {
+            converted_type: Some(GroupConvertedType::MapKeyValue),
+            ..
+        } | ParquetType::GroupType {
+            logical_type: Some(LogicalType::MAP(_)),
+            ..
+        }
+    )
+}
+
+/// Whether `repeated`, the repeated field of the `LIST`-annotated group `list`, is itself the
+/// element of the list (legacy 2-level layout), as opposed to the group of the element.
+fn is_legacy_element(list: &ParquetType, repeated: &ParquetType) -> bool {
+    // SPEC: backward-compatibility rules of LIST
+    match repeated {
+        ParquetType::PrimitiveType { .. } => true,
+        ParquetType::GroupType { fields, .. } => {
+            fields.len() > 1
+                || repeated.name() == "array"
+                || repeated.name() == format!("{}_tuple", list.name())
+        }
+    }
+}
+
+/// Returns the nodes from the top-level field of `descriptor` to its leaf
+fn path(descriptor: &ColumnDescriptor) -> Result<Vec<Node<'_>>> {
+    let mut types = vec![descriptor.base_type()];
+    for name in descriptor.path_in_schema().iter().skip(1) {
+        let field = match types[types.len() - 1] {
+            ParquetType::GroupType { fields, .. } => {
+                fields.iter().find(|field| field.name() == name)
+            }
+            _ => None,
+        };
+        types.push(field.ok_or_else(|| {
+            general_err!("The field \"{}\" is not part of the column's schema", name)
+        })?);
+    }
+
+    let mut rep_level = 0;
+    let mut def_level = 0;
+    let mut parent_def_level = 0;
+    let nodes = types
+        .into_iter()
+        .map(|type_| {
+            let node_rep_level = rep_level;
+            let node_parent_def_level = parent_def_level;
+            match type_.get_basic_info().repetition() {
+                Repetition::Required => {}
+                Repetition::Optional => def_level += 1,
+                Repetition::Repeated => {
+                    def_level += 1;
+                    rep_level += 1;
+                    parent_def_level = def_level;
+                }
+            }
+            Node {
+                type_,
+                rep_level: node_rep_level,
+                parent_def_level: node_parent_def_level,
+                def_level,
+                length: 0,
+                validity: vec![],
+                lengths: vec![],
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if def_level != descriptor.max_def_level() as u32
+        || rep_level != descriptor.max_rep_level() as u32
+    {
+        return Err(ParquetError::OutOfSpec(
+            "The maximum levels of the column do not match its schema".to_string(),
+        ));
+    }
+    Ok(nodes)
+}
+
+/// Groups the (physical) nodes into (logical) nesting levels
+fn nest(nodes: Vec<Node<'_>>) -> Result<Vec<Nested>> {
+    let mut nested = vec![];
+    let mut nodes = nodes.into_iter();
+    while let Some(node) = nodes.next() {
+        let is_repeated = node.repetition() == &Repetition::Repeated;
+        if is_list(node.type_) || is_map(node.type_) {
+            let child = nodes
+                .next()
+                .filter(|child| child.repetition() == &Repetition::Repeated)
+                .filter(|_| !is_repeated)
+                .ok_or_else(|| {
+                    ParquetError::OutOfSpec(
+                        "A LIST or MAP-annotated group must be non-repeated and have a repeated field"
+                            .to_string(),
+                    )
+                })?;
+            nested.push(Nested::List {
+                offsets: child.offsets(),
+                validity: node.validity(),
+            });
+            // a map's key-value group, and a list's legacy element, are the items of the list.
+            // Otherwise, the element of the list is the next node.
+            if is_map(node.type_) || is_legacy_element(node.type_, child.type_) {
+                nested.push(child.items());
+            }
+        } else if is_repeated {
+            nested.push(Nested::List {
+                offsets: node.offsets(),
+                validity: None,
+            });
+            nested.push(node.items());
+        } else {
+            nested.push(item(node.type_, node.length, node.validity()));
+        }
+    }
+    Ok(nested)
+}
+
+/// Reconstructs the nesting levels of the column `descriptor` from its `levels`, the pairs of
+/// repetition and definition levels of its values.
+///
+/// The levels of consecutive pages of a column chunk can be chained, as long as every page
+/// starts at a new row (a repetition level of 0).
+/// # Error
+/// Errors iff the levels are out of spec for `descriptor`.
+pub fn reconstruct<I: IntoIterator<Item = (u32, u32)>>(
+    descriptor: &ColumnDescriptor,
+    levels: I,
+) -> Result<Vec<Nested>> {
+    let max_rep_level = descriptor.max_rep_level() as u32;
+    let max_def_level = descriptor.max_def_level() as u32;
+
+    let mut nodes = path(descriptor)?;
+    for (rep, def) in levels {
+        if rep > max_rep_level || def > max_def_level {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The levels ({}, {}) exceed the maximum levels of the column ({}, {})",
+                rep, def, max_rep_level, max_def_level
+            )));
+        }
+        nodes.iter_mut().try_for_each(|node| node.push(rep, def))?;
+    }
+    nest(nodes)
+}
+
+/// Returns the (empty) nesting levels of the column `descriptor`. Its optional levels have an
+/// empty validity, and its required levels none.
+pub(crate) fn shape(descriptor: &ColumnDescriptor) -> Result<Vec<Nested>> {
+    reconstruct(descriptor, std::iter::empty())
+}
+
+fn decode_levels(
+    levels: &[u8],
+    encoding: Encoding,
+    max_level: i16,
+    num_values: usize,
+) -> Result<Vec<u32>> {
+    if max_level == 0 {
+        return Ok(vec![0; num_values]);
+    }
+    match encoding {
+        Encoding::Rle => {
+            Ok(HybridRleDecoder::new(levels, get_bit_width(max_level), num_values).collect())
+        }
+        other => Err(general_err!(
+            "Decoding levels encoded as {:?} is not supported",
+            other
+        )),
+    }
+}
+
+/// Returns the pairs of repetition and definition levels of the values of `page`.
+pub fn levels(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<Vec<(u32, u32)>> {
+    let (rep_levels, def_levels, _) = split_buffer(page, descriptor);
+    let num_values = page.num_values();
+    let rep_levels = decode_levels(
+        rep_levels,
+        page.repetition_level_encoding(),
+        descriptor.max_rep_level(),
+        num_values,
+    )?;
+    let def_levels = decode_levels(
+        def_levels,
+        page.definition_level_encoding(),
+        descriptor.max_def_level(),
+        num_values,
+    )?;
+    Ok(rep_levels.into_iter().zip(def_levels).collect())
+}
+
+/// Reconstructs the nesting levels of `page`. See [`reconstruct`].
+pub fn reconstruct_page(page: &DataPage, descriptor: &ColumnDescriptor) -> Result<Vec<Nested>> {
+    reconstruct(descriptor, levels(page, descriptor)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encoding::hybrid_rle;
+    use crate::page::{DataPageHeader, DataPageHeaderV1};
+    use crate::tests::descriptor;
+
+    #[test]
+    fn list() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element;
+                    }
+                }
+            }",
+            0,
+        );
+        // [[1, None, 2], [], None, [3]]
+        let levels = vec![(0, 3), (1, 2), (1, 3), (0, 1), (0, 0), (0, 3)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 3, 3, 3, 4],
+                validity: Some(vec![true, true, false, true]),
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: Some(vec![true, false, true, true]),
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn list_of_lists() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                REQUIRED group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element (LIST) {
+                            REPEATED group list {
+                                REQUIRED INT32 element;
+                            }
+                        }
+                    }
+                }
+            }",
+            0,
+        );
+        // [[[1, 2], None], [], [[3]]]
+        let levels = vec![(0, 3), (2, 3), (1, 1), (0, 0), (0, 3)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2, 3],
+                validity: None,
+            },
+            Nested::List {
+                offsets: vec![0, 2, 2, 3],
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Primitive {
+                length: 3,
+                validity: None,
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_list() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED INT32 array;
+                }
+            }",
+            0,
+        );
+        // [[1, 2], None, []]
+        let levels = vec![(0, 2), (1, 2), (0, 0), (0, 1)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2, 2],
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Primitive {
+                length: 2,
+                validity: None,
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_list_of_structs() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group a_tuple {
+                        OPTIONAL INT32 b;
+                    }
+                }
+            }",
+            0,
+        );
+        // [[{b: 1}, {b: None}], []]
+        let levels = vec![(0, 3), (1, 2), (0, 1)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2],
+                validity: Some(vec![true, true]),
+            },
+            Nested::Struct {
+                length: 2,
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 2,
+                validity: Some(vec![true, false]),
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn repeated() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                REPEATED INT32 a;
+            }",
+            0,
+        );
+        // [[1, 2], []]
+        let levels = vec![(0, 1), (1, 1), (0, 0)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2],
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 2,
+                validity: None,
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn map() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group m (MAP) {
+                    REPEATED group key_value {
+                        REQUIRED BYTE_ARRAY key (UTF8);
+                        OPTIONAL INT32 value;
+                    }
+                }
+            }",
+            1,
+        );
+        // [{"a": 1, "b": None}, None, {}]
+        let levels = vec![(0, 3), (1, 2), (0, 0), (0, 1)];
+
+        let expected = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2, 2],
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Struct {
+                length: 2,
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 2,
+                validity: Some(vec![true, false]),
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn structs() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a {
+                    REQUIRED group b {
+                        OPTIONAL INT32 c;
+                    }
+                }
+            }",
+            0,
+        );
+        // [{b: {c: 1}}, None, {b: {c: None}}]
+        let levels = vec![(0, 2), (0, 0), (0, 1)];
+
+        let expected = vec![
+            Nested::Struct {
+                length: 3,
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Struct {
+                length: 3,
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 3,
+                validity: Some(vec![true, false, false]),
+            },
+        ];
+        assert_eq!(reconstruct(&descriptor, levels)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn page() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT32 element;
+                    }
+                }
+            }",
+            0,
+        );
+        let rep_levels = [0u32, 1, 1, 0, 0, 0];
+        let def_levels = [3u32, 2, 3, 1, 0, 3];
+
+        let mut buffer = vec![];
+        for (levels, num_bits) in [(&rep_levels, 1), (&def_levels, 2)] {
+            let mut encoded = vec![];
+            hybrid_rle::encode_u32(&mut encoded, levels.iter().copied(), num_bits).unwrap();
+            buffer.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+            buffer.extend(encoded);
+        }
+        // the values, that are not read
+        buffer.extend_from_slice(&[0; 12]);
+
+        let header = DataPageHeaderV1 {
+            num_values: 6,
+            encoding: Encoding::Plain.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics: None,
+        };
+        let page = DataPage::new(DataPageHeader::V1(header), buffer, None, descriptor.clone());
+
+        let nested = reconstruct_page(&page, &descriptor)?;
+        assert_eq!(nested[0].len(), 4);
+        assert_eq!(
+            nested[0].validity(),
+            Some([true, true, false, true].as_ref())
+        );
+        assert_eq!(
+            nested[1].validity(),
+            Some([true, false, true, true].as_ref())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let descriptor = descriptor(
+            "message schema {
+                REPEATED INT32 a;
+            }",
+            0,
+        );
+        // the first value must start a row
+        assert!(reconstruct(&descriptor, vec![(1, 1)]).is_err());
+        // levels beyond the maximum levels
+        assert!(reconstruct(&descriptor, vec![(0, 2)]).is_err());
+        assert!(reconstruct(&descriptor, vec![(2, 1)]).is_err());
+    }
+}
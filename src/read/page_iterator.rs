@@ -3,13 +3,14 @@ use std::{io::Read, sync::Arc};
 
 use parquet_format_async_temp::thrift::protocol::TCompactInputProtocol;
 
-use crate::compression::Compression;
-use crate::error::Result;
+use crate::compression::{CodecRegistry, Compression};
+use crate::encryption::{ColumnCipher, ModuleType};
+use crate::error::{ParquetError, Result};
 use crate::metadata::ColumnDescriptor;
 
 use crate::page::{
-    read_dict_page, CompressedDataPage, DataPageHeader, DictPage, EncodedDictPage, PageType,
-    ParquetPageHeader,
+    read_dict_page_with_codecs, CompressedDataPage, DataPageHeader, DictPage, EncodedDictPage,
+    PageType, ParquetPageHeader,
 };
 
 /// Type declaration for a page filter
@@ -36,6 +37,16 @@ pub struct PageIterator<R: Read> {
 
     descriptor: ColumnDescriptor,
 
+    // The cipher of encrypted column chunks and the ordinal of the next data page.
+    cipher: Option<ColumnCipher>,
+    page_ordinal: i16,
+
+    // The codecs used to decompress dictionary pages and, by `Decompressor`, data pages.
+    codecs: CodecRegistry,
+
+    // The maximum compressed and uncompressed size of a page, if any.
+    max_page_size: Option<usize>,
+
     // The currently allocated buffer.
     pub(crate) buffer: Vec<u8>,
 }
@@ -57,12 +68,54 @@ impl<R: Read> PageIterator<R> {
             current_dictionary: None,
             descriptor,
             pages_filter,
+            cipher: None,
+            page_ordinal: 0,
+            codecs: CodecRegistry::default(),
+            max_page_size: None,
             buffer,
         }
     }
 
+    /// Sets the [`CodecRegistry`] used to decompress the pages of this column chunk.
+    pub fn with_codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
+    /// Returns the [`CodecRegistry`] used to decompress the pages of this column chunk.
+    pub fn codecs(&self) -> &CodecRegistry {
+        &self.codecs
+    }
+
+    /// Sets the maximum compressed and uncompressed size of the pages of this column chunk.
+    /// Pages whose header declares a larger size error before any buffer is allocated for them.
+    pub fn with_max_page_size(mut self, max_page_size: usize) -> Self {
+        self.max_page_size = Some(max_page_size);
+        self
+    }
+
+    /// Returns the maximum compressed and uncompressed size of the pages of this column chunk.
+    pub fn max_page_size(&self) -> Option<usize> {
+        self.max_page_size
+    }
+
+    /// Sets the cipher used to decrypt the pages of an encrypted column chunk.
+    pub(crate) fn with_cipher(mut self, cipher: Option<ColumnCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Reads Page header from Thrift.
     fn read_page_header(&mut self) -> Result<ParquetPageHeader> {
+        if let Some(cipher) = &self.cipher {
+            let first_page = self.page_ordinal == 0 && self.current_dictionary.is_none();
+            return read_encrypted_page_header(
+                &mut self.reader,
+                cipher,
+                self.page_ordinal,
+                first_page,
+            );
+        }
         let mut prot = TCompactInputProtocol::new(&mut self.reader);
         let page_header = ParquetPageHeader::read_from_in_protocol(&mut prot)?;
         Ok(page_header)
@@ -131,6 +184,7 @@ fn build_page<R: Read>(
     buffer: &mut Vec<u8>,
 ) -> Result<Option<CompressedDataPage>> {
     let page_header = reader.read_page_header()?;
+    validate_page_sizes(&page_header, reader.max_page_size)?;
     reader.seen_num_values += get_page_header(&page_header)
         .map(|x| x.num_values() as i64)
         .unwrap_or_default();
@@ -146,12 +200,26 @@ fn build_page<R: Read>(
         reader.reader.read_exact(buffer)?;
     }
 
+    if let Some(cipher) = &reader.cipher {
+        let module = match page_header.type_.try_into()? {
+            PageType::DictionaryPage => cipher.module(ModuleType::DictionaryPage, 0),
+            _ => cipher.module(ModuleType::DataPage, reader.page_ordinal),
+        };
+        *buffer = cipher.cipher.decrypt(module, buffer)?;
+        if get_page_header(&page_header).is_some() {
+            reader.page_ordinal = reader.page_ordinal.checked_add(1).ok_or_else(|| {
+                general_err!("Encrypted column chunks support at most 32767 data pages")
+            })?;
+        }
+    }
+
     let result = finish_page(
         page_header,
         buffer,
         reader.compression,
         &reader.current_dictionary,
         &reader.descriptor,
+        &reader.codecs,
     )?;
 
     match result {
@@ -164,6 +232,45 @@ fn build_page<R: Read>(
     }
 }
 
+fn read_encrypted_page_header<R: Read>(
+    reader: &mut R,
+    cipher: &ColumnCipher,
+    page_ordinal: i16,
+    first_page: bool,
+) -> Result<ParquetPageHeader> {
+    let module = read_module(reader)?;
+
+    let data_page = cipher.module(ModuleType::DataPageHeader, page_ordinal);
+    let header = match cipher.cipher.decrypt(data_page, &module) {
+        Ok(header) => header,
+        // only the first page of a column chunk can be a dictionary page
+        Err(_) if first_page => {
+            let dict_page = cipher.module(ModuleType::DictionaryPageHeader, 0);
+            cipher.cipher.decrypt(dict_page, &module)?
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut prot = TCompactInputProtocol::new(header.as_slice());
+    let page_header = ParquetPageHeader::read_from_in_protocol(&mut prot)?;
+    Ok(page_header)
+}
+
+/// Reads an encrypted module, i.e. its length followed by as many bytes
+fn read_module<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut module = vec![0; 4];
+    reader.read_exact(&mut module)?;
+    let length = u32::from_le_bytes(module[..].try_into().unwrap()) as u64;
+
+    reader.by_ref().take(length).read_to_end(&mut module)?;
+    if module.len() as u64 != 4 + length {
+        return Err(ParquetError::OutOfSpec(
+            "An encrypted module is shorter than its declared length".to_string(),
+        ));
+    }
+    Ok(module)
+}
+
 pub(super) enum FinishedPage {
     Data(CompressedDataPage),
     Dict(Arc<dyn DictPage>),
@@ -176,6 +283,7 @@ pub(super) fn finish_page(
     compression: Compression,
     current_dictionary: &Option<Arc<dyn DictPage>>,
     descriptor: &ColumnDescriptor,
+    codecs: &CodecRegistry,
 ) -> Result<FinishedPage> {
     let type_ = page_header.type_.try_into()?;
     match type_ {
@@ -187,11 +295,12 @@ pub(super) fn finish_page(
             let mut dict_page =
                 EncodedDictPage::new(std::mem::take(buffer), dict_header.num_values as usize);
 
-            let page = read_dict_page(
+            let page = read_dict_page_with_codecs(
                 &dict_page,
                 (compression, page_header.uncompressed_page_size as usize),
                 is_sorted,
                 descriptor.physical_type(),
+                codecs,
             )?;
             // take the buffer out of the `dict_page` to re-use it
             std::mem::swap(&mut dict_page.buffer, buffer);
@@ -226,6 +335,36 @@ pub(super) fn finish_page(
     }
 }
 
+/// Errors if the compressed or uncompressed size declared by a page header is negative or larger
+/// than `max_page_size`, so that no buffer is allocated for it.
+pub(super) fn validate_page_sizes(
+    page_header: &ParquetPageHeader,
+    max_page_size: Option<usize>,
+) -> Result<()> {
+    let sizes = [
+        page_header.compressed_page_size,
+        page_header.uncompressed_page_size,
+    ];
+    for size in sizes {
+        if size < 0 {
+            return Err(ParquetError::OutOfSpec(format!(
+                "The page header declares a negative page size ({})",
+                size
+            )));
+        }
+        match max_page_size {
+            Some(max_page_size) if size as usize > max_page_size => {
+                return Err(ParquetError::OutOfSpec(format!(
+                    "The page header declares a page size of {} bytes, larger than the maximum of {}",
+                    size, max_page_size
+                )))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
 pub(super) fn get_page_header(header: &ParquetPageHeader) -> Option<DataPageHeader> {
     let type_ = header.type_.try_into().unwrap();
     match type_ {
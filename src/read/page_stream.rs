@@ -4,47 +4,82 @@ use async_stream::try_stream;
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, Stream};
 use parquet_format_async_temp::thrift::protocol::TCompactInputStreamProtocol;
 
-use crate::compression::Compression;
-use crate::error::Result;
-use crate::metadata::{ColumnChunkMetaData, ColumnDescriptor};
+use crate::compression::CodecRegistry;
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnChunkMetaData;
 use crate::page::{CompressedDataPage, ParquetPageHeader};
 
-use super::page_iterator::{finish_page, get_page_header, FinishedPage};
+use super::page_iterator::{finish_page, get_page_header, validate_page_sizes, FinishedPage};
 use super::PageFilter;
 
 /// Returns a stream of compressed data pages
+/// # Error
+/// Errors if the column chunk is encrypted, which is not supported when reading asynchronously.
 pub async fn get_page_stream<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
     column_metadata: &'a ColumnChunkMetaData,
     reader: &'a mut RR,
     buffer: Vec<u8>,
     pages_filter: PageFilter,
 ) -> Result<impl Stream<Item = Result<CompressedDataPage>> + 'a> {
+    get_page_stream_with_codecs(
+        column_metadata,
+        reader,
+        buffer,
+        pages_filter,
+        &CodecRegistry::default(),
+        None,
+    )
+    .await
+}
+
+/// Returns a stream of compressed data pages like [`get_page_stream`], whose dictionary pages
+/// are decompressed with the codecs of `codecs`. The stream errors on pages whose compressed
+/// or uncompressed size is larger than `max_page_size`, before reading them.
+/// # Error
+/// Errors if the column chunk is encrypted, which is not supported when reading asynchronously.
+pub async fn get_page_stream_with_codecs<'a, RR: AsyncRead + Unpin + Send + AsyncSeek>(
+    column_metadata: &'a ColumnChunkMetaData,
+    reader: &'a mut RR,
+    buffer: Vec<u8>,
+    pages_filter: PageFilter,
+    codecs: &CodecRegistry,
+    max_page_size: Option<usize>,
+) -> Result<impl Stream<Item = Result<CompressedDataPage>> + 'a> {
+    if column_metadata.is_encrypted() {
+        return Err(general_err!(
+            "Reading encrypted column chunks asynchronously is not supported"
+        ));
+    }
     let (col_start, _) = column_metadata.byte_range();
     reader.seek(SeekFrom::Start(col_start)).await?;
     Ok(_get_page_stream(
         reader,
-        column_metadata.num_values(),
-        column_metadata.compression(),
-        column_metadata.descriptor(),
+        column_metadata,
         buffer,
         pages_filter,
+        codecs.clone(),
+        max_page_size,
     ))
 }
 
 fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
     reader: &'a mut R,
-    total_num_values: i64,
-    compression: Compression,
-    descriptor: &'a ColumnDescriptor,
+    column_metadata: &'a ColumnChunkMetaData,
     mut buffer: Vec<u8>,
     pages_filter: PageFilter,
+    codecs: CodecRegistry,
+    max_page_size: Option<usize>,
 ) -> impl Stream<Item = Result<CompressedDataPage>> + 'a {
+    let total_num_values = column_metadata.num_values();
+    let compression = column_metadata.compression();
+    let descriptor = column_metadata.descriptor();
     let mut seen_values = 0i64;
     let mut current_dictionary = None;
     try_stream! {
         while seen_values < total_num_values {
             // the header
             let page_header = read_page_header(reader).await?;
+            validate_page_sizes(&page_header, max_page_size)?;
 
             let data_header = get_page_header(&page_header);
             seen_values += data_header.as_ref().map(|x| x.num_values() as i64).unwrap_or_default();
@@ -71,6 +106,7 @@ fn _get_page_stream<'a, R: AsyncRead + AsyncSeek + Unpin + Send>(
                 compression,
                 &current_dictionary,
                 descriptor,
+                &codecs,
             )?;
 
             match result {
@@ -0,0 +1,748 @@
+//! Evaluation of predicates against the statistics of a file, to skip row groups and pages
+//! whose rows cannot match.
+use std::cmp::Ordering;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use crate::error::{ParquetError, Result};
+use crate::indexes::{
+    compute_rows, BooleanIndex, ByteIndex, FilteredPage, FixedLenByteIndex, Index, NativeIndex,
+    PageIndex, PageLocation,
+};
+use crate::metadata::{
+    get_column_sort_order, ColumnDescriptor, ColumnOrder, FileMetaData, RowGroupMetaData, SortOrder,
+};
+use crate::read::PageFilter;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    BinaryStatistics, BooleanStatistics, FixedLenStatistics, PrimitiveStatistics, Statistics,
+};
+
+/// A value to compare the values of a column to. Its variant must match the physical type of
+/// the column, [`Scalar::Binary`] being used for both `BYTE_ARRAY` and `FIXED_LEN_BYTE_ARRAY`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Boolean(bool),
+    Int32(i32),
+    Int64(i64),
+    Int96([u32; 3]),
+    Float(f32),
+    Double(f64),
+    Binary(Vec<u8>),
+}
+
+/// A predicate over the values of the (leaf) columns of a row group, identified by their
+/// path in the schema. Comparisons follow SQL semantics: they are never true for null values.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// `column = value`
+    Eq(Vec<String>, Scalar),
+    /// `column < value`
+    Lt(Vec<String>, Scalar),
+    /// `column > value`
+    Gt(Vec<String>, Scalar),
+    /// `low <= column <= high`
+    Between(Vec<String>, Scalar, Scalar),
+    /// `column IN (values)`
+    In(Vec<String>, Vec<Scalar>),
+    /// `column IS NULL`
+    IsNull(Vec<String>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+    Not(Box<Predicate>),
+}
+
+/// The statistics of a column (chunk or page) that a [`Predicate`] is evaluated against.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Bounds {
+    physical_type: PhysicalType,
+    min: Option<Scalar>,
+    max: Option<Scalar>,
+    null_count: Option<i64>,
+    // `None` when the number of values is unknown
+    num_values: Option<i64>,
+    sort_order: SortOrder,
+}
+
+impl Bounds {
+    /// Returns the [`Bounds`] of `num_values` values summarized by `statistics`, whose min
+    /// and max values are ordered by `sort_order`.
+    pub(crate) fn new(statistics: &dyn Statistics, num_values: i64, sort_order: SortOrder) -> Self {
+        let (min, max) = min_max(statistics);
+        Self {
+            physical_type: *statistics.physical_type(),
+            min,
+            max,
+            null_count: statistics.null_count(),
+            num_values: Some(num_values),
+            sort_order,
+        }
+    }
+
+    /// Returns the [`Bounds`] of the `num_values` values of the page `page` of a column chunk,
+    /// from the column chunk's `index`. Returns `None` if the index has no such page.
+    fn from_index(
+        index: &dyn Index,
+        page: usize,
+        num_values: Option<i64>,
+        sort_order: SortOrder,
+    ) -> Option<Self> {
+        page_min_max(index, page).map(|(min, max, null_count)| Self {
+            physical_type: *index.physical_type(),
+            min,
+            max,
+            null_count,
+            num_values,
+            sort_order,
+        })
+    }
+
+    fn check(&self, value: &Scalar) -> Result<()> {
+        let is_valid = matches!(
+            (value, &self.physical_type),
+            (Scalar::Boolean(_), PhysicalType::Boolean)
+                | (Scalar::Int32(_), PhysicalType::Int32)
+                | (Scalar::Int64(_), PhysicalType::Int64)
+                | (Scalar::Int96(_), PhysicalType::Int96)
+                | (Scalar::Float(_), PhysicalType::Float)
+                | (Scalar::Double(_), PhysicalType::Double)
+                | (
+                    Scalar::Binary(_),
+                    PhysicalType::ByteArray | PhysicalType::FixedLenByteArray(_)
+                )
+        );
+        if is_valid {
+            Ok(())
+        } else {
+            Err(general_err!(
+                "The value {:?} cannot be compared to a column of type {:?}",
+                value,
+                self.physical_type
+            ))
+        }
+    }
+
+    /// Whether `value` is above `low`, or `None` if they cannot be compared
+    fn above(&self, value: &Scalar, low: Bound<&Scalar>) -> Option<bool> {
+        match low {
+            Bound::Unbounded => Some(true),
+            Bound::Included(low) => compare(value, low, self.sort_order).map(|x| x.is_ge()),
+            Bound::Excluded(low) => compare(value, low, self.sort_order).map(|x| x.is_gt()),
+        }
+    }
+
+    /// Whether `value` is below `high`, or `None` if they cannot be compared
+    fn below(&self, value: &Scalar, high: Bound<&Scalar>) -> Option<bool> {
+        match high {
+            Bound::Unbounded => Some(true),
+            Bound::Included(high) => compare(value, high, self.sort_order).map(|x| x.is_le()),
+            Bound::Excluded(high) => compare(value, high, self.sort_order).map(|x| x.is_lt()),
+        }
+    }
+
+    /// Evaluates whether the values are within `low` and `high`
+    fn range(&self, low: Bound<&Scalar>, high: Bound<&Scalar>) -> Result<Option<bool>> {
+        for bound in [low, high] {
+            if let Bound::Included(value) | Bound::Excluded(value) = bound {
+                self.check(value)?
+            }
+        }
+        if self.null_count.is_some() && self.null_count == self.num_values {
+            // only nulls, for which comparisons are never true
+            return Ok(Some(false));
+        }
+        let (min, max) = match (&self.min, &self.max) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return Ok(None),
+        };
+        // writers leave NaN out of the min and max of floating point columns, so their values
+        // are never known to all be within the bounds
+        let may_have_nan = matches!(
+            self.physical_type,
+            PhysicalType::Float | PhysicalType::Double
+        );
+        Ok(
+            if self.above(max, low) == Some(false) || self.below(min, high) == Some(false) {
+                Some(false)
+            } else if !may_have_nan
+                && self.above(min, low) == Some(true)
+                && self.below(max, high) == Some(true)
+            {
+                Some(true)
+            } else {
+                None
+            },
+        )
+    }
+
+    fn is_null(&self) -> Option<bool> {
+        match self.null_count {
+            Some(0) => Some(false),
+            Some(null_count) if Some(null_count) == self.num_values => Some(true),
+            _ => None,
+        }
+    }
+}
+
+fn min_max(statistics: &dyn Statistics) -> (Option<Scalar>, Option<Scalar>) {
+    let statistics = statistics.as_any();
+    if let Some(s) = statistics.downcast_ref::<BooleanStatistics>() {
+        (
+            s.min_value.map(Scalar::Boolean),
+            s.max_value.map(Scalar::Boolean),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<PrimitiveStatistics<i32>>() {
+        (
+            s.min_value.map(Scalar::Int32),
+            s.max_value.map(Scalar::Int32),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<PrimitiveStatistics<i64>>() {
+        (
+            s.min_value.map(Scalar::Int64),
+            s.max_value.map(Scalar::Int64),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<PrimitiveStatistics<[u32; 3]>>() {
+        (
+            s.min_value.map(Scalar::Int96),
+            s.max_value.map(Scalar::Int96),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<PrimitiveStatistics<f32>>() {
+        (
+            s.min_value.map(Scalar::Float),
+            s.max_value.map(Scalar::Float),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<PrimitiveStatistics<f64>>() {
+        (
+            s.min_value.map(Scalar::Double),
+            s.max_value.map(Scalar::Double),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<BinaryStatistics>() {
+        (
+            s.min_value.clone().map(Scalar::Binary),
+            s.max_value.clone().map(Scalar::Binary),
+        )
+    } else if let Some(s) = statistics.downcast_ref::<FixedLenStatistics>() {
+        (
+            s.min_value.clone().map(Scalar::Binary),
+            s.max_value.clone().map(Scalar::Binary),
+        )
+    } else {
+        (None, None)
+    }
+}
+
+type PageMinMax = (Option<Scalar>, Option<Scalar>, Option<i64>);
+
+fn page<T: Clone>(indexes: &[PageIndex<T>], page: usize, f: fn(T) -> Scalar) -> Option<PageMinMax> {
+    indexes.get(page).map(|index| {
+        (
+            index.min.clone().map(f),
+            index.max.clone().map(f),
+            index.null_count,
+        )
+    })
+}
+
+fn page_min_max(index: &dyn Index, i: usize) -> Option<PageMinMax> {
+    let index = index.as_any();
+    if let Some(index) = index.downcast_ref::<BooleanIndex>() {
+        page(&index.indexes, i, Scalar::Boolean)
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<i32>>() {
+        page(&index.indexes, i, Scalar::Int32)
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<i64>>() {
+        page(&index.indexes, i, Scalar::Int64)
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<[u32; 3]>>() {
+        page(&index.indexes, i, Scalar::Int96)
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<f32>>() {
+        page(&index.indexes, i, Scalar::Float)
+    } else if let Some(index) = index.downcast_ref::<NativeIndex<f64>>() {
+        page(&index.indexes, i, Scalar::Double)
+    } else if let Some(index) = index.downcast_ref::<ByteIndex>() {
+        page(&index.indexes, i, Scalar::Binary)
+    } else if let Some(index) = index.downcast_ref::<FixedLenByteIndex>() {
+        page(&index.indexes, i, Scalar::Binary)
+    } else {
+        None
+    }
+}
+
+/// Compares two values according to `sort_order`, or returns `None` if they cannot be
+/// compared (e.g. `NaN` or an undefined order).
+fn compare(lhs: &Scalar, rhs: &Scalar, sort_order: SortOrder) -> Option<Ordering> {
+    use Scalar::*;
+    match (sort_order, lhs, rhs) {
+        (SortOrder::Undefined, _, _) => None,
+        (_, Boolean(lhs), Boolean(rhs)) => Some(lhs.cmp(rhs)),
+        (SortOrder::Signed, Int32(lhs), Int32(rhs)) => Some(lhs.cmp(rhs)),
+        (SortOrder::Unsigned, Int32(lhs), Int32(rhs)) => Some((*lhs as u32).cmp(&(*rhs as u32))),
+        (SortOrder::Signed, Int64(lhs), Int64(rhs)) => Some(lhs.cmp(rhs)),
+        (SortOrder::Unsigned, Int64(lhs), Int64(rhs)) => Some((*lhs as u64).cmp(&(*rhs as u64))),
+        (_, Float(lhs), Float(rhs)) => lhs.partial_cmp(rhs),
+        (_, Double(lhs), Double(rhs)) => lhs.partial_cmp(rhs),
+        (SortOrder::Signed, Binary(lhs), Binary(rhs)) => Some(compare_signed(lhs, rhs)),
+        (SortOrder::Unsigned, Binary(lhs), Binary(rhs)) => Some(lhs.cmp(rhs)),
+        _ => None,
+    }
+}
+
+/// Compares two big-endian two's complement integers (e.g. decimals) of any length
+fn compare_signed(lhs: &[u8], rhs: &[u8]) -> Ordering {
+    let len = lhs.len().max(rhs.len());
+    let extend = |x: &[u8]| {
+        let sign = match x.first() {
+            Some(first) if (*first as i8) < 0 => 0xFF,
+            _ => 0,
+        };
+        let mut extended = vec![sign; len - x.len()];
+        extended.extend_from_slice(x);
+        extended
+    };
+    let (lhs, rhs) = (extend(lhs), extend(rhs));
+    match (lhs.first(), rhs.first()) {
+        (Some(l), Some(r)) => (*l as i8)
+            .cmp(&(*r as i8))
+            .then_with(|| lhs[1..].cmp(&rhs[1..])),
+        _ => Ordering::Equal,
+    }
+}
+
+/// The order of the min and max values of `column`: `min_value` and `max_value` are always
+/// ordered by the type of the column, which files without column orders do not declare.
+fn sort_order(column_order: ColumnOrder, descriptor: &ColumnDescriptor) -> SortOrder {
+    match column_order {
+        ColumnOrder::TypeDefinedOrder(sort_order) => sort_order,
+        ColumnOrder::Undefined => get_column_sort_order(descriptor),
+    }
+}
+
+fn and(lhs: Option<bool>, rhs: Option<bool>) -> Option<bool> {
+    match (lhs, rhs) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn or(lhs: Option<bool>, rhs: Option<bool>) -> Option<bool> {
+    match (lhs, rhs) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+impl Predicate {
+    /// Evaluates this predicate against the statistics of the column chunks of `row_group`,
+    /// a row group of `metadata`. Returns
+    /// * `Some(false)` if the predicate is true for no row of the row group,
+    /// * `Some(true)` if the predicate is false for no row of the row group,
+    /// * `None` if this cannot be determined from the statistics.
+    /// # Errors
+    /// Errors if a column is not part of the schema, a value does not match the physical type
+    /// of its column, or statistics cannot be deserialized.
+    pub fn evaluate(
+        &self,
+        metadata: &FileMetaData,
+        row_group: &RowGroupMetaData,
+    ) -> Result<Option<bool>> {
+        self.evaluate_by(&|path| {
+            let (i, column) = row_group
+                .columns()
+                .iter()
+                .enumerate()
+                .find(|(_, column)| column.descriptor().path_in_schema() == path)
+                .ok_or_else(|| general_err!("The column {:?} is not part of the schema", path))?;
+            let sort_order = sort_order(metadata.column_order(i), column.descriptor());
+            column
+                .statistics()
+                .map(|statistics| {
+                    Ok(Bounds::new(
+                        statistics?.as_ref(),
+                        column.num_values(),
+                        sort_order,
+                    ))
+                })
+                .transpose()
+        })
+    }
+
+    /// Checks that the values compared to the column `descriptor` match its physical type, and
+    /// returns its path and the order of the min/max values of its pages.
+    fn check_page_bounds(&self, descriptor: &ColumnDescriptor) -> Result<(Vec<String>, SortOrder)> {
+        let path = descriptor.path_in_schema().to_vec();
+        // min/max values of pages are always ordered by the type of the column
+        let sort_order = sort_order(ColumnOrder::Undefined, descriptor);
+
+        // check the values compared to the column against bounds without statistics
+        let empty = Bounds {
+            physical_type: *descriptor.physical_type(),
+            min: None,
+            max: None,
+            null_count: None,
+            num_values: None,
+            sort_order,
+        };
+        self.evaluate_by(&|p| Ok(if p == path { Some(empty.clone()) } else { None }))?;
+        Ok((path, sort_order))
+    }
+
+    /// Returns a [`PageFilter`] that skips the data pages of the column `descriptor` for which
+    /// this predicate is true for no value, evaluated against the statistics of each page header.
+    /// Parts of the predicate over other columns are unknown at the page level, and pages of
+    /// other columns are never skipped.
+    ///
+    /// The filter holds no state, so it can be shared by the column chunks of all row groups.
+    /// # Errors
+    /// Errors if a value compared to the column does not match its physical type.
+    pub fn page_filter(&self, descriptor: &ColumnDescriptor) -> Result<PageFilter> {
+        let (path, sort_order) = self.check_page_bounds(descriptor)?;
+
+        let predicate = self.clone();
+        Ok(Arc::new(move |descriptor, header| {
+            if descriptor.path_in_schema() != path.as_slice() {
+                return true;
+            }
+            let bounds = match header.statistics(descriptor) {
+                Some(Ok(statistics)) => Some(Bounds::new(
+                    statistics.as_ref(),
+                    header.num_values() as i64,
+                    sort_order,
+                )),
+                _ => None,
+            };
+            // pages whose statistics cannot be read are not skipped
+            predicate
+                .evaluate_by(&|p| Ok(if p == path { bounds.clone() } else { None }))
+                .map(|result| result != Some(false))
+                .unwrap_or(true)
+        }))
+    }
+
+    /// Returns the data pages of a column chunk of the column `descriptor` for which this
+    /// predicate may be true for some value, to be read via [`crate::read::IndexedPageReader`].
+    /// The predicate is evaluated against the entry of each page in `index`, the column index of
+    /// the column chunk, and pages are identified by their `locations`, from its offset index.
+    /// `num_rows` is the number of rows of the row group.
+    /// # Errors
+    /// Errors if a value compared to the column does not match its physical type, or if the
+    /// `locations` are invalid.
+    pub fn select_pages(
+        &self,
+        descriptor: &ColumnDescriptor,
+        index: &dyn Index,
+        locations: &[PageLocation],
+        num_rows: usize,
+    ) -> Result<Vec<FilteredPage>> {
+        let (path, sort_order) = self.check_page_bounds(descriptor)?;
+
+        let mut intervals = vec![];
+        for (page, rows) in compute_rows(locations, num_rows)?.into_iter().enumerate() {
+            // the number of values of a page is only known when each row has a single value
+            let num_values = if descriptor.max_rep_level() == 0 {
+                Some(rows.length as i64)
+            } else {
+                None
+            };
+            let bounds = Bounds::from_index(index, page, num_values, sort_order);
+            let result =
+                self.evaluate_by(&|p| Ok(if p == path { bounds.clone() } else { None }))?;
+            if result != Some(false) {
+                intervals.push(rows);
+            }
+        }
+        crate::indexes::select_pages(&intervals, locations, num_rows)
+    }
+
+    /// Evaluates this predicate against the [`Bounds`] returned by `bounds` for the path of each
+    /// column, `None` meaning that the column has no statistics.
+    pub(crate) fn evaluate_by<F>(&self, bounds: &F) -> Result<Option<bool>>
+    where
+        F: Fn(&[String]) -> Result<Option<Bounds>>,
+    {
+        let range = |path: &[String], low, high| -> Result<Option<bool>> {
+            Ok(match bounds(path)? {
+                Some(bounds) => bounds.range(low, high)?,
+                None => None,
+            })
+        };
+        Ok(match self {
+            Predicate::Eq(path, value) => {
+                range(path, Bound::Included(value), Bound::Included(value))?
+            }
+            Predicate::Lt(path, value) => range(path, Bound::Unbounded, Bound::Excluded(value))?,
+            Predicate::Gt(path, value) => range(path, Bound::Excluded(value), Bound::Unbounded)?,
+            Predicate::Between(path, low, high) => {
+                range(path, Bound::Included(low), Bound::Included(high))?
+            }
+            Predicate::In(path, values) => values.iter().try_fold(Some(false), |acc, value| {
+                range(path, Bound::Included(value), Bound::Included(value))
+                    .map(|result| or(acc, result))
+            })?,
+            Predicate::IsNull(path) => bounds(path)?.and_then(|bounds| bounds.is_null()),
+            Predicate::And(lhs, rhs) => and(lhs.evaluate_by(bounds)?, rhs.evaluate_by(bounds)?),
+            Predicate::Or(lhs, rhs) => or(lhs.evaluate_by(bounds)?, rhs.evaluate_by(bounds)?),
+            Predicate::Not(predicate) => predicate.evaluate_by(bounds)?.map(|x| !x),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(
+        min: Scalar,
+        max: Scalar,
+        null_count: i64,
+        sort_order: SortOrder,
+        physical_type: PhysicalType,
+    ) -> Bounds {
+        Bounds {
+            physical_type,
+            min: Some(min),
+            max: Some(max),
+            null_count: Some(null_count),
+            num_values: Some(10),
+            sort_order,
+        }
+    }
+
+    fn evaluate(predicate: &Predicate, bounds: &Bounds) -> Result<Option<bool>> {
+        predicate.evaluate_by(&|path| {
+            Ok(if path == ["a"] {
+                Some(bounds.clone())
+            } else {
+                None
+            })
+        })
+    }
+
+    fn a() -> Vec<String> {
+        vec!["a".to_string()]
+    }
+
+    #[test]
+    fn comparisons() -> Result<()> {
+        let b = bounds(
+            Scalar::Int32(1),
+            Scalar::Int32(5),
+            0,
+            SortOrder::Signed,
+            PhysicalType::Int32,
+        );
+        let cases = [
+            (Predicate::Eq(a(), Scalar::Int32(0)), Some(false)),
+            (Predicate::Eq(a(), Scalar::Int32(3)), None),
+            (Predicate::Eq(a(), Scalar::Int32(6)), Some(false)),
+            (Predicate::Lt(a(), Scalar::Int32(1)), Some(false)),
+            (Predicate::Lt(a(), Scalar::Int32(2)), None),
+            (Predicate::Lt(a(), Scalar::Int32(6)), Some(true)),
+            (Predicate::Gt(a(), Scalar::Int32(5)), Some(false)),
+            (Predicate::Gt(a(), Scalar::Int32(0)), Some(true)),
+            (
+                Predicate::Between(a(), Scalar::Int32(6), Scalar::Int32(9)),
+                Some(false),
+            ),
+            (
+                Predicate::Between(a(), Scalar::Int32(1), Scalar::Int32(5)),
+                Some(true),
+            ),
+            (
+                Predicate::Between(a(), Scalar::Int32(2), Scalar::Int32(9)),
+                None,
+            ),
+            (
+                Predicate::In(a(), vec![Scalar::Int32(0), Scalar::Int32(7)]),
+                Some(false),
+            ),
+            (
+                Predicate::In(a(), vec![Scalar::Int32(0), Scalar::Int32(2)]),
+                None,
+            ),
+            (Predicate::In(a(), vec![]), Some(false)),
+            (Predicate::IsNull(a()), Some(false)),
+        ];
+        for (predicate, expected) in cases {
+            assert_eq!(evaluate(&predicate, &b)?, expected, "{:?}", predicate);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nulls() -> Result<()> {
+        let mut b = bounds(
+            Scalar::Int32(1),
+            Scalar::Int32(1),
+            3,
+            SortOrder::Signed,
+            PhysicalType::Int32,
+        );
+        // comparisons are never false for nulls
+        assert_eq!(
+            evaluate(&Predicate::Eq(a(), Scalar::Int32(1)), &b)?,
+            Some(true)
+        );
+        assert_eq!(evaluate(&Predicate::IsNull(a()), &b)?, None);
+
+        b.null_count = Some(10);
+        b.min = None;
+        b.max = None;
+        assert_eq!(
+            evaluate(&Predicate::Eq(a(), Scalar::Int32(1)), &b)?,
+            Some(false)
+        );
+        assert_eq!(evaluate(&Predicate::IsNull(a()), &b)?, Some(true));
+
+        b.null_count = None;
+        assert_eq!(evaluate(&Predicate::Eq(a(), Scalar::Int32(1)), &b)?, None);
+        assert_eq!(evaluate(&Predicate::IsNull(a()), &b)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn logical() -> Result<()> {
+        let b = bounds(
+            Scalar::Int64(1),
+            Scalar::Int64(5),
+            0,
+            SortOrder::Signed,
+            PhysicalType::Int64,
+        );
+        let none = Predicate::Gt(a(), Scalar::Int64(5));
+        let all = Predicate::Lt(a(), Scalar::Int64(6));
+        let unknown = Predicate::Eq(a(), Scalar::Int64(2));
+        let missing = Predicate::Eq(vec!["b".to_string()], Scalar::Int64(2));
+        let and = |lhs: &Predicate, rhs: &Predicate| {
+            Predicate::And(Box::new(lhs.clone()), Box::new(rhs.clone()))
+        };
+        let or = |lhs: &Predicate, rhs: &Predicate| {
+            Predicate::Or(Box::new(lhs.clone()), Box::new(rhs.clone()))
+        };
+        let not = |predicate: &Predicate| Predicate::Not(Box::new(predicate.clone()));
+
+        assert_eq!(evaluate(&and(&all, &none), &b)?, Some(false));
+        assert_eq!(evaluate(&and(&all, &unknown), &b)?, None);
+        assert_eq!(evaluate(&and(&missing, &none), &b)?, Some(false));
+        assert_eq!(evaluate(&or(&all, &unknown), &b)?, Some(true));
+        assert_eq!(evaluate(&or(&none, &unknown), &b)?, None);
+        assert_eq!(evaluate(&or(&none, &none), &b)?, Some(false));
+        assert_eq!(evaluate(&not(&all), &b)?, Some(false));
+        assert_eq!(evaluate(&not(&none), &b)?, Some(true));
+        assert_eq!(evaluate(&not(&unknown), &b)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_orders() -> Result<()> {
+        // -1 is the largest value when ordered as unsigned
+        let b = bounds(
+            Scalar::Int32(1),
+            Scalar::Int32(-1),
+            0,
+            SortOrder::Unsigned,
+            PhysicalType::Int32,
+        );
+        assert_eq!(
+            evaluate(&Predicate::Gt(a(), Scalar::Int32(-1)), &b)?,
+            Some(false)
+        );
+        assert_eq!(
+            evaluate(&Predicate::Lt(a(), Scalar::Int32(0)), &b)?,
+            Some(false)
+        );
+
+        // bytes are ordered as unsigned...
+        let b = bounds(
+            Scalar::Binary(b"a".to_vec()),
+            Scalar::Binary(vec![0xC3, 0xA9]),
+            0,
+            SortOrder::Unsigned,
+            PhysicalType::ByteArray,
+        );
+        assert_eq!(
+            evaluate(&Predicate::Gt(a(), Scalar::Binary(b"z".to_vec())), &b)?,
+            None
+        );
+        assert_eq!(
+            evaluate(&Predicate::Lt(a(), Scalar::Binary(b"a".to_vec())), &b)?,
+            Some(false)
+        );
+
+        // ... and decimals as signed integers: [-2, 300]
+        let b = bounds(
+            Scalar::Binary(vec![0xFE]),
+            Scalar::Binary(vec![0x01, 0x2C]),
+            0,
+            SortOrder::Signed,
+            PhysicalType::ByteArray,
+        );
+        assert_eq!(
+            evaluate(&Predicate::Eq(a(), Scalar::Binary(vec![0xFF, 0x00])), &b)?,
+            Some(false)
+        );
+        assert_eq!(
+            evaluate(&Predicate::Eq(a(), Scalar::Binary(vec![0xFF])), &b)?,
+            None
+        );
+
+        // no order: nothing can be pruned
+        let b = bounds(
+            Scalar::Int96([0, 0, 0]),
+            Scalar::Int96([1, 0, 0]),
+            0,
+            SortOrder::Undefined,
+            PhysicalType::Int96,
+        );
+        assert_eq!(
+            evaluate(&Predicate::Eq(a(), Scalar::Int96([2, 0, 0])), &b)?,
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn nan() -> Result<()> {
+        let b = bounds(
+            Scalar::Double(1.0),
+            Scalar::Double(f64::NAN),
+            0,
+            SortOrder::Signed,
+            PhysicalType::Double,
+        );
+        assert_eq!(
+            evaluate(&Predicate::Gt(a(), Scalar::Double(2.0)), &b)?,
+            None
+        );
+        assert_eq!(
+            evaluate(&Predicate::Lt(a(), Scalar::Double(1.0)), &b)?,
+            Some(false)
+        );
+
+        // NaN is not part of the min and max, so the values may not all be within them
+        let b = bounds(
+            Scalar::Double(1.0),
+            Scalar::Double(5.0),
+            0,
+            SortOrder::Signed,
+            PhysicalType::Double,
+        );
+        let gt = |value| Predicate::Gt(a(), Scalar::Double(value));
+        let not = |predicate| Predicate::Not(Box::new(predicate));
+        assert_eq!(evaluate(&gt(0.0), &b)?, None);
+        assert_eq!(evaluate(&not(gt(0.0)), &b)?, None);
+        assert_eq!(evaluate(&not(gt(6.0)), &b)?, Some(true));
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let b = bounds(
+            Scalar::Int32(1),
+            Scalar::Int32(5),
+            0,
+            SortOrder::Signed,
+            PhysicalType::Int32,
+        );
+        assert!(evaluate(&Predicate::Eq(a(), Scalar::Int64(1)), &b).is_err());
+    }
+}
@@ -4,7 +4,9 @@ use futures::{io::Cursor, AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
 use parquet_format_async_temp::thrift::protocol::TCompactInputStreamProtocol;
 use parquet_format_async_temp::FileMetaData as TFileMetaData;
 
-use super::super::{metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC};
+use super::super::{
+    metadata::*, DEFAULT_FOOTER_READ_SIZE, FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED,
+};
 use super::metadata::{metadata_len, parse_column_orders};
 use crate::error::{ParquetError, Result};
 
@@ -23,6 +25,10 @@ async fn stream_len(
     Ok(len)
 }
 
+/// Reads a file's metadata asynchronously.
+///
+/// Files whose footer is encrypted are not supported; the metadata of files with a plaintext
+/// footer is read as by [`crate::read::read_metadata`].
 pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>(
     reader: &mut R,
 ) -> Result<FileMetaData> {
@@ -43,6 +49,11 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
     reader.read_exact(&mut default_len_end_buf).await?;
 
     // check this is indeed a parquet file
+    if default_len_end_buf[default_end_len - 4..] == PARQUET_MAGIC_ENCRYPTED {
+        return Err(general_err!(
+            "The footer of this file is encrypted, which is not supported when reading asynchronously"
+        ));
+    }
     if default_len_end_buf[default_end_len - 4..] != PARQUET_MAGIC {
         return Err(general_err!("Invalid Parquet file. Corrupt footer"));
     }
@@ -85,7 +96,7 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
     let schema = t_file_metadata.schema.iter().collect::<Vec<_>>();
     let schema_descr = SchemaDescriptor::try_from_thrift(&schema)?;
 
-    let row_groups = t_file_metadata
+    let mut row_groups = t_file_metadata
         .row_groups
         .into_iter()
         .map(|rg| RowGroupMetaData::try_from_thrift(&schema_descr, rg))
@@ -95,6 +106,13 @@ pub async fn read_metadata<R: AsyncRead + AsyncSeek + Send + std::marker::Unpin>
     let column_orders = t_file_metadata
         .column_orders
         .map(|orders| parse_column_orders(&orders, &schema_descr));
+    if let Some(column_orders) = &column_orders {
+        for row_group in row_groups.iter_mut() {
+            for (column, order) in row_group.columns_mut().iter_mut().zip(column_orders) {
+                column.set_column_order(*order);
+            }
+        }
+    }
 
     Ok(FileMetaData::new(
         t_file_metadata.version,
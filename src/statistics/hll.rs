@@ -0,0 +1,223 @@
+use crate::bloom_filter::{hash_byte, hash_native};
+use crate::error::{ParquetError, Result};
+use crate::types::NativeType;
+
+/// The key of the key-value metadata of a column chunk under which its [`HyperLogLog`] sketch is
+/// written, as the hexadecimal representation of [`HyperLogLog::registers`].
+pub const DISTINCT_SKETCH_KEY: &str = "parquet2.distinct_sketch";
+
+const MIN_PRECISION: u8 = 4;
+const MAX_PRECISION: u8 = 18;
+
+/// A HyperLogLog sketch, used to estimate the number of distinct values of pages and column
+/// chunks.
+///
+/// Values are hashed like [`crate::bloom_filter`] (xxh64 of their plain representation), so
+/// sketches can be merged across pages and column chunks of the same column.
+/// A sketch of precision `p` has `2^p` registers of one byte and a relative error of about
+/// `1.04 / sqrt(2^p)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Returns an empty [`HyperLogLog`] with `2^precision` registers.
+    /// # Error
+    /// Errors iff `precision` is not between 4 and 18.
+    pub fn try_new(precision: u8) -> Result<Self> {
+        if !(MIN_PRECISION..=MAX_PRECISION).contains(&precision) {
+            return Err(general_err!(
+                "The precision of a HyperLogLog sketch must be between {} and {}, not {}",
+                MIN_PRECISION,
+                MAX_PRECISION,
+                precision
+            ));
+        }
+        Ok(Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        })
+    }
+
+    /// Returns a [`HyperLogLog`] from existing registers.
+    /// # Error
+    /// Errors iff the number of registers is not a power of two of a valid precision, or a
+    /// register is larger than what a 64-bit hash can produce.
+    pub fn try_from_registers(registers: Vec<u8>) -> Result<Self> {
+        let precision = registers.len().trailing_zeros() as u8;
+        if !registers.len().is_power_of_two()
+            || !(MIN_PRECISION..=MAX_PRECISION).contains(&precision)
+        {
+            return Err(ParquetError::OutOfSpec(format!(
+                "A HyperLogLog sketch cannot have {} registers",
+                registers.len()
+            )));
+        }
+        if registers.iter().any(|x| *x > 64 - precision + 1) {
+            return Err(ParquetError::OutOfSpec(
+                "The registers of a HyperLogLog sketch are out of bounds".to_string(),
+            ));
+        }
+        Ok(Self {
+            precision,
+            registers,
+        })
+    }
+
+    /// The precision of this sketch
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// The registers of this sketch
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// Returns the hexadecimal representation of the registers of this sketch, as written to
+    /// the key-value metadata of column chunks.
+    pub(crate) fn to_hex(&self) -> String {
+        self.registers
+            .iter()
+            .map(|x| format!("{:02x}", x))
+            .collect()
+    }
+
+    /// Returns a [`HyperLogLog`] from the hexadecimal representation of its registers.
+    pub(crate) fn try_from_hex(hex: &str) -> Result<Self> {
+        let registers = hex
+            .as_bytes()
+            .chunks(2)
+            .map(|x| {
+                std::str::from_utf8(x)
+                    .ok()
+                    .filter(|x| x.len() == 2)
+                    .and_then(|x| u8::from_str_radix(x, 16).ok())
+                    .ok_or_else(|| {
+                        ParquetError::OutOfSpec(
+                            "A HyperLogLog sketch must be hexadecimal".to_string(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::try_from_registers(registers)
+    }
+
+    /// Inserts the (xxh64) hash of a value
+    pub fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        // the position of the first set bit of the remaining bits, bounded by their number
+        let rank =
+            ((hash << self.precision).leading_zeros() as u8 + 1).min(64 - self.precision + 1);
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+    }
+
+    /// Inserts a value of a [`NativeType`] physical type (e.g. `Int32`, `Double`).
+    pub fn insert_native<T: NativeType>(&mut self, value: T) {
+        self.insert_hash(hash_native(value))
+    }
+
+    /// Inserts a value of the `ByteArray` or `FixedLenByteArray` physical types.
+    pub fn insert_bytes<A: AsRef<[u8]>>(&mut self, value: A) {
+        self.insert_hash(hash_byte(value))
+    }
+
+    /// Merges `other` into this sketch, so that it estimates the distinct values of both.
+    /// # Error
+    /// Errors iff the sketches have different precisions.
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        if self.precision != other.precision {
+            return Err(general_err!(
+                "Cannot merge HyperLogLog sketches of precisions {} and {}",
+                self.precision,
+                other.precision
+            ));
+        }
+        self.registers
+            .iter_mut()
+            .zip(other.registers.iter())
+            .for_each(|(x, y)| *x = (*x).max(*y));
+        Ok(())
+    }
+
+    /// Returns the estimated number of distinct values inserted in this sketch.
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum = self
+            .registers
+            .iter()
+            .map(|x| 2f64.powi(-(*x as i32)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+
+        // small ranges are better estimated by linear counting. Large ranges need no correction
+        // since hashes have 64 bits.
+        let zeros = self.registers.iter().filter(|x| **x == 0).count();
+        let estimate = if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precision() {
+        assert!(HyperLogLog::try_new(3).is_err());
+        assert!(HyperLogLog::try_new(19).is_err());
+        assert_eq!(HyperLogLog::try_new(12).unwrap().registers().len(), 4096);
+        assert!(HyperLogLog::try_from_registers(vec![0; 100]).is_err());
+        assert!(HyperLogLog::try_from_registers(vec![62; 16]).is_err());
+    }
+
+    #[test]
+    fn estimate() -> Result<()> {
+        let mut sketch = HyperLogLog::try_new(12)?;
+        assert_eq!(sketch.estimate(), 0);
+
+        // duplicates do not count
+        (0..10i64)
+            .chain(0..10)
+            .for_each(|x| sketch.insert_native(x));
+        assert_eq!(sketch.estimate(), 10);
+
+        let mut sketch = HyperLogLog::try_new(12)?;
+        (0..100_000i64).for_each(|x| sketch.insert_native(x));
+        let error = (sketch.estimate() as f64 - 100_000.0).abs() / 100_000.0;
+        assert!(error < 0.05, "{}", sketch.estimate());
+        Ok(())
+    }
+
+    #[test]
+    fn merge() -> Result<()> {
+        let mut a = HyperLogLog::try_new(10)?;
+        let mut b = HyperLogLog::try_new(10)?;
+        (0..500).for_each(|x| a.insert_bytes(format!("{}", x)));
+        (250..750).for_each(|x| b.insert_bytes(format!("{}", x)));
+
+        let mut expected = HyperLogLog::try_new(10)?;
+        (0..750).for_each(|x| expected.insert_bytes(format!("{}", x)));
+
+        a.merge(&b)?;
+        assert_eq!(a, expected);
+        assert_eq!(HyperLogLog::try_from_hex(&a.to_hex())?, a);
+        assert!(HyperLogLog::try_from_hex("0g").is_err());
+        assert!(HyperLogLog::try_from_hex("000").is_err());
+        assert!(a.merge(&HyperLogLog::try_new(11)?).is_err());
+        Ok(())
+    }
+}
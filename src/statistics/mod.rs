@@ -1,6 +1,7 @@
 mod binary;
 mod boolean;
 mod fixed_len_binary;
+mod hll;
 mod primitive;
 
 use std::{any::Any, sync::Arc};
@@ -8,12 +9,13 @@ use std::{any::Any, sync::Arc};
 pub use parquet_format_async_temp::Statistics as ParquetStatistics;
 
 use crate::error::Result;
-use crate::metadata::ColumnDescriptor;
+use crate::metadata::{get_column_sort_order, ColumnDescriptor, ColumnOrder, SortOrder};
 use crate::schema::types::PhysicalType;
 
 pub use binary::BinaryStatistics;
 pub use boolean::BooleanStatistics;
 pub use fixed_len_binary::FixedLenStatistics;
+pub use hll::{HyperLogLog, DISTINCT_SKETCH_KEY};
 pub use primitive::PrimitiveStatistics;
 
 /// A trait used to describe specific statistics. Each physical type has its own struct.
@@ -92,7 +94,8 @@ impl PartialEq for &dyn Statistics {
     }
 }
 
-/// Deserializes a raw parquet statistics into [`Statistics`].
+/// Deserializes a raw parquet statistics into [`Statistics`], like
+/// [`deserialize_statistics_with_order`] for a column without column order.
 /// # Error
 /// This function errors if it is not possible to read the statistics to the
 /// corresponding `physical_type`.
@@ -100,6 +103,52 @@ pub fn deserialize_statistics(
     statistics: &ParquetStatistics,
     descriptor: ColumnDescriptor,
 ) -> Result<Arc<dyn Statistics>> {
+    deserialize_statistics_with_order(statistics, descriptor, ColumnOrder::Undefined)
+}
+
+/// Whether the deprecated `min` and `max` of a column are ordered by the sort order of its type.
+/// Legacy writers order them as signed values, which is only the order of types with a signed
+/// sort order (and of booleans), unless the file declares its column orders. Types with an
+/// undefined sort order (e.g. `INT96` or `INTERVAL`) have no order to trust.
+fn is_legacy_min_max_ordered(descriptor: &ColumnDescriptor, column_order: ColumnOrder) -> bool {
+    let sort_order = get_column_sort_order(descriptor);
+    match column_order {
+        ColumnOrder::Undefined => {
+            *descriptor.physical_type() == PhysicalType::Boolean || sort_order == SortOrder::Signed
+        }
+        ColumnOrder::TypeDefinedOrder(_) => sort_order != SortOrder::Undefined,
+    }
+}
+
+/// Deserializes a raw parquet statistics of a column with `column_order` into [`Statistics`].
+///
+/// When `min_value` and `max_value` are absent, the deprecated `min` and `max` are read instead,
+/// unless they are untrustworthy: the column has an undefined sort order, or its column order is
+/// [`ColumnOrder::Undefined`] and its sort order is not signed. The min and max of untrustworthy
+/// statistics are `None`, so that they are never used to skip data. Note that this is stricter
+/// than some implementations, which also read the deprecated `min` and `max` of columns with an
+/// undefined sort order.
+/// # Error
+/// This function errors if it is not possible to read the statistics to the
+/// corresponding `physical_type`.
+pub fn deserialize_statistics_with_order(
+    statistics: &ParquetStatistics,
+    descriptor: ColumnDescriptor,
+    column_order: ColumnOrder,
+) -> Result<Arc<dyn Statistics>> {
+    let legacy;
+    let statistics = if statistics.min_value.is_none() && statistics.max_value.is_none() {
+        let is_ordered = is_legacy_min_max_ordered(&descriptor, column_order);
+        legacy = ParquetStatistics {
+            min_value: statistics.min.clone().filter(|_| is_ordered),
+            max_value: statistics.max.clone().filter(|_| is_ordered),
+            ..statistics.clone()
+        };
+        &legacy
+    } else {
+        statistics
+    };
+
     match descriptor.physical_type() {
         PhysicalType::Boolean => boolean::read(statistics),
         PhysicalType::Int32 => primitive::read::<i32>(statistics, descriptor),
@@ -108,7 +157,19 @@ pub fn deserialize_statistics(
         PhysicalType::Float => primitive::read::<f32>(statistics, descriptor),
         PhysicalType::Double => primitive::read::<f64>(statistics, descriptor),
         PhysicalType::ByteArray => binary::read(statistics, descriptor),
-        PhysicalType::FixedLenByteArray(size) => fixed_len_binary::read(statistics, *size, descriptor),
+        PhysicalType::FixedLenByteArray(size) => {
+            fixed_len_binary::read(statistics, *size, descriptor)
+        }
+    }
+}
+
+/// Writes the `min_value` and `max_value` of `statistics`, of the column `descriptor`, to its
+/// deprecated `min` and `max`, for older readers. Since these are ordered as signed values, they
+/// are only written for columns whose type is ordered as signed.
+pub fn write_legacy_min_max(statistics: &mut ParquetStatistics, descriptor: &ColumnDescriptor) {
+    if is_legacy_min_max_ordered(descriptor, ColumnOrder::Undefined) {
+        statistics.min = statistics.min_value.clone();
+        statistics.max = statistics.max_value.clone();
     }
 }
 
@@ -131,3 +192,106 @@ pub fn serialize_statistics(statistics: &dyn Statistics) -> ParquetStatistics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+
+    fn legacy(min: Vec<u8>, max: Vec<u8>) -> ParquetStatistics {
+        ParquetStatistics {
+            null_count: Some(0),
+            distinct_count: None,
+            max_value: None,
+            min_value: None,
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    #[test]
+    fn legacy_min_max() -> Result<()> {
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { REQUIRED INT32 a; REQUIRED BYTE_ARRAY b (UTF8); REQUIRED INT96 c; }",
+        )?;
+        let a = schema.columns()[0].clone();
+        let b = schema.columns()[1].clone();
+        let c = schema.columns()[2].clone();
+
+        // signed: read
+        let statistics = legacy((-1i32).to_le_bytes().to_vec(), 2i32.to_le_bytes().to_vec());
+        let result = deserialize_statistics(&statistics, a.clone())?;
+        let result = result.as_any().downcast_ref::<PrimitiveStatistics<i32>>();
+        assert_eq!(result.unwrap().min_value, Some(-1));
+        assert_eq!(result.unwrap().max_value, Some(2));
+
+        // unsigned without column order: untrustworthy
+        let statistics = legacy(vec![0xC3, 0xA9], b"a".to_vec());
+        let result = deserialize_statistics(&statistics, b.clone())?;
+        let result = result.as_any().downcast_ref::<BinaryStatistics>().unwrap();
+        assert_eq!(result.min_value, None);
+        assert_eq!(result.max_value, None);
+        assert_eq!(result.null_count, Some(0));
+
+        // unsigned with column order: read
+        let column_order = ColumnOrder::TypeDefinedOrder(SortOrder::Unsigned);
+        let statistics = legacy(b"a".to_vec(), vec![0xC3, 0xA9]);
+        let result = deserialize_statistics_with_order(&statistics, b.clone(), column_order)?;
+        let result = result.as_any().downcast_ref::<BinaryStatistics>().unwrap();
+        assert_eq!(result.min_value, Some(b"a".to_vec()));
+
+        // undefined sort order, with or without column order: untrustworthy
+        let statistics = legacy(vec![0; 12], vec![1; 12]);
+        for column_order in [
+            ColumnOrder::Undefined,
+            ColumnOrder::TypeDefinedOrder(SortOrder::Undefined),
+        ] {
+            let result = deserialize_statistics_with_order(&statistics, c.clone(), column_order)?;
+            let result = result
+                .as_any()
+                .downcast_ref::<PrimitiveStatistics<[u32; 3]>>()
+                .unwrap();
+            assert_eq!(result.min_value, None);
+            assert_eq!(result.max_value, None);
+        }
+
+        // min_value and max_value take precedence
+        let mut statistics = legacy(b"a".to_vec(), b"z".to_vec());
+        statistics.min_value = Some(b"b".to_vec());
+        statistics.max_value = Some(b"c".to_vec());
+        let result = deserialize_statistics(&statistics, b)?;
+        let result = result.as_any().downcast_ref::<BinaryStatistics>().unwrap();
+        assert_eq!(result.min_value, Some(b"b".to_vec()));
+        assert_eq!(result.max_value, Some(b"c".to_vec()));
+        Ok(())
+    }
+
+    #[test]
+    fn write_legacy() -> Result<()> {
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { REQUIRED INT32 a; REQUIRED INT32 b (UINT_32); }",
+        )?;
+        let statistics = |descriptor: &ColumnDescriptor| {
+            let mut statistics = serialize_statistics(&PrimitiveStatistics::<i32> {
+                descriptor: descriptor.clone(),
+                null_count: Some(0),
+                distinct_count: None,
+                max_value: Some(1),
+                min_value: Some(-1),
+            });
+            write_legacy_min_max(&mut statistics, descriptor);
+            statistics
+        };
+
+        let a = statistics(&schema.columns()[0]);
+        assert_eq!(a.min, a.min_value);
+        assert_eq!(a.max, a.max_value);
+
+        // legacy readers would order these as signed
+        let b = statistics(&schema.columns()[1]);
+        assert_eq!(b.min, None);
+        assert_eq!(b.max, None);
+        Ok(())
+    }
+}
@@ -12,6 +12,12 @@ pub trait NativeType: Sized + Copy + std::fmt::Debug + Send + Sync + 'static {
 
     fn ord(&self, other: &Self) -> std::cmp::Ordering;
 
+    /// Whether this value is a floating point `NaN`
+    #[inline]
+    fn is_nan(&self) -> bool {
+        false
+    }
+
     const TYPE: PhysicalType;
 }
 
@@ -34,6 +40,11 @@ macro_rules! native {
                 self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
             }
 
+            #[inline]
+            fn is_nan(&self) -> bool {
+                self.partial_cmp(self).is_none()
+            }
+
             const TYPE: PhysicalType = $physical_type;
         }
     };
@@ -95,10 +106,17 @@ impl NativeType for [u32; 3] {
 
     #[inline]
     fn ord(&self, other: &Self) -> std::cmp::Ordering {
-        int96_to_i64_ns(*self).ord(&int96_to_i64_ns(*other))
+        ord_int96(*self, *other)
     }
 }
 
+/// Compares two Int96 timestamps by their Julian day and then by their nanoseconds of the day,
+/// which, unlike [`int96_to_i64_ns`], does not overflow for any day.
+pub fn ord_int96(lhs: [u32; 3], rhs: [u32; 3]) -> std::cmp::Ordering {
+    let nanoseconds = |value: [u32; 3]| ((value[1] as u64) << 32) + value[0] as u64;
+    (lhs[2], nanoseconds(lhs)).cmp(&(rhs[2], nanoseconds(rhs)))
+}
+
 pub fn int96_to_i64_ns(value: [u32; 3]) -> i64 {
     const JULIAN_DAY_OF_EPOCH: i64 = 2_440_588;
     const SECONDS_PER_DAY: i64 = 86_400;
@@ -0,0 +1,46 @@
+use parquet_format_async_temp::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_async_temp::{
+    BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash, BloomFilterHeader, RowGroup,
+    SplitBlockAlgorithm, Uncompressed, XxHash,
+};
+
+use crate::bloom_filter::BloomFilter;
+use crate::error::Result;
+
+/// Serializes the bloom filters of all `row_groups` (per row group, per column), assuming that
+/// they are written starting at `offset`. The offset of each filter is set on the corresponding
+/// column chunk.
+pub(crate) fn serialize_bloom_filters(
+    mut offset: u64,
+    row_groups: &mut [RowGroup],
+    bloom_filters: &[Vec<Option<BloomFilter>>],
+) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    for (row_group, bloom_filters) in row_groups.iter_mut().zip(bloom_filters.iter()) {
+        for (column, bloom_filter) in row_group.columns.iter_mut().zip(bloom_filters.iter()) {
+            let bloom_filter = match bloom_filter {
+                Some(bloom_filter) => bloom_filter,
+                None => continue,
+            };
+            let bitset = bloom_filter.bitset();
+
+            let header = BloomFilterHeader {
+                num_bytes: bitset.len() as i32,
+                algorithm: BloomFilterAlgorithm::BLOCK(SplitBlockAlgorithm {}),
+                hash: BloomFilterHash::XXHASH(XxHash {}),
+                compression: BloomFilterCompression::UNCOMPRESSED(Uncompressed {}),
+            };
+            let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+            let header_length = header.write_to_out_protocol(&mut protocol)?;
+            buffer.extend_from_slice(bitset);
+
+            if let Some(meta_data) = column.meta_data.as_mut() {
+                meta_data.bloom_filter_offset = Some(offset as i64);
+            }
+            offset += (header_length + bitset.len()) as u64;
+        }
+    }
+
+    Ok(buffer)
+}
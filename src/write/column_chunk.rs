@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use std::convert::TryInto;
 use std::io::Write;
 
@@ -6,9 +5,10 @@ use futures::AsyncWrite;
 use parquet_format_async_temp::thrift::protocol::{
     TCompactOutputProtocol, TCompactOutputStreamProtocol, TOutputProtocol, TOutputStreamProtocol,
 };
-use parquet_format_async_temp::{ColumnChunk, ColumnMetaData};
+use parquet_format_async_temp::{ColumnChunk, ColumnMetaData, KeyValue, PageEncodingStats};
 
-use crate::statistics::serialize_statistics;
+use crate::encryption::ColumnEncryptor;
+use crate::statistics::{serialize_statistics, write_legacy_min_max, DISTINCT_SKETCH_KEY};
 use crate::FallibleStreamingIterator;
 use crate::{
     compression::Compression,
@@ -19,39 +19,100 @@ use crate::{
     schema::types::{physical_type_to_type, ParquetType},
 };
 
-use super::page::{write_page, write_page_async, PageWriteSpec};
-use super::statistics::reduce;
-use super::DynStreamingIterator;
+use super::page::{write_encrypted_page, write_page, write_page_async, PageWriteSpec};
+use super::statistics::{reduce, reduce_distinct};
+use super::{DynStreamingIterator, WriteOptions};
 
 pub fn write_column_chunk<'a, W, E>(
     writer: &mut W,
-    mut offset: u64,
+    offset: u64,
     descriptor: &ColumnDescriptor,
-    compression: Compression,
-    mut compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
-) -> Result<(ColumnChunk, u64)>
+    options: &WriteOptions,
+    compressed_pages: DynStreamingIterator<'a, CompressedPage, E>,
+    encryptor: Option<ColumnEncryptor>,
+) -> Result<(ColumnChunk, Vec<PageWriteSpec>, u64)>
 where
     W: Write,
     ParquetError: From<E>,
     E: std::error::Error,
 {
-    // write every page
+    let mut specs = write_pages(
+        writer,
+        offset,
+        descriptor,
+        options,
+        compressed_pages,
+        encryptor.as_ref(),
+    )?;
+    let pages_size = specs.iter().map(|spec| spec.bytes_written).sum::<u64>();
 
-    let initial = offset;
+    let (column_chunk, metadata_size) =
+        write_column_metadata(writer, &mut specs, descriptor, options, encryptor)?;
+    Ok((column_chunk, specs, pages_size + metadata_size))
+}
 
+/// Writes every page of `compressed_pages`, starting at `offset`, and returns their specs.
+pub(super) fn write_pages<W, E>(
+    writer: &mut W,
+    mut offset: u64,
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
+    encryptor: Option<&ColumnEncryptor>,
+) -> Result<Vec<PageWriteSpec>>
+where
+    W: Write,
+    ParquetError: From<E>,
+    E: std::error::Error,
+{
     let mut specs = vec![];
+    let mut data_pages = 0i16;
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page(writer, offset, compressed_page)?;
+        let spec = if let Some(encryptor) = encryptor {
+            let page_ordinal = data_pages;
+            if let CompressedPage::Data(_) = compressed_page {
+                data_pages = data_pages.checked_add(1).ok_or_else(|| {
+                    general_err!("Encrypted column chunks support at most 32767 data pages")
+                })?;
+            }
+            write_encrypted_page(
+                writer,
+                offset,
+                descriptor,
+                options,
+                compressed_page,
+                &encryptor.cipher,
+                page_ordinal,
+            )?
+        } else {
+            write_page(writer, offset, descriptor, options, compressed_page)?
+        };
         offset += spec.bytes_written;
         specs.push(spec);
     }
-    let mut bytes_written = offset - initial;
+    Ok(specs)
+}
+
+/// Writes the metadata of a column chunk whose pages were written with `specs`, and returns it
+/// together with the number of bytes written. The sketches of the pages are released.
+pub(super) fn write_column_metadata<W: Write>(
+    writer: &mut W,
+    specs: &mut [PageWriteSpec],
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    encryptor: Option<ColumnEncryptor>,
+) -> Result<(ColumnChunk, u64)> {
+    let mut column_chunk = build_column_chunk(specs, descriptor, options)?;
 
-    let column_chunk = build_column_chunk(&specs, descriptor, compression)?;
+    if let Some(encryptor) = encryptor {
+        // the metadata of encrypted columns is only written (encrypted) in the footer
+        column_chunk.crypto_metadata = Some(encryptor.crypto_metadata);
+        return Ok((column_chunk, 0));
+    }
 
     // write metadata
     let mut protocol = TCompactOutputProtocol::new(writer);
-    bytes_written += column_chunk.write_to_out_protocol(&mut protocol)? as u64;
+    let bytes_written = column_chunk.write_to_out_protocol(&mut protocol)? as u64;
     protocol.flush()?;
 
     Ok((column_chunk, bytes_written))
@@ -61,9 +122,9 @@ pub async fn write_column_chunk_async<W, E>(
     writer: &mut W,
     mut offset: u64,
     descriptor: &ColumnDescriptor,
-    compression: Compression,
+    options: &WriteOptions,
     mut compressed_pages: DynStreamingIterator<'_, CompressedPage, E>,
-) -> Result<(ColumnChunk, usize)>
+) -> Result<(ColumnChunk, Vec<PageWriteSpec>, usize)>
 where
     W: AsyncWrite + Unpin + Send,
     ParquetError: From<E>,
@@ -73,13 +134,13 @@ where
     // write every page
     let mut specs = vec![];
     while let Some(compressed_page) = compressed_pages.next()? {
-        let spec = write_page_async(writer, offset, compressed_page).await?;
+        let spec = write_page_async(writer, offset, descriptor, options, compressed_page).await?;
         offset += spec.bytes_written;
         specs.push(spec);
     }
     let mut bytes_written = (offset - initial) as usize;
 
-    let column_chunk = build_column_chunk(&specs, descriptor, compression)?;
+    let column_chunk = build_column_chunk(&mut specs, descriptor, options)?;
 
     // write metadata
     let mut protocol = TCompactOutputStreamProtocol::new(writer);
@@ -88,13 +149,13 @@ where
         .await?;
     protocol.flush().await?;
 
-    Ok((column_chunk, bytes_written))
+    Ok((column_chunk, specs, bytes_written))
 }
 
 fn build_column_chunk(
-    specs: &[PageWriteSpec],
+    specs: &mut [PageWriteSpec],
     descriptor: &ColumnDescriptor,
-    compression: Compression,
+    options: &WriteOptions,
 ) -> Result<ColumnChunk> {
     // compute stats to build header at the end of the chunk
 
@@ -108,7 +169,18 @@ fn build_column_chunk(
         .iter()
         .map(|x| x.header_size as i64 + x.header.uncompressed_page_size as i64)
         .sum();
-    let data_page_offset = specs.first().map(|spec| spec.offset).unwrap_or(0) as i64;
+    // SPEC: the dictionary page, if any, is the first page of the column chunk
+    let dictionary_page_offset = specs
+        .first()
+        .filter(|spec| is_dictionary_page(spec))
+        .map(|spec| spec.offset as i64);
+    let data_page_offset = specs
+        .iter()
+        .find(|spec| !is_dictionary_page(spec))
+        .or_else(|| specs.first())
+        .map(|spec| spec.offset)
+        .unwrap_or(0) as i64;
+    let column_start = dictionary_page_offset.unwrap_or(data_page_offset);
     let num_values = specs
         .iter()
         .map(|spec| {
@@ -150,13 +222,41 @@ fn build_column_chunk(
             }
         })
         .flatten()
-        .collect::<HashSet<_>>() // unique
-        .into_iter() // to vec
-        .collect();
+        .fold(vec![], |mut encodings, encoding| {
+            // unique, in order of first appearance so that the output is deterministic
+            if !encodings.contains(&encoding) {
+                encodings.push(encoding)
+            }
+            encodings
+        });
+
+    let encoding_stats = encoding_stats(specs);
 
     let statistics = specs.iter().map(|x| &x.statistics).collect::<Vec<_>>();
     let statistics = reduce(&statistics)?;
-    let statistics = statistics.map(|x| serialize_statistics(x.as_ref()));
+    let mut statistics = statistics.map(|x| serialize_statistics(x.as_ref()));
+    if let (Some(statistics), true) = (&mut statistics, options.write_legacy_min_max) {
+        write_legacy_min_max(statistics, descriptor);
+    }
+
+    // the sketches are no longer needed once merged
+    let sketches = specs
+        .iter_mut()
+        .filter(|spec| !is_dictionary_page(spec))
+        .map(|spec| spec.distinct_sketch.take())
+        .collect::<Vec<_>>();
+    let sketch = reduce_distinct(&sketches)?;
+    if let (Some(statistics), Some(sketch)) = (&mut statistics, &sketch) {
+        statistics.distinct_count = Some(sketch.estimate() as i64);
+    }
+    let key_value_metadata = sketch
+        .filter(|_| options.write_distinct_count_sketch)
+        .map(|sketch| {
+            vec![KeyValue {
+                key: DISTINCT_SKETCH_KEY.to_string(),
+                value: Some(sketch.to_hex()),
+            }]
+        });
 
     let type_ = match descriptor.type_() {
         ParquetType::PrimitiveType { physical_type, .. } => physical_type_to_type(physical_type).0,
@@ -171,22 +271,22 @@ fn build_column_chunk(
         type_,
         encodings,
         path_in_schema: descriptor.path_in_schema().to_vec(),
-        codec: compression.into(),
+        codec: Compression::from(options.compression).into(),
         num_values,
         total_uncompressed_size,
         total_compressed_size,
-        key_value_metadata: None,
+        key_value_metadata,
         data_page_offset,
         index_page_offset: None,
-        dictionary_page_offset: None,
+        dictionary_page_offset,
         statistics,
-        encoding_stats: None,
+        encoding_stats: Some(encoding_stats),
         bloom_filter_offset: None,
     };
 
     Ok(ColumnChunk {
         file_path: None, // same file for now.
-        file_offset: data_page_offset + total_compressed_size,
+        file_offset: column_start + total_compressed_size,
         meta_data: Some(metadata),
         offset_index_offset: None,
         offset_index_length: None,
@@ -196,3 +296,37 @@ fn build_column_chunk(
         encrypted_column_metadata: None,
     })
 }
+
+fn is_dictionary_page(spec: &PageWriteSpec) -> bool {
+    let type_ = spec.header.type_.try_into();
+    matches!(type_, Ok(PageType::DictionaryPage))
+}
+
+/// Returns the number of pages of each page type and encoding, in order of first appearance
+fn encoding_stats(specs: &[PageWriteSpec]) -> Vec<PageEncodingStats> {
+    let mut stats: Vec<PageEncodingStats> = vec![];
+    for spec in specs {
+        let header = &spec.header;
+        let encoding = if let Some(header) = &header.data_page_header {
+            header.encoding
+        } else if let Some(header) = &header.data_page_header_v2 {
+            header.encoding
+        } else if let Some(header) = &header.dictionary_page_header {
+            header.encoding
+        } else {
+            continue;
+        };
+        match stats
+            .iter_mut()
+            .find(|x| x.page_type == header.type_ && x.encoding == encoding)
+        {
+            Some(stat) => stat.count += 1,
+            None => stats.push(PageEncodingStats {
+                page_type: header.type_,
+                encoding,
+                count: 1,
+            }),
+        }
+    }
+    stats
+}
@@ -1,36 +1,38 @@
+use crate::compression::CodecRegistry;
 use crate::error::{ParquetError, Result};
+use crate::page::{CompressedDataPage, DataPage, EncodedPage};
 use crate::page::{CompressedDictPage, CompressedPage, DataPageHeader, EncodedDictPage};
-use crate::parquet_bridge::Compression;
+use crate::parquet_bridge::CompressionOptions;
 use crate::FallibleStreamingIterator;
-use crate::{
-    compression,
-    page::{CompressedDataPage, DataPage, EncodedPage},
-};
 
 /// Compresses a [`DataPage`] into a [`CompressedDataPage`].
 fn compress_data(
     page: DataPage,
     mut compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
+    codecs: &CodecRegistry,
 ) -> Result<CompressedDataPage> {
+    let num_rows = page.num_rows();
     let DataPage {
         mut buffer,
         header,
         dictionary_page,
         descriptor,
+        selected_rows,
+        distinct_sketch,
     } = page;
     let uncompressed_page_size = buffer.len();
-    if compression != Compression::Uncompressed {
+    if compression != CompressionOptions::Uncompressed {
         match &header {
             DataPageHeader::V1(_) => {
-                compression::compress(compression, &buffer, &mut compressed_buffer)?;
+                codecs.compress(compression, &buffer, &mut compressed_buffer)?;
             }
             DataPageHeader::V2(header) => {
                 let levels_byte_length = (header.repetition_levels_byte_length
                     + header.definition_levels_byte_length)
                     as usize;
                 compressed_buffer.extend_from_slice(&buffer[..levels_byte_length]);
-                compression::compress(
+                codecs.compress(
                     compression,
                     &buffer[levels_byte_length..],
                     &mut compressed_buffer,
@@ -40,28 +42,33 @@ fn compress_data(
     } else {
         std::mem::swap(&mut buffer, &mut compressed_buffer);
     };
-    Ok(CompressedDataPage::new(
+    let mut page = CompressedDataPage::new(
         header,
         compressed_buffer,
-        compression,
+        compression.into(),
         uncompressed_page_size,
         dictionary_page,
         descriptor,
-    ))
+    );
+    page.num_rows = num_rows;
+    page.selected_rows = selected_rows;
+    page.distinct_sketch = distinct_sketch;
+    Ok(page)
 }
 
 fn compress_dict(
     page: EncodedDictPage,
     mut compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
+    codecs: &CodecRegistry,
 ) -> Result<CompressedDictPage> {
     let EncodedDictPage {
         mut buffer,
         num_values,
     } = page;
     let uncompressed_page_size = buffer.len();
-    if compression != Compression::Uncompressed {
-        compression::compress(compression, &buffer, &mut compressed_buffer)?;
+    if compression != CompressionOptions::Uncompressed {
+        codecs.compress(compression, &buffer, &mut compressed_buffer)?;
     } else {
         std::mem::swap(&mut buffer, &mut compressed_buffer);
     }
@@ -72,17 +79,33 @@ fn compress_dict(
     ))
 }
 
+/// Compresses an [`EncodedPage`] into a [`CompressedPage`] with the builtin codecs.
 pub fn compress(
     page: EncodedPage,
     compressed_buffer: Vec<u8>,
-    compression: Compression,
+    compression: CompressionOptions,
+) -> Result<CompressedPage> {
+    compress_with_codecs(
+        page,
+        compressed_buffer,
+        compression,
+        &CodecRegistry::default(),
+    )
+}
+
+/// Compresses an [`EncodedPage`] into a [`CompressedPage`] with the codecs of `codecs`.
+pub fn compress_with_codecs(
+    page: EncodedPage,
+    compressed_buffer: Vec<u8>,
+    compression: CompressionOptions,
+    codecs: &CodecRegistry,
 ) -> Result<CompressedPage> {
     match page {
         EncodedPage::Data(page) => {
-            compress_data(page, compressed_buffer, compression).map(CompressedPage::Data)
+            compress_data(page, compressed_buffer, compression, codecs).map(CompressedPage::Data)
         }
         EncodedPage::Dict(page) => {
-            compress_dict(page, compressed_buffer, compression).map(CompressedPage::Dict)
+            compress_dict(page, compressed_buffer, compression, codecs).map(CompressedPage::Dict)
         }
     }
 }
@@ -91,24 +114,32 @@ pub fn compress(
 /// holding a reusable buffer ([`Vec<u8>`]) for compression.
 pub struct Compressor<I: Iterator<Item = Result<EncodedPage>>> {
     iter: I,
-    compression: Compression,
+    compression: CompressionOptions,
+    codecs: CodecRegistry,
     buffer: Vec<u8>,
     current: Option<CompressedPage>,
 }
 
 impl<I: Iterator<Item = Result<EncodedPage>>> Compressor<I> {
-    pub fn new_from_vec(iter: I, compression: Compression, buffer: Vec<u8>) -> Self {
+    pub fn new_from_vec(iter: I, compression: CompressionOptions, buffer: Vec<u8>) -> Self {
         Self::new(iter, compression, buffer)
     }
 
-    pub fn new(iter: I, compression: Compression, buffer: Vec<u8>) -> Self {
+    pub fn new(iter: I, compression: CompressionOptions, buffer: Vec<u8>) -> Self {
         Self {
             iter,
             compression,
+            codecs: CodecRegistry::default(),
             buffer,
             current: None,
         }
     }
+
+    /// Sets the [`CodecRegistry`] used to compress the pages.
+    pub fn with_codecs(mut self, codecs: CodecRegistry) -> Self {
+        self.codecs = codecs;
+        self
+    }
 }
 
 impl<I: Iterator<Item = Result<EncodedPage>>> FallibleStreamingIterator for Compressor<I> {
@@ -126,7 +157,11 @@ impl<I: Iterator<Item = Result<EncodedPage>>> FallibleStreamingIterator for Comp
         let next = self
             .iter
             .next()
-            .map(|x| x.and_then(|page| compress(page, compressed_buffer, self.compression)))
+            .map(|x| {
+                x.and_then(|page| {
+                    compress_with_codecs(page, compressed_buffer, self.compression, &self.codecs)
+                })
+            })
             .transpose()?;
         self.current = next;
         Ok(())
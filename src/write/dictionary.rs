@@ -0,0 +1,627 @@
+//! Dictionary encoders of columns.
+//!
+//! Each encoder deduplicates the values of a column chunk into a dictionary, encodes every page
+//! it receives as `RLE_DICTIONARY` indices into that dictionary and, once the dictionary would
+//! exceed [`DictionaryOptions::max_dictionary_size`], falls back to encode the remaining pages as
+//! `PLAIN`. [`PrimitiveDictEncoder::finish`] returns the dictionary page followed by the data pages,
+//! in the order they must be written to the column chunk.
+//!
+//! The encoders only support non-repeated columns.
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use parquet_format_async_temp::Statistics as ParquetStatistics;
+
+use crate::compression::CompressionOptions;
+use crate::encoding::hybrid_rle::encode_u32;
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{
+    DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedDictPage, EncodedPage,
+};
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    serialize_statistics, write_legacy_min_max, BinaryStatistics, HyperLogLog, PrimitiveStatistics,
+};
+use crate::types::NativeType;
+
+use super::nested::encode_levels;
+use super::{Version, WriteOptions};
+
+/// Options of the dictionary encoders
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DictionaryOptions {
+    /// The maximum size in bytes of the (plain-encoded) dictionary page of a column chunk.
+    /// Pages whose values would make the dictionary exceed it are encoded as `PLAIN`, and so are
+    /// all pages after them.
+    pub max_dictionary_size: usize,
+}
+
+impl Default for DictionaryOptions {
+    fn default() -> Self {
+        Self {
+            max_dictionary_size: 1024 * 1024,
+        }
+    }
+}
+
+/// The physical-type independent part of the encoders. Values are identified by their bytes,
+/// and `encode_plain` plain-encodes them.
+#[derive(Debug)]
+struct Encoder {
+    descriptor: ColumnDescriptor,
+    options: WriteOptions,
+    max_dictionary_size: usize,
+    encode_plain: fn(&[u8], &mut Vec<u8>),
+    // the index of each value in the dictionary
+    indices: HashMap<Vec<u8>, u32>,
+    // the plain-encoded dictionary
+    dictionary: Vec<u8>,
+    fallback: bool,
+    is_dictionary_encoded: bool,
+    pages: Vec<DataPage>,
+}
+
+impl Encoder {
+    fn try_new(
+        descriptor: ColumnDescriptor,
+        options: WriteOptions,
+        dictionary_options: DictionaryOptions,
+        encode_plain: fn(&[u8], &mut Vec<u8>),
+    ) -> Result<Self> {
+        if descriptor.max_rep_level() > 0 {
+            return Err(general_err!(
+                "The dictionary encoders only support non-repeated columns"
+            ));
+        }
+        if let Some(precision) = options.distinct_count_precision {
+            HyperLogLog::try_new(precision)?;
+        }
+        Ok(Self {
+            descriptor,
+            options,
+            max_dictionary_size: dictionary_options.max_dictionary_size,
+            encode_plain,
+            indices: HashMap::new(),
+            dictionary: vec![],
+            fallback: false,
+            is_dictionary_encoded: false,
+            pages: vec![],
+        })
+    }
+
+    /// Inserts the values of `values` in the dictionary and returns their indices, or `None`
+    /// (leaving the dictionary unchanged) if they do not fit in it.
+    fn try_insert<A: AsRef<[u8]>>(&mut self, values: &[Option<A>]) -> Option<Vec<u32>> {
+        let num_entries = self.indices.len() as u32;
+        let dictionary_size = self.dictionary.len();
+
+        let mut indices = Vec::with_capacity(values.len());
+        for value in values.iter().flatten() {
+            let value = value.as_ref();
+            let index = match self.indices.get(value) {
+                Some(index) => *index,
+                None => {
+                    (self.encode_plain)(value, &mut self.dictionary);
+                    if self.dictionary.len() > self.max_dictionary_size {
+                        self.dictionary.truncate(dictionary_size);
+                        self.indices.retain(|_, index| *index < num_entries);
+                        return None;
+                    }
+                    let index = self.indices.len() as u32;
+                    self.indices.insert(value.to_vec(), index);
+                    index
+                }
+            };
+            indices.push(index);
+        }
+        Some(indices)
+    }
+
+    fn encode_page<A: AsRef<[u8]>>(
+        &mut self,
+        values: &[Option<A>],
+        mut statistics: Option<ParquetStatistics>,
+    ) -> Result<()> {
+        if let (Some(statistics), true) = (&mut statistics, self.options.write_legacy_min_max) {
+            write_legacy_min_max(statistics, &self.descriptor);
+        }
+        let distinct_sketch = match (&mut statistics, self.options.distinct_count_precision) {
+            (Some(statistics), Some(precision)) => {
+                let mut sketch = HyperLogLog::try_new(precision)?;
+                values
+                    .iter()
+                    .flatten()
+                    .for_each(|value| sketch.insert_bytes(value));
+                statistics.distinct_count = Some(sketch.estimate() as i64);
+                Some(sketch)
+            }
+            _ => None,
+        };
+        let num_nulls = values.iter().filter(|x| x.is_none()).count();
+        let max_def_level = self.descriptor.max_def_level();
+        if max_def_level == 0 && num_nulls > 0 {
+            return Err(general_err!("A required column cannot contain nulls"));
+        }
+
+        let mut buffer = vec![];
+        let def_levels = values
+            .iter()
+            .map(|x| if x.is_some() { max_def_level as u32 } else { 0 })
+            .collect::<Vec<_>>();
+        let definition_levels_byte_length = encode_levels(
+            &mut buffer,
+            &def_levels,
+            max_def_level,
+            self.options.version,
+        )?;
+
+        let indices = if self.fallback {
+            None
+        } else {
+            self.try_insert(values)
+        };
+        let encoding = if let Some(indices) = indices {
+            self.is_dictionary_encoded = true;
+            let max_index = (self.indices.len() as u32).saturating_sub(1);
+            let num_bits = (32 - max_index.leading_zeros()) as u8;
+            buffer.push(num_bits);
+            encode_u32(&mut buffer, indices.into_iter(), num_bits)?;
+            Encoding::RleDictionary
+        } else {
+            self.fallback = true;
+            values
+                .iter()
+                .flatten()
+                .for_each(|value| (self.encode_plain)(value.as_ref(), &mut buffer));
+            Encoding::Plain
+        };
+
+        let header = match self.options.version {
+            Version::V1 => DataPageHeader::V1(DataPageHeaderV1 {
+                num_values: values.len() as i32,
+                encoding: encoding.into(),
+                definition_level_encoding: Encoding::Rle.into(),
+                repetition_level_encoding: Encoding::Rle.into(),
+                statistics,
+            }),
+            Version::V2 => DataPageHeader::V2(DataPageHeaderV2 {
+                num_values: values.len() as i32,
+                num_nulls: num_nulls as i32,
+                num_rows: values.len() as i32,
+                encoding: encoding.into(),
+                definition_levels_byte_length: definition_levels_byte_length as i32,
+                repetition_levels_byte_length: 0,
+                is_compressed: Some(self.options.compression != CompressionOptions::Uncompressed),
+                statistics,
+            }),
+        };
+
+        let mut page = DataPage::new(header, buffer, None, self.descriptor.clone());
+        page.set_distinct_sketch(distinct_sketch);
+        self.pages.push(page);
+        Ok(())
+    }
+
+    fn finish(self) -> Vec<EncodedPage> {
+        let dict_page = if self.is_dictionary_encoded {
+            Some(EncodedPage::Dict(EncodedDictPage::new(
+                self.dictionary,
+                self.indices.len(),
+            )))
+        } else {
+            None
+        };
+        dict_page
+            .into_iter()
+            .chain(self.pages.into_iter().map(EncodedPage::Data))
+            .collect()
+    }
+}
+
+fn check_physical_type(descriptor: &ColumnDescriptor, expected: &PhysicalType) -> Result<()> {
+    if descriptor.physical_type() != expected {
+        return Err(general_err!(
+            "The column's physical type {:?} is not {:?}",
+            descriptor.physical_type(),
+            expected
+        ));
+    }
+    Ok(())
+}
+
+fn encode_plain_fixed(value: &[u8], buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(value)
+}
+
+fn encode_plain_binary(value: &[u8], buffer: &mut Vec<u8>) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value)
+}
+
+/// Dictionary-encodes the pages of a column chunk whose physical type is `T`.
+#[derive(Debug)]
+pub struct PrimitiveDictEncoder<T: NativeType> {
+    encoder: Encoder,
+    phantom: PhantomData<T>,
+}
+
+impl<T: NativeType> PrimitiveDictEncoder<T> {
+    /// Returns a new [`PrimitiveDictEncoder`].
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `T` or the column is repeated.
+    pub fn try_new(
+        descriptor: ColumnDescriptor,
+        options: WriteOptions,
+        dictionary_options: DictionaryOptions,
+    ) -> Result<Self> {
+        check_physical_type(&descriptor, &T::TYPE)?;
+        Ok(Self {
+            encoder: Encoder::try_new(descriptor, options, dictionary_options, encode_plain_fixed)?,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Encodes `values` into a new data page.
+    /// # Error
+    /// Errors iff the column is required and `values` contains nulls.
+    pub fn encode_page(&mut self, values: &[Option<T>]) -> Result<()> {
+        let statistics = if self.encoder.options.write_statistics {
+            // NaN is not part of the min and max, as it has no order
+            let non_nan = values.iter().flatten().filter(|x| !x.is_nan());
+            let statistics = PrimitiveStatistics {
+                descriptor: self.encoder.descriptor.clone(),
+                null_count: Some(values.iter().filter(|x| x.is_none()).count() as i64),
+                distinct_count: None,
+                max_value: non_nan.clone().max_by(|x, y| x.ord(y)).copied(),
+                min_value: non_nan.min_by(|x, y| x.ord(y)).copied(),
+            };
+            Some(serialize_statistics(&statistics))
+        } else {
+            None
+        };
+
+        let values = values
+            .iter()
+            .map(|x| x.map(|x| x.to_le_bytes()))
+            .collect::<Vec<_>>();
+        self.encoder.encode_page(&values, statistics)
+    }
+
+    /// Returns the pages of the column chunk: the dictionary page, if any page was
+    /// dictionary-encoded, followed by the data pages.
+    pub fn finish(self) -> Vec<EncodedPage> {
+        self.encoder.finish()
+    }
+}
+
+/// Dictionary-encodes the pages of a column chunk of physical type `ByteArray`.
+#[derive(Debug)]
+pub struct BinaryDictEncoder {
+    encoder: Encoder,
+}
+
+impl BinaryDictEncoder {
+    /// Returns a new [`BinaryDictEncoder`].
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `ByteArray` or the column is repeated.
+    pub fn try_new(
+        descriptor: ColumnDescriptor,
+        options: WriteOptions,
+        dictionary_options: DictionaryOptions,
+    ) -> Result<Self> {
+        check_physical_type(&descriptor, &PhysicalType::ByteArray)?;
+        Ok(Self {
+            encoder: Encoder::try_new(
+                descriptor,
+                options,
+                dictionary_options,
+                encode_plain_binary,
+            )?,
+        })
+    }
+
+    /// Encodes `values` into a new data page.
+    /// # Error
+    /// Errors iff the column is required and `values` contains nulls.
+    pub fn encode_page<A: AsRef<[u8]>>(&mut self, values: &[Option<A>]) -> Result<()> {
+        let statistics = if self.encoder.options.write_statistics {
+            let statistics = BinaryStatistics {
+                descriptor: self.encoder.descriptor.clone(),
+                null_count: Some(values.iter().filter(|x| x.is_none()).count() as i64),
+                distinct_count: None,
+                max_value: values
+                    .iter()
+                    .flatten()
+                    .map(|x| x.as_ref())
+                    .max()
+                    .map(|x| x.to_vec()),
+                min_value: values
+                    .iter()
+                    .flatten()
+                    .map(|x| x.as_ref())
+                    .min()
+                    .map(|x| x.to_vec()),
+            };
+            Some(serialize_statistics(&statistics))
+        } else {
+            None
+        };
+        self.encoder.encode_page(values, statistics)
+    }
+
+    /// Returns the pages of the column chunk: the dictionary page, if any page was
+    /// dictionary-encoded, followed by the data pages.
+    pub fn finish(self) -> Vec<EncodedPage> {
+        self.encoder.finish()
+    }
+}
+
+/// Dictionary-encodes the pages of a column chunk of physical type `FixedLenByteArray`.
+///
+/// Its pages have no statistics, since the order of fixed-length binary values depends on
+/// their logical type.
+#[derive(Debug)]
+pub struct FixedLenBinaryDictEncoder {
+    encoder: Encoder,
+    size: usize,
+}
+
+impl FixedLenBinaryDictEncoder {
+    /// Returns a new [`FixedLenBinaryDictEncoder`].
+    /// # Error
+    /// Errors iff the physical type of `descriptor` is not `FixedLenByteArray` or the column is
+    /// repeated.
+    pub fn try_new(
+        descriptor: ColumnDescriptor,
+        options: WriteOptions,
+        dictionary_options: DictionaryOptions,
+    ) -> Result<Self> {
+        let size = if let PhysicalType::FixedLenByteArray(size) = descriptor.physical_type() {
+            *size as usize
+        } else {
+            return Err(general_err!(
+                "The column's physical type {:?} is not FixedLenByteArray",
+                descriptor.physical_type()
+            ));
+        };
+        Ok(Self {
+            encoder: Encoder::try_new(descriptor, options, dictionary_options, encode_plain_fixed)?,
+            size,
+        })
+    }
+
+    /// Encodes `values` into a new data page.
+    /// # Error
+    /// Errors iff a value's length is not the column's size, or the column is required and
+    /// `values` contains nulls.
+    pub fn encode_page<A: AsRef<[u8]>>(&mut self, values: &[Option<A>]) -> Result<()> {
+        if values
+            .iter()
+            .flatten()
+            .any(|x| x.as_ref().len() != self.size)
+        {
+            return Err(general_err!(
+                "All values of a FixedLenByteArray({}) column must have {} bytes",
+                self.size,
+                self.size
+            ));
+        }
+        self.encoder.encode_page(values, None)
+    }
+
+    /// Returns the pages of the column chunk: the dictionary page, if any page was
+    /// dictionary-encoded, followed by the data pages.
+    pub fn finish(self) -> Vec<EncodedPage> {
+        self.encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::encoding::hybrid_rle::HybridRleDecoder;
+    use crate::page::split_buffer;
+    use crate::tests::descriptor;
+
+    fn options(version: Version) -> WriteOptions {
+        WriteOptions {
+            write_statistics: false,
+            version,
+            ..Default::default()
+        }
+    }
+
+    fn data_page(page: &EncodedPage) -> &DataPage {
+        match page {
+            EncodedPage::Data(page) => page,
+            EncodedPage::Dict(_) => panic!("expected a data page"),
+        }
+    }
+
+    fn indices(page: &DataPage, num_values: usize) -> Vec<u32> {
+        let (_, _, values) = split_buffer(page, page.descriptor());
+        let num_bits = values[0] as u32;
+        HybridRleDecoder::new(&values[1..], num_bits, num_values).collect()
+    }
+
+    #[test]
+    fn primitive() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED INT32 col; }", 0);
+        let mut encoder = PrimitiveDictEncoder::<i32>::try_new(
+            descriptor,
+            options(Version::V1),
+            Default::default(),
+        )?;
+        encoder.encode_page(&[Some(3), Some(1), Some(3), Some(3), Some(2)])?;
+        let pages = encoder.finish();
+        assert_eq!(pages.len(), 2);
+
+        match &pages[0] {
+            EncodedPage::Dict(dict) => {
+                assert_eq!(dict.num_values, 3);
+                assert_eq!(dict.buffer, vec![3, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+            }
+            EncodedPage::Data(_) => panic!("expected a dictionary page"),
+        }
+        let page = data_page(&pages[1]);
+        assert_eq!(page.encoding(), Encoding::RleDictionary);
+        assert_eq!(indices(page, 5), vec![0, 1, 0, 0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn optional_binary_v2() -> Result<()> {
+        let descriptor = descriptor("message schema { OPTIONAL BYTE_ARRAY col; }", 0);
+        let mut encoder =
+            BinaryDictEncoder::try_new(descriptor, options(Version::V2), Default::default())?;
+        encoder.encode_page(&[Some(b"aa".as_ref()), None, Some(b"b"), Some(b"aa")])?;
+        let pages = encoder.finish();
+        assert_eq!(pages.len(), 2);
+
+        match &pages[0] {
+            EncodedPage::Dict(dict) => {
+                assert_eq!(dict.num_values, 2);
+                assert_eq!(dict.buffer, vec![2, 0, 0, 0, b'a', b'a', 1, 0, 0, 0, b'b']);
+            }
+            EncodedPage::Data(_) => panic!("expected a dictionary page"),
+        }
+        let page = data_page(&pages[1]);
+        match page.header() {
+            DataPageHeader::V2(header) => {
+                assert_eq!(header.num_nulls, 1);
+                assert_eq!(header.num_rows, 4);
+            }
+            DataPageHeader::V1(_) => panic!("expected a v2 page"),
+        }
+        // only the non-null values have indices
+        assert_eq!(indices(page, 3), vec![0, 1, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn fallback() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED INT64 col; }", 0);
+        let dictionary_options = DictionaryOptions {
+            max_dictionary_size: 3 * 8,
+        };
+        let mut encoder = PrimitiveDictEncoder::<i64>::try_new(
+            descriptor,
+            options(Version::V1),
+            dictionary_options,
+        )?;
+        encoder.encode_page(&[Some(1), Some(2), Some(1)])?;
+        // would grow the dictionary to 4 entries
+        encoder.encode_page(&[Some(3), Some(4)])?;
+        // fits in the dictionary, but the encoder has already fallen back
+        encoder.encode_page(&[Some(1)])?;
+        let pages = encoder.finish();
+        assert_eq!(pages.len(), 4);
+
+        match &pages[0] {
+            EncodedPage::Dict(dict) => {
+                // the entries of the page that did not fit are not in the dictionary
+                assert_eq!(dict.num_values, 2);
+                assert_eq!(dict.buffer.len(), 2 * 8);
+            }
+            EncodedPage::Data(_) => panic!("expected a dictionary page"),
+        }
+        assert_eq!(data_page(&pages[1]).encoding(), Encoding::RleDictionary);
+
+        let page = data_page(&pages[2]);
+        assert_eq!(page.encoding(), Encoding::Plain);
+        let expected = [3i64.to_le_bytes(), 4i64.to_le_bytes()].concat();
+        assert_eq!(page.buffer(), expected.as_slice());
+
+        assert_eq!(data_page(&pages[3]).encoding(), Encoding::Plain);
+        Ok(())
+    }
+
+    #[test]
+    fn plain_only() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED INT32 col; }", 0);
+        let dictionary_options = DictionaryOptions {
+            max_dictionary_size: 0,
+        };
+        let mut encoder = PrimitiveDictEncoder::<i32>::try_new(
+            descriptor,
+            options(Version::V1),
+            dictionary_options,
+        )?;
+        encoder.encode_page(&[Some(1)])?;
+        let pages = encoder.finish();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(data_page(&pages[0]).encoding(), Encoding::Plain);
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_nan() -> Result<()> {
+        let descriptor = descriptor("message schema { OPTIONAL FLOAT col; }", 0);
+        let options = WriteOptions {
+            write_statistics: true,
+            ..options(Version::V1)
+        };
+        let mut encoder =
+            PrimitiveDictEncoder::<f32>::try_new(descriptor, options, Default::default())?;
+        encoder.encode_page(&[Some(f32::NAN), Some(2.0), None, Some(1.0)])?;
+        encoder.encode_page(&[Some(f32::NAN), None])?;
+        let pages = encoder.finish();
+
+        let statistics = pages[1..]
+            .iter()
+            .map(|page| {
+                let statistics = data_page(page).statistics().unwrap()?;
+                let statistics = statistics
+                    .as_any()
+                    .downcast_ref::<PrimitiveStatistics<f32>>()
+                    .unwrap();
+                Ok((statistics.min_value, statistics.max_value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // NaN is neither the min nor the max
+        assert_eq!(statistics, vec![(Some(1.0), Some(2.0)), (None, None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let int32 = descriptor("message schema { REQUIRED INT32 col; }", 0);
+        assert!(PrimitiveDictEncoder::<i64>::try_new(
+            int32.clone(),
+            options(Version::V1),
+            Default::default()
+        )
+        .is_err());
+        assert!(BinaryDictEncoder::try_new(
+            int32.clone(),
+            options(Version::V1),
+            Default::default()
+        )
+        .is_err());
+
+        let mut encoder =
+            PrimitiveDictEncoder::<i32>::try_new(int32, options(Version::V1), Default::default())
+                .unwrap();
+        assert!(encoder.encode_page(&[Some(1), None]).is_err());
+
+        let repeated = descriptor("message schema { REPEATED INT32 col; }", 0);
+        assert!(PrimitiveDictEncoder::<i32>::try_new(
+            repeated,
+            options(Version::V1),
+            Default::default()
+        )
+        .is_err());
+
+        let fixed = descriptor(
+            "message schema { REQUIRED FIXED_LEN_BYTE_ARRAY(2) col; }",
+            0,
+        );
+        let mut encoder =
+            FixedLenBinaryDictEncoder::try_new(fixed, options(Version::V1), Default::default())
+                .unwrap();
+        assert!(encoder.encode_page(&[Some(b"abc")]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use std::convert::TryInto;
 use std::io::Write;
 
 use parquet_format_async_temp::FileMetaData;
@@ -8,12 +9,23 @@ use parquet_format_async_temp::RowGroup;
 
 pub use crate::metadata::KeyValue;
 use crate::{
+    bloom_filter::BloomFilter,
+    encryption::{FileEncryptionProperties, FileEncryptor, Module},
     error::{ParquetError, Result},
-    metadata::SchemaDescriptor,
-    FOOTER_SIZE, PARQUET_MAGIC,
+    metadata::{SchemaDescriptor, SortingColumn},
+    FOOTER_SIZE, PARQUET_MAGIC, PARQUET_MAGIC_ENCRYPTED,
 };
 
-use super::{row_group::write_row_group, RowGroupIter, WriteOptions};
+use super::{
+    bloom_filter::serialize_bloom_filters,
+    indexes::serialize_indexes,
+    page::PageWriteSpec,
+    row_group::{check_sorting_columns, write_row_group},
+    RowGroupIter, WriteOptions,
+};
+
+#[cfg(feature = "rayon")]
+use super::row_group::write_row_group_parallel;
 
 pub(super) fn start_file<W: Write>(writer: &mut W) -> Result<u64> {
     writer.write_all(&PARQUET_MAGIC)?;
@@ -26,16 +38,60 @@ pub(super) fn end_file<W: Write>(mut writer: &mut W, metadata: FileMetaData) ->
     let metadata_len = metadata.write_to_out_protocol(&mut protocol)? as i32;
     protocol.flush()?;
 
-    // Write footer
+    write_footer(writer, metadata_len, &PARQUET_MAGIC)?;
+    Ok(metadata_len as u64 + FOOTER_SIZE)
+}
+
+/// Writes the footer of an encrypted file: either the signed plaintext metadata or the crypto
+/// metadata followed by the encrypted metadata.
+fn end_encrypted_file<W: Write>(
+    writer: &mut W,
+    mut metadata: FileMetaData,
+    encryptor: &FileEncryptor,
+) -> Result<u64> {
+    let mut buffer = vec![];
+    let magic = if encryptor.plaintext_footer() {
+        metadata.encryption_algorithm = Some(encryptor.algorithm());
+        metadata.footer_signing_key_metadata = encryptor.footer_key_metadata();
+
+        let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+        metadata.write_to_out_protocol(&mut protocol)?;
+        let signature = encryptor.footer().sign(&buffer)?;
+        buffer.extend_from_slice(&signature);
+        PARQUET_MAGIC
+    } else {
+        let mut protocol = TCompactOutputProtocol::new(&mut buffer);
+        encryptor
+            .crypto_metadata()
+            .write_to_out_protocol(&mut protocol)?;
+
+        let mut serialized = vec![];
+        let mut protocol = TCompactOutputProtocol::new(&mut serialized);
+        metadata.write_to_out_protocol(&mut protocol)?;
+        let encrypted = encryptor.footer().encrypt(Module::footer(), &serialized)?;
+        buffer.extend_from_slice(&encrypted);
+        PARQUET_MAGIC_ENCRYPTED
+    };
+    writer.write_all(&buffer)?;
+
+    let metadata_len: i32 = buffer
+        .len()
+        .try_into()
+        .map_err(|_| general_err!("The footer of the file exceeds 2GB"))?;
+    write_footer(writer, metadata_len, &magic)?;
+    Ok(buffer.len() as u64 + FOOTER_SIZE)
+}
+
+fn write_footer<W: Write>(writer: &mut W, metadata_len: i32, magic: &[u8; 4]) -> Result<()> {
     let metadata_bytes = metadata_len.to_le_bytes();
     let mut footer_buffer = [0u8; FOOTER_SIZE as usize];
     (0..4).for_each(|i| {
         footer_buffer[i] = metadata_bytes[i];
     });
 
-    (&mut footer_buffer[4..]).write_all(&PARQUET_MAGIC)?;
+    (&mut footer_buffer[4..]).write_all(magic)?;
     writer.write_all(&footer_buffer)?;
-    Ok(metadata_len as u64 + FOOTER_SIZE)
+    Ok(())
 }
 
 /// An interface to write a parquet file.
@@ -49,6 +105,9 @@ pub struct FileWriter<W: Write> {
 
     offset: u64,
     row_groups: Vec<RowGroup>,
+    page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    bloom_filters: Vec<Vec<Option<BloomFilter>>>,
+    encryptor: Option<FileEncryptor>,
 }
 
 // Accessors
@@ -79,12 +138,38 @@ impl<W: Write> FileWriter<W> {
             created_by,
             offset: 0,
             row_groups: vec![],
+            page_specs: vec![],
+            bloom_filters: vec![],
+            encryptor: None,
         }
     }
 
+    /// Returns a new [`FileWriter`] that encrypts the file according to `properties`.
+    ///
+    /// Column and offset indexes of encrypted columns are encrypted with their column's key.
+    /// Bloom filters of encrypted columns are not supported.
+    /// This requires the `encryption` feature.
+    pub fn with_encryption(
+        writer: W,
+        schema: SchemaDescriptor,
+        options: WriteOptions,
+        created_by: Option<String>,
+        properties: FileEncryptionProperties,
+    ) -> Result<Self> {
+        let mut file = Self::new(writer, schema, options, created_by);
+        file.encryptor = Some(FileEncryptor::try_new(properties)?);
+        Ok(file)
+    }
+
     /// Writes the header of the file
     pub fn start(&mut self) -> Result<()> {
-        self.offset = start_file(&mut self.writer)? as u64;
+        self.offset = match &self.encryptor {
+            Some(encryptor) if !encryptor.plaintext_footer() => {
+                self.writer.write_all(&PARQUET_MAGIC_ENCRYPTED)?;
+                PARQUET_MAGIC_ENCRYPTED.len() as u64
+            }
+            _ => start_file(&mut self.writer)? as u64,
+        };
         Ok(())
     }
 
@@ -96,22 +181,147 @@ impl<W: Write> FileWriter<W> {
         ParquetError: From<E>,
         E: std::error::Error,
     {
-        if self.offset == 0 {
-            return Err(ParquetError::General(
-                "You must call `start` before writing the first row group".to_string(),
-            ));
-        }
-        let (group, size) = write_row_group(
+        self.check_started()?;
+        let ordinal = self.row_groups.len();
+        let (group, specs, size) = write_row_group(
             &mut self.writer,
             self.offset,
             self.schema.columns(),
-            self.options.compression,
+            &self.options,
             row_group,
             num_rows,
+            self.encryptor
+                .as_ref()
+                .map(|encryptor| encryptor.row_group(ordinal)),
         )?;
+        self.push_row_group(group, specs, size);
+        Ok(())
+    }
+
+    /// Writes a row group to the file like [`FileWriter::write`], declaring that its rows are
+    /// sorted by `sorting_columns`, the first being the primary sort key.
+    /// # Error
+    /// Errors if a sorting column is not a column of the schema.
+    pub fn write_with_sorting_columns<E>(
+        &mut self,
+        row_group: RowGroupIter<'_, E>,
+        num_rows: usize,
+        sorting_columns: Vec<SortingColumn>,
+    ) -> Result<()>
+    where
+        ParquetError: From<E>,
+        E: std::error::Error,
+    {
+        check_sorting_columns(&sorting_columns, self.schema.columns().len())?;
+        self.write(row_group, num_rows)?;
+        // the row group was just written
+        self.row_groups.last_mut().unwrap().sorting_columns = Some(sorting_columns);
+        Ok(())
+    }
+
+    /// Writes a row group to the file like [`FileWriter::write`], but compressing and encoding
+    /// its column chunks in parallel.
+    ///
+    /// Every column chunk of the row group is held in memory until it is written.
+    /// This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn write_parallel<E>(
+        &mut self,
+        row_group: RowGroupIter<'_, E>,
+        num_rows: usize,
+    ) -> Result<()>
+    where
+        ParquetError: From<E>,
+        E: std::error::Error,
+    {
+        self.check_started()?;
+        let ordinal = self.row_groups.len();
+        let (group, specs, size) = write_row_group_parallel(
+            &mut self.writer,
+            self.offset,
+            self.schema.columns(),
+            &self.options,
+            row_group,
+            num_rows,
+            self.encryptor
+                .as_ref()
+                .map(|encryptor| encryptor.row_group(ordinal)),
+        )?;
+        self.push_row_group(group, specs, size);
+        Ok(())
+    }
+
+    /// Writes a row group to the file like [`FileWriter::write_parallel`], declaring that its
+    /// rows are sorted by `sorting_columns`, the first being the primary sort key.
+    /// # Error
+    /// Errors if a sorting column is not a column of the schema.
+    /// This requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn write_parallel_with_sorting_columns<E>(
+        &mut self,
+        row_group: RowGroupIter<'_, E>,
+        num_rows: usize,
+        sorting_columns: Vec<SortingColumn>,
+    ) -> Result<()>
+    where
+        ParquetError: From<E>,
+        E: std::error::Error,
+    {
+        check_sorting_columns(&sorting_columns, self.schema.columns().len())?;
+        self.write_parallel(row_group, num_rows)?;
+        // the row group was just written
+        self.row_groups.last_mut().unwrap().sorting_columns = Some(sorting_columns);
+        Ok(())
+    }
+
+    fn check_started(&self) -> Result<()> {
+        if self.offset == 0 {
+            return Err(ParquetError::General(
+                "You must call `start` before writing the first row group".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn push_row_group(&mut self, group: RowGroup, specs: Vec<Vec<PageWriteSpec>>, size: u64) {
         self.offset += size;
         self.row_groups.push(group);
-        Ok(())
+        self.page_specs.push(specs);
+        self.bloom_filters.push(vec![]);
+    }
+
+    /// Adds the bloom filters of the last row group written, one per column (`None` for columns
+    /// without a bloom filter). They are written to the file on `end`.
+    /// # Error
+    /// Errors if a bloom filter is added to an encrypted column, which is not supported.
+    pub fn add_bloom_filters(&mut self, bloom_filters: Vec<Option<BloomFilter>>) -> Result<()> {
+        if bloom_filters.len() != self.schema.columns().len() {
+            return Err(general_err!(
+                "The number of bloom filters ({}) must equal the number of columns ({})",
+                bloom_filters.len(),
+                self.schema.columns().len()
+            ));
+        }
+        if let Some(row_group) = self.row_groups.last() {
+            let encrypted = row_group
+                .columns
+                .iter()
+                .zip(bloom_filters.iter())
+                .any(|(column, filter)| column.crypto_metadata.is_some() && filter.is_some());
+            if encrypted {
+                return Err(general_err!(
+                    "Writing bloom filters of encrypted columns is not supported"
+                ));
+            }
+        }
+        if let Some(last) = self.bloom_filters.last_mut() {
+            *last = bloom_filters;
+            Ok(())
+        } else {
+            Err(general_err!(
+                "You must write a row group before adding its bloom filters"
+            ))
+        }
     }
 
     /// Writes the footer of the parquet file. Returns the total size of the file and the
@@ -120,6 +330,26 @@ impl<W: Write> FileWriter<W> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write the bloom filters
+        let bloom_filters =
+            serialize_bloom_filters(self.offset, &mut self.row_groups, &self.bloom_filters)?;
+        self.writer.write_all(&bloom_filters)?;
+        self.offset += bloom_filters.len() as u64;
+
+        // write the column and offset indexes, which must precede the file metadata
+        let indexes = serialize_indexes(
+            self.offset,
+            &mut self.row_groups,
+            &self.page_specs,
+            self.encryptor.as_ref(),
+        )?;
+        self.writer.write_all(&indexes)?;
+        self.offset += indexes.len() as u64;
+
+        if let Some(encryptor) = &self.encryptor {
+            encryptor.encrypt_column_metadata(&mut self.row_groups)?;
+        }
+
         let metadata = FileMetaData::new(
             self.options.version.into(),
             self.schema.into_thrift()?,
@@ -132,7 +362,11 @@ impl<W: Write> FileWriter<W> {
             None,
         );
 
-        let len = end_file(&mut self.writer, metadata)?;
+        let len = if let Some(encryptor) = &self.encryptor {
+            end_encrypted_file(&mut self.writer, metadata, encryptor)?
+        } else {
+            end_file(&mut self.writer, metadata)?
+        };
         Ok((self.offset + len, self.writer))
     }
 }
@@ -0,0 +1,4 @@
+mod serialize;
+mod write;
+
+pub(crate) use write::serialize_indexes;
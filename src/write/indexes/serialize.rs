@@ -0,0 +1,89 @@
+use std::convert::TryInto;
+
+use parquet_format_async_temp::{BoundaryOrder, ColumnIndex, OffsetIndex, PageLocation};
+
+use crate::error::Result;
+use crate::page::PageType;
+use crate::statistics::serialize_statistics;
+use crate::write::page::PageWriteSpec;
+
+fn is_data_page(spec: &PageWriteSpec) -> bool {
+    let type_ = spec.header.type_.try_into();
+    matches!(type_, Ok(PageType::DataPage) | Ok(PageType::DataPageV2))
+}
+
+/// The number of values of the data page of `spec`, including nulls
+fn num_values(spec: &PageWriteSpec) -> Option<i64> {
+    let header = &spec.header;
+    header
+        .data_page_header
+        .as_ref()
+        .map(|header| header.num_values)
+        .or_else(|| header.data_page_header_v2.as_ref().map(|x| x.num_values))
+        .map(|x| x as i64)
+}
+
+/// Returns the [`ColumnIndex`] of the data pages in `pages`, or `None` if at least one
+/// data page has no statistics, or no min and max while not having only null values.
+pub fn serialize_column_index(pages: &[PageWriteSpec]) -> Result<Option<ColumnIndex>> {
+    let mut null_pages = Vec::with_capacity(pages.len());
+    let mut min_values = Vec::with_capacity(pages.len());
+    let mut max_values = Vec::with_capacity(pages.len());
+    let mut null_counts = Vec::with_capacity(pages.len());
+
+    for spec in pages.iter().filter(|spec| is_data_page(spec)) {
+        let statistics = match spec.statistics.as_ref() {
+            Some(statistics) => serialize_statistics(statistics.as_ref()),
+            None => return Ok(None),
+        };
+        let is_null_page =
+            statistics.null_count.is_some() && statistics.null_count == num_values(spec);
+        if is_null_page {
+            // SPEC: pages with only null values have empty byte arrays as min and max
+            null_pages.push(true);
+            min_values.push(vec![]);
+            max_values.push(vec![]);
+        } else {
+            match (statistics.min_value, statistics.max_value) {
+                (Some(min), Some(max)) => {
+                    null_pages.push(false);
+                    min_values.push(min);
+                    max_values.push(max);
+                }
+                _ => return Ok(None),
+            }
+        }
+        null_counts.push(statistics.null_count);
+    }
+
+    let null_counts = null_counts.into_iter().collect::<Option<Vec<_>>>();
+
+    Ok(Some(ColumnIndex {
+        null_pages,
+        min_values,
+        max_values,
+        boundary_order: BoundaryOrder::UNORDERED,
+        null_counts,
+    }))
+}
+
+/// Returns the [`OffsetIndex`] of the data pages in `pages`, or `None` if the number of rows
+/// of at least one data page is unknown.
+pub fn serialize_offset_index(pages: &[PageWriteSpec]) -> Option<OffsetIndex> {
+    let mut first_row_index = 0;
+    let page_locations = pages
+        .iter()
+        .filter(|spec| is_data_page(spec))
+        .map(|spec| {
+            let location = PageLocation {
+                offset: spec.offset as i64,
+                compressed_page_size: spec.bytes_written as i32,
+                first_row_index,
+            };
+            first_row_index += spec.num_rows? as i64;
+            Some(location)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(OffsetIndex { page_locations })
+}
@@ -0,0 +1,100 @@
+use parquet_format_async_temp::thrift::protocol::TCompactOutputProtocol;
+use parquet_format_async_temp::{ColumnChunk, RowGroup};
+
+use crate::encryption::{FileEncryptor, ModuleType};
+use crate::error::Result;
+use crate::write::page::PageWriteSpec;
+
+use super::serialize::{serialize_column_index, serialize_offset_index};
+
+/// Returns the serialized `index` of `column`, encrypted as a module of `type_` when the
+/// column chunk is encrypted.
+fn encrypt_index(
+    index: Vec<u8>,
+    column: &ColumnChunk,
+    row_group: usize,
+    ordinal: usize,
+    encryptor: Option<&FileEncryptor>,
+    type_: ModuleType,
+) -> Result<Vec<u8>> {
+    let encryptor = match (encryptor, &column.meta_data) {
+        (Some(encryptor), Some(meta_data)) if column.crypto_metadata.is_some() => {
+            encryptor.column(&meta_data.path_in_schema, row_group, ordinal)?
+        }
+        _ => None,
+    };
+    match encryptor {
+        Some(encryptor) => {
+            let module = encryptor.cipher.module(type_, 0);
+            encryptor.cipher.cipher.encrypt(module, &index)
+        }
+        None => Ok(index),
+    }
+}
+
+/// Serializes the column and offset indexes of all `row_groups`, whose pages are described by
+/// `specs` (per row group, per column), assuming that they are written starting at `offset`.
+///
+/// The offsets and lengths of each index are set on the corresponding column chunks. As
+/// recommended by the spec, all column indexes are written before all offset indexes.
+/// Indexes of encrypted column chunks are encrypted with `encryptor`.
+pub(crate) fn serialize_indexes(
+    mut offset: u64,
+    row_groups: &mut [RowGroup],
+    specs: &[Vec<Vec<PageWriteSpec>>],
+    encryptor: Option<&FileEncryptor>,
+) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    for (row_group_ordinal, (row_group, specs)) in
+        row_groups.iter_mut().zip(specs.iter()).enumerate()
+    {
+        for (ordinal, (column, pages)) in row_group.columns.iter_mut().zip(specs.iter()).enumerate()
+        {
+            if let Some(index) = serialize_column_index(pages)? {
+                let mut serialized = vec![];
+                let mut protocol = TCompactOutputProtocol::new(&mut serialized);
+                index.write_to_out_protocol(&mut protocol)?;
+                let serialized = encrypt_index(
+                    serialized,
+                    column,
+                    row_group_ordinal,
+                    ordinal,
+                    encryptor,
+                    ModuleType::ColumnIndex,
+                )?;
+                buffer.extend_from_slice(&serialized);
+                column.column_index_offset = Some(offset as i64);
+                column.column_index_length = Some(serialized.len() as i32);
+                offset += serialized.len() as u64;
+            }
+        }
+    }
+
+    for (row_group_ordinal, (row_group, specs)) in
+        row_groups.iter_mut().zip(specs.iter()).enumerate()
+    {
+        for (ordinal, (column, pages)) in row_group.columns.iter_mut().zip(specs.iter()).enumerate()
+        {
+            if let Some(index) = serialize_offset_index(pages) {
+                let mut serialized = vec![];
+                let mut protocol = TCompactOutputProtocol::new(&mut serialized);
+                index.write_to_out_protocol(&mut protocol)?;
+                let serialized = encrypt_index(
+                    serialized,
+                    column,
+                    row_group_ordinal,
+                    ordinal,
+                    encryptor,
+                    ModuleType::OffsetIndex,
+                )?;
+                buffer.extend_from_slice(&serialized);
+                column.offset_index_offset = Some(offset as i64);
+                column.offset_index_length = Some(serialized.len() as i32);
+                offset += serialized.len() as u64;
+            }
+        }
+    }
+
+    Ok(buffer)
+}
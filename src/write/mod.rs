@@ -1,6 +1,10 @@
+mod bloom_filter;
 mod column_chunk;
 mod compression;
+pub mod dictionary;
 mod file;
+mod indexes;
+pub mod nested;
 mod page;
 mod row_group;
 pub(self) mod statistics;
@@ -13,13 +17,13 @@ pub use stream::FileStreamer;
 mod dyn_iter;
 pub use dyn_iter::{DynIter, DynStreamingIterator};
 
-pub use compression::{compress, Compressor};
+pub use compression::{compress, compress_with_codecs, Compressor};
 
 pub use file::FileWriter;
 
 pub use row_group::ColumnOffsetsMetadata;
 
-use crate::compression::Compression;
+use crate::compression::CompressionOptions;
 use crate::page::CompressedPage;
 
 pub type RowGroupIter<'a, E> =
@@ -30,10 +34,46 @@ pub type RowGroupIter<'a, E> =
 pub struct WriteOptions {
     /// Whether to write statistics
     pub write_statistics: bool,
-    /// Whether to use compression
-    pub compression: Compression,
+    /// Whether to also write the min and max values of statistics to their deprecated `min` and
+    /// `max` fields, for older readers. See [`crate::statistics::write_legacy_min_max`].
+    pub write_legacy_min_max: bool,
+    /// The precision of the [`crate::statistics::HyperLogLog`] sketches used to estimate the
+    /// `distinct_count` of the statistics of pages and column chunks, or `None` to not estimate
+    /// it. Requires `write_statistics`.
+    /// Only the dictionary encoders ([`dictionary::PrimitiveDictEncoder`] and
+    /// [`dictionary::BinaryDictEncoder`]) estimate it: [`FileWriter`] writes the sketches of the
+    /// pages it is given, and the column chunks of pages without sketches have no estimate.
+    pub distinct_count_precision: Option<u8>,
+    /// Whether to also write the sketch of each column chunk, of `2^precision` bytes, to its
+    /// key-value metadata, so that readers can merge the distinct values of column chunks.
+    /// See [`crate::metadata::ColumnChunkMetaData::distinct_sketch`].
+    pub write_distinct_count_sketch: bool,
+    /// Whether to use compression, and at which level
+    pub compression: CompressionOptions,
     /// Which Parquet version to use
     pub version: Version,
+    /// The target size in bytes of the values of a data page, or `None` for no limit.
+    /// Used by writers that split a column into pages, such as [`nested::nested_pages`].
+    pub data_page_size_limit: Option<usize>,
+    /// The maximum number of rows of a data page, or `None` for no limit.
+    /// Used by writers that split a column into pages, such as [`nested::nested_pages`].
+    pub data_page_row_limit: Option<usize>,
+}
+
+impl Default for WriteOptions {
+    /// Statistics, uncompressed V1 pages, and no page limits.
+    fn default() -> Self {
+        Self {
+            write_statistics: true,
+            write_legacy_min_max: false,
+            distinct_count_precision: None,
+            write_distinct_count_sketch: false,
+            compression: CompressionOptions::Uncompressed,
+            version: Version::V1,
+            data_page_size_limit: None,
+            data_page_row_limit: None,
+        }
+    }
 }
 
 /// The parquet version to use
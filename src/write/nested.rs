@@ -0,0 +1,943 @@
+//! Shredding of nested columns into repetition and definition levels (Dremel).
+//!
+//! The inverse of [`crate::read::nested`]: given the [`Nested`] levels of a column, i.e. the
+//! offsets and validity of every nesting level between its top-level field and its leaf,
+//! [`levels`] computes the repetition and definition levels of its values and [`nested_page`]
+//! writes them to a [`DataPage`] of either version. [`nested_pages`] splits them into multiple
+//! pages at row boundaries, following the page limits of [`WriteOptions`].
+//!
+//! The levels must have the shape of the column's schema (see [`crate::read::nested`]): one
+//! [`Nested::List`] per `LIST`, `MAP` or repeated field, one [`Nested::Struct`] per group, and
+//! a [`Nested::Primitive`] leaf. Optional levels may have no validity (no nulls).
+use std::convert::TryFrom;
+use std::ops::Range;
+
+use parquet_format_async_temp::Statistics as ParquetStatistics;
+
+use crate::compression::CompressionOptions;
+use crate::encoding::hybrid_rle::{bitpacked_encode, encode_u32};
+use crate::encoding::Encoding;
+use crate::error::{ParquetError, Result};
+use crate::metadata::ColumnDescriptor;
+use crate::page::{DataPage, DataPageHeader, DataPageHeaderV1, DataPageHeaderV2, EncodedPage};
+use crate::read::levels::get_bit_width;
+use crate::read::nested::shape;
+use crate::schema::types::PhysicalType;
+use crate::statistics::{
+    serialize_statistics, write_legacy_min_max, BinaryStatistics, BooleanStatistics,
+    PrimitiveStatistics,
+};
+use crate::types::NativeType;
+
+pub use crate::read::nested::Nested;
+
+use super::{Version, WriteOptions};
+
+fn out_of_spec(message: &str) -> ParquetError {
+    ParquetError::OutOfSpec(format!("Invalid nested levels: {}", message))
+}
+
+/// Checks that `nested` has the shape `shape` and that its levels are consistent
+fn check(nested: &[Nested], shape: &[Nested]) -> Result<()> {
+    if nested.len() != shape.len() {
+        return Err(out_of_spec(
+            "the number of levels does not match the column's schema",
+        ));
+    }
+    for (i, (level, expected)) in nested.iter().zip(shape).enumerate() {
+        if std::mem::discriminant(level) != std::mem::discriminant(expected) {
+            return Err(out_of_spec(
+                "the kind of a level does not match the column's schema",
+            ));
+        }
+        if let Some(validity) = level.validity() {
+            if validity.len() != level.len() {
+                return Err(out_of_spec("a validity does not match its level's length"));
+            }
+            if expected.validity().is_none() && validity.iter().any(|x| !*x) {
+                return Err(out_of_spec("a required level cannot contain nulls"));
+            }
+        }
+        // the number of slots of the next level
+        let length = match level {
+            Nested::List { offsets, .. } => {
+                if offsets.is_empty() || offsets[0] != 0 || offsets.windows(2).any(|w| w[0] > w[1])
+                {
+                    return Err(out_of_spec(
+                        "the offsets of a list must start at 0 and be monotonically increasing",
+                    ));
+                }
+                offsets[offsets.len() - 1] as usize
+            }
+            _ => level.len(),
+        };
+        if let Some(next) = nested.get(i + 1) {
+            if next.len() != length {
+                return Err(out_of_spec(
+                    "the length of a level does not match its parent",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Computes the levels of the values of a slot of a level
+struct Shredder<'a> {
+    nested: &'a [Nested],
+    shape: &'a [Nested],
+    rep_levels: Vec<u32>,
+    def_levels: Vec<u32>,
+}
+
+impl<'a> Shredder<'a> {
+    /// Shreds the slot `slot` of the level `level`, whose first value has repetition level `rep`
+    /// and whose parent is defined at `def`.
+    fn shred(&mut self, level: usize, slot: usize, rep: u32, def: u32, list_rep: u32) {
+        let nested = &self.nested[level];
+        let is_optional = self.shape[level].validity().is_some();
+        let is_valid = nested.validity().map(|x| x[slot]).unwrap_or(true);
+        if !is_valid {
+            self.rep_levels.push(rep);
+            self.def_levels.push(def);
+            return;
+        }
+        let def = def + is_optional as u32;
+
+        match nested {
+            Nested::Primitive { .. } => {
+                self.rep_levels.push(rep);
+                self.def_levels.push(def);
+            }
+            Nested::Struct { .. } => self.shred(level + 1, slot, rep, def, list_rep),
+            Nested::List { offsets, .. } => {
+                let start = offsets[slot] as usize;
+                let end = offsets[slot + 1] as usize;
+                if start == end {
+                    self.rep_levels.push(rep);
+                    self.def_levels.push(def);
+                }
+                // SPEC: the first item continues the parent's repetition; the others repeat
+                // this list.
+                let list_rep = list_rep + 1;
+                for item in start..end {
+                    let rep = if item == start { rep } else { list_rep };
+                    self.shred(level + 1, item, rep, def + 1, list_rep);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the repetition and definition levels of the values of the column `descriptor`
+/// with the nesting levels `nested`.
+/// # Error
+/// Errors iff `nested` does not have the shape of the column's schema or is inconsistent.
+pub fn levels(descriptor: &ColumnDescriptor, nested: &[Nested]) -> Result<(Vec<u32>, Vec<u32>)> {
+    let shape = shape(descriptor)?;
+    check(nested, &shape)?;
+
+    let mut shredder = Shredder {
+        nested,
+        shape: &shape,
+        rep_levels: vec![],
+        def_levels: vec![],
+    };
+    for slot in 0..nested[0].len() {
+        shredder.shred(0, slot, 0, 0, 0);
+    }
+    Ok((shredder.rep_levels, shredder.def_levels))
+}
+
+/// Encodes the `levels` of a page, of at most `max_level`, to `buffer`, returning the number
+/// of bytes written
+pub(super) fn encode_levels(
+    buffer: &mut Vec<u8>,
+    levels: &[u32],
+    max_level: i16,
+    version: Version,
+) -> Result<usize> {
+    if max_level == 0 {
+        return Ok(0);
+    }
+    let num_bits = get_bit_width(max_level) as u8;
+    let start = buffer.len();
+    match version {
+        Version::V1 => {
+            // SPEC: v1 pages prefix the levels with their length
+            buffer.extend_from_slice(&[0; 4]);
+            encode_u32(buffer, levels.iter().copied(), num_bits)?;
+            let length = (buffer.len() - start - 4) as i32;
+            buffer[start..start + 4].copy_from_slice(&length.to_le_bytes());
+        }
+        Version::V2 => encode_u32(buffer, levels.iter().copied(), num_bits)?,
+    }
+    Ok(buffer.len() - start)
+}
+
+/// Returns a page of the column `descriptor` with the levels `levels` and the
+/// `encoding`-encoded `values`
+fn page(
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    levels: (&[u32], &[u32]),
+    values: &[u8],
+    encoding: Encoding,
+    statistics: Option<ParquetStatistics>,
+) -> Result<EncodedPage> {
+    let (rep_levels, def_levels) = levels;
+
+    let mut buffer = vec![];
+    let repetition_levels_byte_length = encode_levels(
+        &mut buffer,
+        rep_levels,
+        descriptor.max_rep_level(),
+        options.version,
+    )?;
+    let definition_levels_byte_length = encode_levels(
+        &mut buffer,
+        def_levels,
+        descriptor.max_def_level(),
+        options.version,
+    )?;
+    buffer.extend_from_slice(values);
+
+    let num_values = def_levels.len() as i32;
+    let header = match options.version {
+        Version::V1 => DataPageHeader::V1(DataPageHeaderV1 {
+            num_values,
+            encoding: encoding.into(),
+            definition_level_encoding: Encoding::Rle.into(),
+            repetition_level_encoding: Encoding::Rle.into(),
+            statistics,
+        }),
+        Version::V2 => {
+            let max_def_level = descriptor.max_def_level() as u32;
+            DataPageHeader::V2(DataPageHeaderV2 {
+                num_values,
+                num_nulls: def_levels.iter().filter(|x| **x < max_def_level).count() as i32,
+                num_rows: rep_levels.iter().filter(|x| **x == 0).count() as i32,
+                encoding: encoding.into(),
+                definition_levels_byte_length: definition_levels_byte_length as i32,
+                repetition_levels_byte_length: repetition_levels_byte_length as i32,
+                is_compressed: Some(options.compression != CompressionOptions::Uncompressed),
+                statistics,
+            })
+        }
+    };
+
+    Ok(EncodedPage::Data(DataPage::new(
+        header,
+        buffer,
+        None,
+        descriptor.clone(),
+    )))
+}
+
+/// Returns a [`DataPage`] of the column `descriptor` with the nesting levels `nested` and the
+/// `encoding`-encoded non-null `values` of its leaf.
+/// # Error
+/// Errors iff `nested` does not have the shape of the column's schema or is inconsistent.
+pub fn nested_page(
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    nested: &[Nested],
+    values: &[u8],
+    encoding: Encoding,
+    statistics: Option<ParquetStatistics>,
+) -> Result<EncodedPage> {
+    let (rep_levels, def_levels) = levels(descriptor, nested)?;
+    page(
+        descriptor,
+        options,
+        (&rep_levels, &def_levels),
+        values,
+        encoding,
+        statistics,
+    )
+}
+
+/// Returns the ranges of the levels and of the values of each page, splitting the levels at
+/// rows (a repetition level of 0) such that each page has at most `options.data_page_row_limit`
+/// rows and at most `options.data_page_size_limit` bytes of values, whose `sizes` are in bits.
+/// A row larger than the size limit is written to a page of its own.
+fn split(
+    rep_levels: &[u32],
+    def_levels: &[u32],
+    max_def_level: u32,
+    sizes: &[usize],
+    options: &WriteOptions,
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let row_limit = options.data_page_row_limit.unwrap_or(usize::MAX).max(1);
+    let size_limit = options
+        .data_page_size_limit
+        .map(|x| x.saturating_mul(8))
+        .unwrap_or(usize::MAX);
+
+    let mut pages = vec![];
+    let (mut level_start, mut value_start) = (0, 0);
+    let (mut rows, mut size) = (0, 0);
+    let (mut level, mut value) = (0, 0);
+    while level < rep_levels.len() {
+        // the levels and values of the row starting at `level`
+        let (row_level, row_value) = (level, value);
+        let mut row_size = 0;
+        loop {
+            if def_levels[level] == max_def_level {
+                row_size += sizes[value];
+                value += 1;
+            }
+            level += 1;
+            if level == rep_levels.len() || rep_levels[level] == 0 {
+                break;
+            }
+        }
+
+        // SPEC: pages must start at a new row
+        if rows > 0 && (rows == row_limit || size + row_size > size_limit) {
+            pages.push((level_start..row_level, value_start..row_value));
+            level_start = row_level;
+            value_start = row_value;
+            rows = 0;
+            size = 0;
+        }
+        rows += 1;
+        size += row_size;
+    }
+    if rows > 0 {
+        pages.push((level_start..rep_levels.len(), value_start..value));
+    }
+    pages
+}
+
+/// Returns the statistics of `values` of the native type `T`, each represented by its
+/// little-endian bytes. `NaN` is not part of the min and max, as it has no order.
+fn primitive_statistics<T: NativeType, A: AsRef<[u8]>>(
+    descriptor: &ColumnDescriptor,
+    values: &[A],
+    null_count: Option<i64>,
+) -> ParquetStatistics {
+    let values = values
+        .iter()
+        .filter_map(|x| T::Bytes::try_from(x.as_ref()).ok())
+        .map(T::from_le_bytes)
+        .filter(|x| !x.is_nan());
+    serialize_statistics(&PrimitiveStatistics {
+        descriptor: descriptor.clone(),
+        null_count,
+        distinct_count: None,
+        max_value: values.clone().max_by(|x, y| x.ord(y)),
+        min_value: values.min_by(|x, y| x.ord(y)),
+    })
+}
+
+/// Returns the statistics of a page of the column `descriptor` with the non-null `values` and
+/// `null_count` nulls, or `None` if `options` does not write statistics or the column is
+/// fixed-length binary.
+fn statistics<A: AsRef<[u8]>>(
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    values: &[A],
+    null_count: usize,
+) -> Option<ParquetStatistics> {
+    if !options.write_statistics {
+        return None;
+    }
+    let null_count = Some(null_count as i64);
+    let mut statistics = match descriptor.physical_type() {
+        PhysicalType::Boolean => {
+            let values = values.iter().map(|x| x.as_ref()[0] != 0);
+            serialize_statistics(&BooleanStatistics {
+                null_count,
+                distinct_count: None,
+                max_value: values.clone().max(),
+                min_value: values.min(),
+            })
+        }
+        PhysicalType::Int32 => primitive_statistics::<i32, _>(descriptor, values, null_count),
+        PhysicalType::Int64 => primitive_statistics::<i64, _>(descriptor, values, null_count),
+        PhysicalType::Int96 => primitive_statistics::<[u32; 3], _>(descriptor, values, null_count),
+        PhysicalType::Float => primitive_statistics::<f32, _>(descriptor, values, null_count),
+        PhysicalType::Double => primitive_statistics::<f64, _>(descriptor, values, null_count),
+        PhysicalType::ByteArray => {
+            let values = values.iter().map(|x| x.as_ref());
+            serialize_statistics(&BinaryStatistics {
+                descriptor: descriptor.clone(),
+                null_count,
+                distinct_count: None,
+                max_value: values.clone().max().map(|x| x.to_vec()),
+                min_value: values.min().map(|x| x.to_vec()),
+            })
+        }
+        // the order of fixed-length binary values depends on their logical type
+        PhysicalType::FixedLenByteArray(_) => return None,
+    };
+    if options.write_legacy_min_max {
+        write_legacy_min_max(&mut statistics, descriptor);
+    }
+    Some(statistics)
+}
+
+/// Returns the `PLAIN`-encoded data pages of the column `descriptor` with the nesting levels
+/// `nested` and the non-null `values` of its leaf, split into pages according to the
+/// `data_page_size_limit` and `data_page_row_limit` of `options`. Pages always start at a new
+/// row, so that no repeated value is split across pages.
+///
+/// Each value is represented by its bytes: the little-endian bytes of numbers (e.g.
+/// [`crate::types::NativeType::to_le_bytes`]), a byte of 0 (false) or 1 (true) for booleans,
+/// or the bytes of binary values. Booleans are bit-packed, so that each counts as a bit towards
+/// the size limit.
+///
+/// When `options.write_statistics` is set, each page has the statistics of its values, except
+/// for fixed-length binary columns, whose order depends on their logical type.
+/// # Error
+/// Errors iff `nested` does not have the shape of the column's schema or is inconsistent, the
+/// number of values does not match `nested`, or a value of a fixed-size physical type does not
+/// have its size.
+pub fn nested_pages<A: AsRef<[u8]>>(
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    nested: &[Nested],
+    values: &[A],
+) -> Result<Vec<EncodedPage>> {
+    let physical_type = descriptor.physical_type();
+    let size = match physical_type {
+        PhysicalType::Boolean => Some(1),
+        PhysicalType::Int32 | PhysicalType::Float => Some(4),
+        PhysicalType::Int64 | PhysicalType::Double => Some(8),
+        PhysicalType::Int96 => Some(12),
+        PhysicalType::FixedLenByteArray(size) => Some(*size as usize),
+        PhysicalType::ByteArray => None,
+    };
+    if let Some(size) = size {
+        if values.iter().any(|x| x.as_ref().len() != size) {
+            return Err(general_err!(
+                "All values of a {:?} column must have {} bytes",
+                physical_type,
+                size
+            ));
+        }
+    }
+
+    let (rep_levels, def_levels) = levels(descriptor, nested)?;
+    let max_def_level = descriptor.max_def_level() as u32;
+    if def_levels.iter().filter(|x| **x == max_def_level).count() != values.len() {
+        return Err(out_of_spec(
+            "the number of values does not match the validity of the leaf",
+        ));
+    }
+
+    // the size of each value in bits.
+    // SPEC: plain-encoded byte arrays are prefixed by their length
+    let sizes = values
+        .iter()
+        .map(|x| match physical_type {
+            PhysicalType::Boolean => 1,
+            PhysicalType::ByteArray => (4 + x.as_ref().len()) * 8,
+            _ => x.as_ref().len() * 8,
+        })
+        .collect::<Vec<_>>();
+
+    split(&rep_levels, &def_levels, max_def_level, &sizes, options)
+        .into_iter()
+        .map(|(levels, values_range)| {
+            let values = &values[values_range];
+            let mut buffer = vec![];
+            match physical_type {
+                PhysicalType::Boolean => {
+                    bitpacked_encode(&mut buffer, values.iter().map(|x| x.as_ref()[0] != 0))?
+                }
+                PhysicalType::ByteArray => values.iter().for_each(|value| {
+                    let value = value.as_ref();
+                    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(value);
+                }),
+                _ => values
+                    .iter()
+                    .for_each(|value| buffer.extend_from_slice(value.as_ref())),
+            }
+
+            let (rep_levels, def_levels) = (&rep_levels[levels.clone()], &def_levels[levels]);
+            let statistics =
+                statistics(descriptor, options, values, def_levels.len() - values.len());
+            page(
+                descriptor,
+                options,
+                (rep_levels, def_levels),
+                &buffer,
+                Encoding::Plain,
+                statistics,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::read::deserialize::{deserialize, Values};
+    use crate::read::nested::reconstruct_page;
+    use crate::tests::descriptor;
+
+    const LIST: &str = "message schema {
+        OPTIONAL group a (LIST) {
+            REPEATED group list {
+                OPTIONAL INT32 element;
+            }
+        }
+    }";
+
+    #[test]
+    fn list() -> Result<()> {
+        let descriptor = descriptor(LIST, 0);
+        // [[1, None, 2], [], None, [3]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 3, 3, 3, 4],
+                validity: Some(vec![true, true, false, true]),
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: Some(vec![true, false, true, true]),
+            },
+        ];
+        let (rep_levels, def_levels) = levels(&descriptor, &nested)?;
+        assert_eq!(rep_levels, vec![0, 1, 1, 0, 0, 0]);
+        assert_eq!(def_levels, vec![3, 2, 3, 1, 0, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_of_structs() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                OPTIONAL group a (LIST) {
+                    REPEATED group list {
+                        OPTIONAL group element {
+                            REQUIRED INT32 b;
+                            OPTIONAL INT32 c;
+                        }
+                    }
+                }
+            }",
+            1,
+        );
+        // [[{b: 1, c: 1}, None], None, [{b: 2, c: None}]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2, 3],
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Struct {
+                length: 3,
+                validity: Some(vec![true, false, true]),
+            },
+            Nested::Primitive {
+                length: 3,
+                validity: Some(vec![true, false, false]),
+            },
+        ];
+        let (rep_levels, def_levels) = levels(&descriptor, &nested)?;
+        assert_eq!(rep_levels, vec![0, 1, 0, 0]);
+        assert_eq!(def_levels, vec![4, 2, 0, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_of_lists() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                REPEATED group a {
+                    REPEATED INT32 b;
+                }
+            }",
+            0,
+        );
+        // [[{b: [1, 2]}, {b: []}], [], [{b: [3]}]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 2, 2, 3],
+                validity: None,
+            },
+            Nested::Struct {
+                length: 3,
+                validity: None,
+            },
+            Nested::List {
+                offsets: vec![0, 2, 2, 3],
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 3,
+                validity: None,
+            },
+        ];
+        let (rep_levels, def_levels) = levels(&descriptor, &nested)?;
+        assert_eq!(rep_levels, vec![0, 2, 1, 0, 0]);
+        assert_eq!(def_levels, vec![2, 2, 1, 0, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn roundtrip() -> Result<()> {
+        let descriptor = descriptor(LIST, 0);
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 3, 3, 3, 4],
+                validity: Some(vec![true, true, false, true]),
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: Some(vec![true, false, true, true]),
+            },
+        ];
+        let values = [1i32, 2, 3]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        for version in [Version::V1, Version::V2] {
+            let options = WriteOptions {
+                write_statistics: false,
+                version,
+                ..Default::default()
+            };
+            let page = match nested_page(
+                &descriptor,
+                &options,
+                &nested,
+                &values,
+                Encoding::Plain,
+                None,
+            )? {
+                EncodedPage::Data(page) => page,
+                _ => unreachable!(),
+            };
+            assert_eq!(page.num_values(), 6);
+
+            assert_eq!(reconstruct_page(&page, &descriptor)?, nested);
+            assert_eq!(
+                deserialize(&page, &descriptor)?,
+                Values::Int32(vec![Some(1), None, Some(2), None, None, Some(3)])
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn required_without_validity() -> Result<()> {
+        let descriptor = descriptor(LIST, 0);
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 1],
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 1,
+                validity: None,
+            },
+        ];
+        let (rep_levels, def_levels) = levels(&descriptor, &nested)?;
+        assert_eq!(rep_levels, vec![0]);
+        assert_eq!(def_levels, vec![3]);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let descriptor = descriptor(LIST, 0);
+        let primitive = Nested::Primitive {
+            length: 1,
+            validity: None,
+        };
+        // wrong shape
+        assert!(levels(&descriptor, std::slice::from_ref(&primitive)).is_err());
+        // offsets beyond the leaf
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 2],
+                validity: None,
+            },
+            primitive.clone(),
+        ];
+        assert!(levels(&descriptor, &nested).is_err());
+        // decreasing offsets
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 1, 0],
+                validity: None,
+            },
+            primitive,
+        ];
+        assert!(levels(&descriptor, &nested).is_err());
+
+        // nulls in a required level
+        let descriptor = self::descriptor("message schema { REPEATED INT32 a; }", 0);
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 1],
+                validity: Some(vec![false]),
+            },
+            Nested::Primitive {
+                length: 1,
+                validity: None,
+            },
+        ];
+        assert!(levels(&descriptor, &nested).is_err());
+    }
+
+    fn write_options(size_limit: Option<usize>, row_limit: Option<usize>) -> WriteOptions {
+        WriteOptions {
+            write_statistics: false,
+            version: Version::V2,
+            data_page_size_limit: size_limit,
+            data_page_row_limit: row_limit,
+            ..Default::default()
+        }
+    }
+
+    fn data_pages(pages: Vec<EncodedPage>) -> Vec<DataPage> {
+        pages
+            .into_iter()
+            .map(|page| match page {
+                EncodedPage::Data(page) => page,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_rows() -> Result<()> {
+        let descriptor = descriptor(LIST, 0);
+        // [[1, None, 2], [], None, [3]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 3, 3, 3, 4],
+                validity: Some(vec![true, true, false, true]),
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: Some(vec![true, false, true, true]),
+            },
+        ];
+        let values = [1i32, 2, 3]
+            .iter()
+            .map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let options = write_options(None, Some(3));
+        let pages = data_pages(nested_pages(&descriptor, &options, &nested, &values)?);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(
+            deserialize(&pages[0], &descriptor)?,
+            Values::Int32(vec![Some(1), None, Some(2), None, None])
+        );
+        assert_eq!(
+            reconstruct_page(&pages[1], &descriptor)?,
+            vec![
+                Nested::List {
+                    offsets: vec![0, 1],
+                    validity: Some(vec![true]),
+                },
+                Nested::Primitive {
+                    length: 1,
+                    validity: Some(vec![true]),
+                },
+            ]
+        );
+
+        // without limits, a single page
+        let options = write_options(None, None);
+        let pages = data_pages(nested_pages(&descriptor, &options, &nested, &values)?);
+        assert_eq!(pages.len(), 1);
+        assert_eq!(reconstruct_page(&pages[0], &descriptor)?, nested);
+        Ok(())
+    }
+
+    #[test]
+    fn split_size() -> Result<()> {
+        let descriptor = descriptor(
+            "message schema {
+                REPEATED BYTE_ARRAY a;
+            }",
+            0,
+        );
+        // [["aa", "bb"], ["cc"], ["dddd"]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 2, 3, 4],
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: None,
+            },
+        ];
+        let values = vec!["aa", "bb", "cc", "dddd"];
+
+        let binary =
+            |x: &[&str]| Values::Binary(x.iter().map(|x| Some(x.as_bytes().to_vec())).collect());
+        let split = |size_limit| -> Result<Vec<Values>> {
+            let options = write_options(Some(size_limit), None);
+            data_pages(nested_pages(&descriptor, &options, &nested, &values)?)
+                .iter()
+                .map(|page| deserialize(page, &descriptor))
+                .collect()
+        };
+
+        // each value is prefixed by its length: the rows have 12, 6 and 8 bytes.
+        // The first row exceeds the limit, but rows are never split
+        assert_eq!(
+            split(10)?,
+            vec![binary(&["aa", "bb"]), binary(&["cc"]), binary(&["dddd"])]
+        );
+        // a page is never larger than the limit, unless it has a single row
+        assert_eq!(
+            split(14)?,
+            vec![binary(&["aa", "bb"]), binary(&["cc", "dddd"])]
+        );
+        assert_eq!(
+            split(13)?,
+            vec![binary(&["aa", "bb"]), binary(&["cc"]), binary(&["dddd"])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn split_errors() {
+        let descriptor = descriptor(LIST, 0);
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 1],
+                validity: None,
+            },
+            Nested::Primitive {
+                length: 1,
+                validity: None,
+            },
+        ];
+        let options = write_options(None, None);
+        // one value missing
+        let values: Vec<[u8; 4]> = vec![];
+        assert!(nested_pages(&descriptor, &options, &nested, &values).is_err());
+        // a value of the wrong size
+        assert!(nested_pages(&descriptor, &options, &nested, &[[1u8; 8]]).is_err());
+    }
+
+    #[test]
+    fn split_statistics() -> Result<()> {
+        let descriptor = descriptor(LIST, 0);
+        // [[1, None, 2], [], None, [3]]
+        let nested = vec![
+            Nested::List {
+                offsets: vec![0, 3, 3, 3, 4],
+                validity: Some(vec![true, true, false, true]),
+            },
+            Nested::Primitive {
+                length: 4,
+                validity: Some(vec![true, false, true, true]),
+            },
+        ];
+        let values = [2i32, 1, 3]
+            .iter()
+            .map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let options = WriteOptions {
+            write_statistics: true,
+            ..write_options(None, Some(3))
+        };
+        let pages = data_pages(nested_pages(&descriptor, &options, &nested, &values)?);
+        let statistics = pages
+            .iter()
+            .map(|page| {
+                let statistics = page.statistics().unwrap()?;
+                let statistics = statistics
+                    .as_any()
+                    .downcast_ref::<PrimitiveStatistics<i32>>()
+                    .unwrap();
+                Ok((
+                    statistics.min_value,
+                    statistics.max_value,
+                    statistics.null_count,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // the empty and the null lists are nulls of the first page
+        assert_eq!(
+            statistics,
+            vec![(Some(1), Some(2), Some(3)), (Some(3), Some(3), Some(0))]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn statistics_nan() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED DOUBLE a; }", 0);
+        let nested = vec![Nested::Primitive {
+            length: 4,
+            validity: None,
+        }];
+        let values = [1.0f64, f64::NAN, f64::NAN, f64::NAN]
+            .iter()
+            .map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let options = WriteOptions {
+            write_statistics: true,
+            ..write_options(None, Some(2))
+        };
+        let pages = data_pages(nested_pages(&descriptor, &options, &nested, &values)?);
+        let statistics = pages
+            .iter()
+            .map(|page| {
+                let statistics = page.statistics().unwrap()?;
+                let statistics = statistics
+                    .as_any()
+                    .downcast_ref::<PrimitiveStatistics<f64>>()
+                    .unwrap();
+                Ok((statistics.min_value, statistics.max_value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // NaN is neither the min nor the max
+        assert_eq!(statistics, vec![(Some(1.0), Some(1.0)), (None, None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn split_boolean() -> Result<()> {
+        let descriptor = descriptor("message schema { REQUIRED BOOLEAN a; }", 0);
+        let nested = vec![Nested::Primitive {
+            length: 20,
+            validity: None,
+        }];
+        let values = (0..20u8).map(|x| [(x < 10) as u8]).collect::<Vec<_>>();
+
+        // each value is a bit: 16 values per page of 2 bytes
+        let options = WriteOptions {
+            write_statistics: true,
+            ..write_options(Some(2), None)
+        };
+        let pages = data_pages(nested_pages(&descriptor, &options, &nested, &values)?);
+        assert_eq!(pages.len(), 2);
+        let expected = (0..20).map(|x| Some(x < 10)).collect::<Vec<_>>();
+        assert_eq!(
+            deserialize(&pages[0], &descriptor)?,
+            Values::Boolean(expected[..16].to_vec())
+        );
+        assert_eq!(
+            deserialize(&pages[1], &descriptor)?,
+            Values::Boolean(expected[16..].to_vec())
+        );
+
+        let statistics = pages[1].statistics().unwrap()?;
+        let statistics = statistics
+            .as_any()
+            .downcast_ref::<BooleanStatistics>()
+            .unwrap();
+        assert_eq!(statistics.min_value, Some(false));
+        assert_eq!(statistics.max_value, Some(false));
+        Ok(())
+    }
+}
@@ -7,11 +7,15 @@ use parquet_format_async_temp::thrift::protocol::{
 };
 use parquet_format_async_temp::{DictionaryPageHeader, Encoding, PageType};
 
+use crate::encryption::{ColumnCipher, ModuleType};
 use crate::error::Result;
+use crate::metadata::ColumnDescriptor;
 use crate::page::{
     CompressedDataPage, CompressedDictPage, CompressedPage, DataPageHeader, ParquetPageHeader,
 };
-use crate::statistics::Statistics;
+use crate::statistics::{write_legacy_min_max, HyperLogLog, Statistics};
+
+use super::WriteOptions;
 
 /// Contains page write metrics.
 pub struct PageWriteSpec {
@@ -20,15 +24,24 @@ pub struct PageWriteSpec {
     pub offset: u64,
     pub bytes_written: u64,
     pub statistics: Option<Arc<dyn Statistics>>,
+    /// The number of rows of the page, when known (`None` for dictionary pages)
+    pub num_rows: Option<usize>,
+    /// The sketch of the distinct values of the page, when known (`None` for dictionary pages).
+    /// It is released once merged into the metadata of the column chunk.
+    pub distinct_sketch: Option<HyperLogLog>,
 }
 
 pub fn write_page<W: Write>(
     writer: &mut W,
     offset: u64,
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
     compressed_page: &CompressedPage,
 ) -> Result<PageWriteSpec> {
     let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
+        CompressedPage::Data(compressed_page) => {
+            assemble_data_page_header(compressed_page, descriptor, options)
+        }
         CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
     };
 
@@ -50,6 +63,14 @@ pub fn write_page<W: Write>(
         CompressedPage::Data(compressed_page) => compressed_page.statistics().transpose()?,
         CompressedPage::Dict(_) => None,
     };
+    let num_rows = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.num_rows(),
+        CompressedPage::Dict(_) => None,
+    };
+    let distinct_sketch = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.distinct_sketch().cloned(),
+        CompressedPage::Dict(_) => None,
+    };
 
     Ok(PageWriteSpec {
         header,
@@ -57,16 +78,87 @@ pub fn write_page<W: Write>(
         offset,
         bytes_written,
         statistics,
+        num_rows,
+        distinct_sketch,
+    })
+}
+
+/// Writes `compressed_page` into `writer` like [`write_page`], encrypting its header and buffer
+/// with `cipher`. `page_ordinal` is the ordinal of the data page within the column chunk.
+pub(crate) fn write_encrypted_page<W: Write>(
+    writer: &mut W,
+    offset: u64,
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+    compressed_page: &CompressedPage,
+    cipher: &ColumnCipher,
+    page_ordinal: i16,
+) -> Result<PageWriteSpec> {
+    let (mut header, buffer, header_module) = match &compressed_page {
+        CompressedPage::Data(compressed_page) => (
+            assemble_data_page_header(compressed_page, descriptor, options),
+            cipher.cipher.encrypt(
+                cipher.module(ModuleType::DataPage, page_ordinal),
+                &compressed_page.buffer,
+            )?,
+            cipher.module(ModuleType::DataPageHeader, page_ordinal),
+        ),
+        CompressedPage::Dict(compressed_page) => (
+            assemble_dict_page_header(compressed_page),
+            cipher.cipher.encrypt(
+                cipher.module(ModuleType::DictionaryPage, 0),
+                &compressed_page.buffer,
+            )?,
+            cipher.module(ModuleType::DictionaryPageHeader, 0),
+        ),
+    };
+    // SPEC: the compressed page size of encrypted pages is the size of the encrypted module
+    header.compressed_page_size = buffer.len() as i32;
+
+    let mut serialized_header = vec![];
+    write_page_header(&mut serialized_header, &header)?;
+    let serialized_header = cipher.cipher.encrypt(header_module, &serialized_header)?;
+
+    writer.write_all(&serialized_header)?;
+    writer.write_all(&buffer)?;
+    let header_size = serialized_header.len() as u64;
+    let bytes_written = header_size + buffer.len() as u64;
+
+    let statistics = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.statistics().transpose()?,
+        CompressedPage::Dict(_) => None,
+    };
+    let num_rows = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.num_rows(),
+        CompressedPage::Dict(_) => None,
+    };
+    let distinct_sketch = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.distinct_sketch().cloned(),
+        CompressedPage::Dict(_) => None,
+    };
+
+    Ok(PageWriteSpec {
+        header,
+        header_size,
+        offset,
+        bytes_written,
+        statistics,
+        num_rows,
+        distinct_sketch,
     })
 }
 
 pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
     writer: &mut W,
     offset: u64,
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
     compressed_page: &CompressedPage,
 ) -> Result<PageWriteSpec> {
     let header = match &compressed_page {
-        CompressedPage::Data(compressed_page) => assemble_data_page_header(compressed_page),
+        CompressedPage::Data(compressed_page) => {
+            assemble_data_page_header(compressed_page, descriptor, options)
+        }
         CompressedPage::Dict(compressed_page) => assemble_dict_page_header(compressed_page),
     };
 
@@ -88,6 +180,14 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
         CompressedPage::Data(compressed_page) => compressed_page.statistics().transpose()?,
         CompressedPage::Dict(_) => None,
     };
+    let num_rows = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.num_rows(),
+        CompressedPage::Dict(_) => None,
+    };
+    let distinct_sketch = match &compressed_page {
+        CompressedPage::Data(compressed_page) => compressed_page.distinct_sketch().cloned(),
+        CompressedPage::Dict(_) => None,
+    };
 
     Ok(PageWriteSpec {
         header,
@@ -95,10 +195,16 @@ pub async fn write_page_async<W: AsyncWrite + Unpin + Send>(
         offset,
         bytes_written,
         statistics,
+        num_rows,
+        distinct_sketch,
     })
 }
 
-fn assemble_data_page_header(compressed_page: &CompressedDataPage) -> ParquetPageHeader {
+fn assemble_data_page_header(
+    compressed_page: &CompressedDataPage,
+    descriptor: &ColumnDescriptor,
+    options: &WriteOptions,
+) -> ParquetPageHeader {
     let mut page_header = ParquetPageHeader {
         type_: match compressed_page.header() {
             DataPageHeader::V1(_) => PageType::DATA_PAGE,
@@ -121,6 +227,19 @@ fn assemble_data_page_header(compressed_page: &CompressedDataPage) -> ParquetPag
             page_header.data_page_header_v2 = Some(header.clone());
         }
     }
+    if options.write_legacy_min_max {
+        let statistics = match (
+            &mut page_header.data_page_header,
+            &mut page_header.data_page_header_v2,
+        ) {
+            (Some(header), _) => header.statistics.as_mut(),
+            (_, Some(header)) => header.statistics.as_mut(),
+            _ => None,
+        };
+        if let Some(statistics) = statistics {
+            write_legacy_min_max(statistics, descriptor);
+        }
+    }
     page_header
 }
 
@@ -1,18 +1,25 @@
+use std::convert::TryFrom;
 use std::io::Write;
 
 use futures::AsyncWrite;
-use parquet_format_async_temp::{ColumnChunk, RowGroup};
+use parquet_format_async_temp::{ColumnChunk, RowGroup, SortingColumn};
 
 use crate::{
-    compression::Compression,
+    encryption::RowGroupEncryptor,
     error::{ParquetError, Result},
     metadata::{ColumnChunkMetaData, ColumnDescriptor},
     page::CompressedPage,
 };
 
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use super::column_chunk::{write_column_metadata, write_pages};
 use super::{
     column_chunk::{write_column_chunk, write_column_chunk_async},
-    DynIter, DynStreamingIterator,
+    page::PageWriteSpec,
+    DynIter, DynStreamingIterator, WriteOptions,
 };
 
 pub struct ColumnOffsetsMetadata {
@@ -38,6 +45,12 @@ impl ColumnOffsetsMetadata {
     pub fn from_column_chunk_metadata(
         column_chunk_metadata: &ColumnChunkMetaData,
     ) -> ColumnOffsetsMetadata {
+        if !column_chunk_metadata.has_metadata() {
+            return ColumnOffsetsMetadata {
+                dictionary_page_offset: None,
+                data_page_offset: None,
+            };
+        }
         ColumnOffsetsMetadata {
             dictionary_page_offset: column_chunk_metadata.dictionary_page_offset(),
             data_page_offset: Some(column_chunk_metadata.data_page_offset()),
@@ -59,28 +72,155 @@ pub fn write_row_group<
     writer: &mut W,
     mut offset: u64,
     descriptors: &[ColumnDescriptor],
-    compression: Compression,
+    options: &WriteOptions,
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     num_rows: usize,
-) -> Result<(RowGroup, u64)>
+    encryptor: Option<RowGroupEncryptor>,
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: Write,
     ParquetError: From<E>,
     E: std::error::Error,
 {
-    let column_iter = descriptors.iter().zip(columns);
+    let column_iter = descriptors.iter().zip(columns).enumerate();
 
     let initial = offset;
-    let columns = column_iter
-        .map(|(descriptor, page_iter)| {
-            let (column, size) =
-                write_column_chunk(writer, offset, descriptor, compression, page_iter?)?;
+    let (columns, specs): (Vec<_>, Vec<_>) = column_iter
+        .map(|(column, (descriptor, page_iter))| {
+            let encryptor = encryptor
+                .map(|encryptor| encryptor.column(descriptor.path_in_schema(), column))
+                .transpose()?
+                .flatten();
+            let (column, specs, size) =
+                write_column_chunk(writer, offset, descriptor, options, page_iter?, encryptor)?;
             offset += size;
-            Ok(column)
+            Ok((column, specs))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+    let bytes_written = offset - initial;
+
+    Ok((
+        build_row_group(columns, num_rows, encryptor)?,
+        specs,
+        bytes_written,
+    ))
+}
+
+/// Writes a row group like [`write_row_group`], but compressing and encoding its column chunks
+/// in parallel, each into an in-memory buffer. The buffers are then written to `writer` in the
+/// order of `descriptors`. This requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn write_row_group_parallel<
+    'a,
+    W,
+    E, // external error any of the iterators may emit
+>(
+    writer: &mut W,
+    mut offset: u64,
+    descriptors: &[ColumnDescriptor],
+    options: &WriteOptions,
+    columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
+    num_rows: usize,
+    encryptor: Option<RowGroupEncryptor>,
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
+where
+    W: Write,
+    ParquetError: From<E>,
+    E: std::error::Error,
+{
+    let columns = descriptors
+        .iter()
+        .zip(columns)
+        .enumerate()
+        .map(|(column, (descriptor, page_iter))| {
+            let encryptor = encryptor
+                .map(|encryptor| encryptor.column(descriptor.path_in_schema(), column))
+                .transpose()?
+                .flatten();
+            Ok((descriptor, page_iter?, encryptor))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // the offsets of the pages are relative to the start of their buffer
+    let buffers = columns
+        .into_par_iter()
+        .map(|(descriptor, page_iter, encryptor)| {
+            let mut buffer = vec![];
+            let specs = write_pages(
+                &mut buffer,
+                0,
+                descriptor,
+                options,
+                page_iter,
+                encryptor.as_ref(),
+            )?;
+            Ok((descriptor, buffer, specs, encryptor))
         })
         .collect::<Result<Vec<_>>>()?;
+
+    let initial = offset;
+    let mut columns = Vec::with_capacity(buffers.len());
+    let mut specs = Vec::with_capacity(buffers.len());
+    for (descriptor, buffer, mut column_specs, encryptor) in buffers {
+        writer.write_all(&buffer)?;
+        column_specs
+            .iter_mut()
+            .for_each(|spec| spec.offset += offset);
+        offset += buffer.len() as u64;
+
+        let (column, size) =
+            write_column_metadata(writer, &mut column_specs, descriptor, options, encryptor)?;
+        offset += size;
+        columns.push(column);
+        specs.push(column_specs);
+    }
     let bytes_written = offset - initial;
 
+    Ok((
+        build_row_group(columns, num_rows, encryptor)?,
+        specs,
+        bytes_written,
+    ))
+}
+
+/// Checks that every column of `sorting_columns` is one of the `num_columns` columns of a row group.
+pub(super) fn check_sorting_columns(
+    sorting_columns: &[SortingColumn],
+    num_columns: usize,
+) -> Result<()> {
+    if let Some(column) = sorting_columns
+        .iter()
+        .find(|column| column.column_idx < 0 || column.column_idx as usize >= num_columns)
+    {
+        return Err(general_err!(
+            "The sorting column {} is out of bounds for a row group with {} columns",
+            column.column_idx,
+            num_columns
+        ));
+    }
+    Ok(())
+}
+
+/// The total compressed size of `columns`, whose metadata is not yet encrypted.
+fn total_compressed_size(columns: &[ColumnChunk]) -> Result<i64> {
+    columns
+        .iter()
+        .map(|c| {
+            c.meta_data
+                .as_ref()
+                .map(|meta_data| meta_data.total_compressed_size)
+                .ok_or_else(|| general_err!("A written column chunk must have metadata"))
+        })
+        .sum()
+}
+
+fn build_row_group(
+    columns: Vec<ColumnChunk>,
+    num_rows: usize,
+    encryptor: Option<RowGroupEncryptor>,
+) -> Result<RowGroup> {
     // compute row group stats
     let file_offest = columns
         .iter()
@@ -90,23 +230,28 @@ where
         })
         .unwrap_or(None);
 
-    let total_byte_size = columns
-        .iter()
-        .map(|c| c.meta_data.as_ref().unwrap().total_compressed_size)
-        .sum();
+    let total_byte_size = total_compressed_size(&columns)?;
 
-    Ok((
-        RowGroup {
-            columns,
-            total_byte_size,
-            num_rows: num_rows as i64,
-            sorting_columns: None,
-            file_offset: file_offest,
-            total_compressed_size: None,
-            ordinal: None,
-        },
-        bytes_written,
-    ))
+    // SPEC: the ordinal is required to decrypt the modules of encrypted files
+    let ordinal = encryptor
+        .map(|encryptor| {
+            i16::try_from(encryptor.ordinal).map_err(|_| {
+                ParquetError::OutOfSpec(
+                    "Encrypted files can have at most 32767 row groups".to_string(),
+                )
+            })
+        })
+        .transpose()?;
+
+    Ok(RowGroup {
+        columns,
+        total_byte_size,
+        num_rows: num_rows as i64,
+        sorting_columns: None,
+        file_offset: file_offest,
+        total_compressed_size: None,
+        ordinal,
+    })
 }
 
 pub async fn write_row_group_async<
@@ -117,10 +262,10 @@ pub async fn write_row_group_async<
     writer: &mut W,
     mut offset: u64,
     descriptors: &[ColumnDescriptor],
-    compression: Compression,
+    options: &WriteOptions,
     columns: DynIter<'a, std::result::Result<DynStreamingIterator<'a, CompressedPage, E>, E>>,
     num_rows: usize,
-) -> Result<(RowGroup, u64)>
+) -> Result<(RowGroup, Vec<Vec<PageWriteSpec>>, u64)>
 where
     W: AsyncWrite + Unpin + Send,
     ParquetError: From<E>,
@@ -130,11 +275,13 @@ where
 
     let initial = offset;
     let mut columns = vec![];
+    let mut specs = vec![];
     for (descriptor, page_iter) in column_iter {
-        let (spec, size) =
-            write_column_chunk_async(writer, offset, descriptor, compression, page_iter?).await?;
+        let (column, column_specs, size) =
+            write_column_chunk_async(writer, offset, descriptor, options, page_iter?).await?;
         offset += size as u64;
-        columns.push(spec);
+        columns.push(column);
+        specs.push(column_specs);
     }
     let bytes_written = offset - initial;
 
@@ -147,10 +294,7 @@ where
         })
         .unwrap_or(None);
 
-    let total_byte_size = columns
-        .iter()
-        .map(|c| c.meta_data.as_ref().unwrap().total_compressed_size)
-        .sum();
+    let total_byte_size = total_compressed_size(&columns)?;
 
     Ok((
         RowGroup {
@@ -162,6 +306,7 @@ where
             total_compressed_size: None,
             ordinal: None,
         },
+        specs,
         bytes_written,
     ))
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use crate::error::{ParquetError, Result};
 use crate::schema::types::PhysicalType;
 use crate::statistics::*;
-use crate::types::NativeType;
+use crate::types::{ord_int96, NativeType};
 
 pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn Statistics>>> {
     if stats.is_empty() {
@@ -29,38 +29,53 @@ pub fn reduce(stats: &[&Option<Arc<dyn Statistics>>]) -> Result<Option<Arc<dyn S
         ));
     };
     Ok(match stats[0].physical_type() {
-        PhysicalType::Boolean => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_boolean(stats)))
-        }
-        PhysicalType::Int32 => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i32, _>(stats)))
-        }
-        PhysicalType::Int64 => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<i64, _>(stats)))
-        }
-        PhysicalType::Float => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f32, _>(stats)))
-        }
-        PhysicalType::Double => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_primitive::<f64, _>(stats)))
-        }
-        PhysicalType::ByteArray => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_binary(stats)))
-        }
+        PhysicalType::Boolean => Some(Arc::new(reduce_boolean(downcast(&stats)?))),
+        PhysicalType::Int32 => Some(Arc::new(reduce_primitive::<i32, _>(downcast(&stats)?))),
+        PhysicalType::Int64 => Some(Arc::new(reduce_primitive::<i64, _>(downcast(&stats)?))),
+        PhysicalType::Int96 => Some(Arc::new(reduce_int96(downcast(&stats)?))),
+        PhysicalType::Float => Some(Arc::new(reduce_primitive::<f32, _>(downcast(&stats)?))),
+        PhysicalType::Double => Some(Arc::new(reduce_primitive::<f64, _>(downcast(&stats)?))),
+        PhysicalType::ByteArray => Some(Arc::new(reduce_binary(downcast(&stats)?))),
         PhysicalType::FixedLenByteArray(_) => {
-            let stats = stats.iter().map(|x| x.as_any().downcast_ref().unwrap());
-            Some(Arc::new(reduce_fix_len_binary(stats)))
+            Some(Arc::new(reduce_fix_len_binary(downcast(&stats)?)))
         }
-        _ => todo!(),
     })
 }
 
+/// Merges the sketches of the distinct values of pages, or returns `None` if there are no
+/// sketches or a page has none.
+pub fn reduce_distinct(sketches: &[Option<HyperLogLog>]) -> Result<Option<HyperLogLog>> {
+    let mut sketches = sketches.iter();
+    let mut acc = match sketches.next() {
+        Some(Some(sketch)) => sketch.clone(),
+        _ => return Ok(None),
+    };
+    for sketch in sketches {
+        match sketch {
+            Some(sketch) => acc.merge(sketch)?,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(acc))
+}
+
+/// Downcasts every statistics to `T`, erroring if one of them is not a `T`.
+fn downcast<'a, T: 'static>(stats: &[&'a dyn Statistics]) -> Result<std::vec::IntoIter<&'a T>> {
+    stats
+        .iter()
+        .map(|x| {
+            x.as_any().downcast_ref::<T>().ok_or_else(|| {
+                general_err!(
+                    "The statistics of physical type {:?} are not {}",
+                    x.physical_type(),
+                    std::any::type_name::<T>()
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(|x| x.into_iter())
+}
+
 fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) -> BinaryStatistics {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
@@ -87,7 +102,9 @@ fn reduce_binary<'a, I: Iterator<Item = &'a BinaryStatistics>>(mut stats: I) ->
     })
 }
 
-fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(mut stats: I) -> FixedLenStatistics {
+fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(
+    mut stats: I,
+) -> FixedLenStatistics {
     let initial = stats.next().unwrap().clone();
     stats.fold(initial, |mut acc, new| {
         acc.min_value = match (acc.min_value, &new.min_value) {
@@ -113,28 +130,12 @@ fn reduce_fix_len_binary<'a, I: Iterator<Item = &'a FixedLenStatistics>>(mut sta
     })
 }
 
-
+/// Returns the larger (when `max`) or the smaller of `a` and `b`, compared lexicographically.
 fn ord_binary(a: Vec<u8>, b: Vec<u8>, max: bool) -> Vec<u8> {
-    for (v1, v2) in a.iter().zip(b.iter()) {
-        match v1.cmp(v2) {
-            std::cmp::Ordering::Greater => {
-                if max {
-                    return a;
-                } else {
-                    return b;
-                }
-            }
-            std::cmp::Ordering::Less => {
-                if max {
-                    return b;
-                } else {
-                    return a;
-                }
-            }
-            _ => {}
-        }
+    match (a.cmp(&b), max) {
+        (std::cmp::Ordering::Less, true) | (std::cmp::Ordering::Greater, false) => b,
+        _ => a,
     }
-    a
 }
 
 fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
@@ -165,6 +166,35 @@ fn reduce_boolean<'a, I: Iterator<Item = &'a BooleanStatistics>>(
     })
 }
 
+/// Reduces Int96 statistics, whose values are ordered as timestamps, i.e. by [`ord_int96`].
+fn reduce_int96<'a, I: Iterator<Item = &'a PrimitiveStatistics<[u32; 3]>>>(
+    mut stats: I,
+) -> PrimitiveStatistics<[u32; 3]> {
+    let initial = stats.next().unwrap().clone();
+    stats.fold(initial, |mut acc, new| {
+        acc.min_value = match (acc.min_value, &new.min_value) {
+            (None, None) => None,
+            (Some(x), None) => Some(x),
+            (None, Some(x)) => Some(*x),
+            (Some(x), Some(y)) => Some(if ord_int96(x, *y).is_gt() { *y } else { x }),
+        };
+        acc.max_value = match (acc.max_value, &new.max_value) {
+            (None, None) => None,
+            (Some(x), None) => Some(x),
+            (None, Some(x)) => Some(*x),
+            (Some(x), Some(y)) => Some(if ord_int96(x, *y).is_lt() { *y } else { x }),
+        };
+        acc.null_count = match (acc.null_count, &new.null_count) {
+            (None, None) => None,
+            (Some(x), None) => Some(x),
+            (None, Some(x)) => Some(*x),
+            (Some(x), Some(y)) => Some(x + *y),
+        };
+        acc.distinct_count = None;
+        acc
+    })
+}
+
 fn reduce_primitive<
     'a,
     T: NativeType + std::cmp::PartialOrd,
@@ -184,7 +214,7 @@ fn reduce_primitive<
             (None, None) => None,
             (Some(x), None) => Some(x),
             (None, Some(x)) => Some(*x),
-            (Some(x), Some(y)) => Some(if x < *y { x } else { *y }),
+            (Some(x), Some(y)) => Some(if x < *y { *y } else { x }),
         };
         acc.null_count = match (acc.null_count, &new.null_count) {
             (None, None) => None,
@@ -196,3 +226,105 @@ fn reduce_primitive<
         acc
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metadata::SchemaDescriptor;
+
+    #[test]
+    fn multiple_pages() -> Result<()> {
+        let schema = SchemaDescriptor::try_from_message(
+            "message schema { OPTIONAL INT32 a; OPTIONAL BYTE_ARRAY b; }",
+        )?;
+
+        let a = schema.columns()[0].clone();
+        let page = |min_value, max_value| -> Option<Arc<dyn Statistics>> {
+            Some(Arc::new(PrimitiveStatistics::<i32> {
+                descriptor: a.clone(),
+                null_count: Some(1),
+                distinct_count: None,
+                min_value: Some(min_value),
+                max_value: Some(max_value),
+            }))
+        };
+        let pages = [page(3, 10), page(-1, 2), page(5, 20), page(0, 4)];
+        let result = reduce(&pages.iter().collect::<Vec<_>>())?.unwrap();
+        let result = result
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<i32>>()
+            .unwrap();
+        assert_eq!(result.min_value, Some(-1));
+        assert_eq!(result.max_value, Some(20));
+        assert_eq!(result.null_count, Some(4));
+
+        // a prefix is smaller than the values it is a prefix of
+        let b = schema.columns()[1].clone();
+        let page = |min_value: &str, max_value: &str| -> Option<Arc<dyn Statistics>> {
+            Some(Arc::new(BinaryStatistics {
+                descriptor: b.clone(),
+                null_count: Some(0),
+                distinct_count: None,
+                min_value: Some(min_value.as_bytes().to_vec()),
+                max_value: Some(max_value.as_bytes().to_vec()),
+            }))
+        };
+        for pages in [
+            [page("ab", "b"), page("a", "ba")],
+            [page("a", "ba"), page("ab", "b")],
+        ] {
+            let result = reduce(&pages.iter().collect::<Vec<_>>())?.unwrap();
+            let result = result.as_any().downcast_ref::<BinaryStatistics>().unwrap();
+            assert_eq!(result.min_value, Some(b"a".to_vec()));
+            assert_eq!(result.max_value, Some(b"ba".to_vec()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn int96() -> Result<()> {
+        let schema = SchemaDescriptor::try_from_message("message schema { OPTIONAL INT96 a; }")?;
+        let descriptor = schema.columns()[0].clone();
+
+        // ordered by day before nanoseconds, unlike the arrays themselves
+        let late_in_day = [u32::MAX, 0, 2_440_588];
+        let next_day = [0, 0, 2_440_589];
+        let page = |min_value, max_value, null_count| -> Option<Arc<dyn Statistics>> {
+            Some(Arc::new(PrimitiveStatistics::<[u32; 3]> {
+                descriptor: descriptor.clone(),
+                null_count: Some(null_count),
+                distinct_count: None,
+                min_value: Some(min_value),
+                max_value: Some(max_value),
+            }))
+        };
+        let a = page(late_in_day, late_in_day, 1);
+        let b = page(next_day, next_day, 2);
+
+        for stats in [[&a, &b], [&b, &a]] {
+            let result = reduce(&stats)?.unwrap();
+            let result = result
+                .as_any()
+                .downcast_ref::<PrimitiveStatistics<[u32; 3]>>()
+                .unwrap();
+            assert_eq!(result.min_value, Some(late_in_day));
+            assert_eq!(result.max_value, Some(next_day));
+            assert_eq!(result.null_count, Some(3));
+        }
+
+        // the year 9999 is beyond the range of timestamps in nanoseconds
+        let year_1970 = [0, 0, 2_440_588];
+        let year_9999 = [0, 0, 5_373_484];
+        let a = page(year_1970, year_9999, 0);
+        let b = page(late_in_day, late_in_day, 0);
+        let result = reduce(&[&a, &b])?.unwrap();
+        let result = result
+            .as_any()
+            .downcast_ref::<PrimitiveStatistics<[u32; 3]>>()
+            .unwrap();
+        assert_eq!(result.min_value, Some(year_1970));
+        assert_eq!(result.max_value, Some(year_9999));
+        Ok(())
+    }
+}
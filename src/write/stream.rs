@@ -8,12 +8,19 @@ use parquet_format_async_temp::{
 };
 
 use crate::{
+    bloom_filter::BloomFilter,
     error::{ParquetError, Result},
-    metadata::{KeyValue, SchemaDescriptor},
+    metadata::{KeyValue, SchemaDescriptor, SortingColumn},
     FOOTER_SIZE, PARQUET_MAGIC,
 };
 
-use super::{row_group::write_row_group_async, RowGroupIter, WriteOptions};
+use super::{
+    bloom_filter::serialize_bloom_filters,
+    indexes::serialize_indexes,
+    page::PageWriteSpec,
+    row_group::{check_sorting_columns, write_row_group_async},
+    RowGroupIter, WriteOptions,
+};
 
 async fn start_file<W: AsyncWrite + Unpin>(writer: &mut W) -> Result<u64> {
     writer.write_all(&PARQUET_MAGIC).await?;
@@ -44,6 +51,7 @@ async fn end_file<W: AsyncWrite + Unpin + Send>(
 /// An interface to write a parquet file asynchronously.
 /// Use `start` to write the header, `write` to write a row group,
 /// and `end` to write the footer.
+/// Encryption is not supported: use [`crate::write::FileWriter::with_encryption`] instead.
 pub struct FileStreamer<W: AsyncWrite + Unpin + Send> {
     writer: W,
     schema: SchemaDescriptor,
@@ -52,6 +60,8 @@ pub struct FileStreamer<W: AsyncWrite + Unpin + Send> {
 
     offset: u64,
     row_groups: Vec<RowGroup>,
+    page_specs: Vec<Vec<Vec<PageWriteSpec>>>,
+    bloom_filters: Vec<Vec<Option<BloomFilter>>>,
 }
 
 // Accessors
@@ -82,6 +92,8 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
             created_by,
             offset: 0,
             row_groups: vec![],
+            page_specs: vec![],
+            bloom_filters: vec![],
         }
     }
 
@@ -102,26 +114,80 @@ impl<W: AsyncWrite + Unpin + Send> FileStreamer<W> {
                 "You must call `start` before writing the first row group".to_string(),
             ));
         }
-        let (group, size) = write_row_group_async(
+        let (group, specs, size) = write_row_group_async(
             &mut self.writer,
             self.offset,
             self.schema.columns(),
-            self.options.compression,
+            &self.options,
             row_group,
             num_rows,
         )
         .await?;
         self.offset += size;
         self.row_groups.push(group);
+        self.page_specs.push(specs);
+        self.bloom_filters.push(vec![]);
+        Ok(())
+    }
+
+    /// Writes a row group to the file like [`FileStreamer::write`], declaring that its rows are
+    /// sorted by `sorting_columns`, the first being the primary sort key.
+    /// # Error
+    /// Errors if a sorting column is not a column of the schema.
+    pub async fn write_with_sorting_columns<E>(
+        &mut self,
+        row_group: RowGroupIter<'_, E>,
+        num_rows: usize,
+        sorting_columns: Vec<SortingColumn>,
+    ) -> Result<()>
+    where
+        ParquetError: From<E>,
+        E: std::error::Error,
+    {
+        check_sorting_columns(&sorting_columns, self.schema.columns().len())?;
+        self.write(row_group, num_rows).await?;
+        // the row group was just written
+        self.row_groups.last_mut().unwrap().sorting_columns = Some(sorting_columns);
         Ok(())
     }
 
+    /// Adds the bloom filters of the last row group written, one per column (`None` for columns
+    /// without a bloom filter). They are written to the file on `end`.
+    pub fn add_bloom_filters(&mut self, bloom_filters: Vec<Option<BloomFilter>>) -> Result<()> {
+        if bloom_filters.len() != self.schema.columns().len() {
+            return Err(general_err!(
+                "The number of bloom filters ({}) must equal the number of columns ({})",
+                bloom_filters.len(),
+                self.schema.columns().len()
+            ));
+        }
+        if let Some(last) = self.bloom_filters.last_mut() {
+            *last = bloom_filters;
+            Ok(())
+        } else {
+            Err(general_err!(
+                "You must write a row group before adding its bloom filters"
+            ))
+        }
+    }
+
     /// Writes the footer of the parquet file. Returns the total size of the file and the
     /// underlying writer.
     pub async fn end(mut self, key_value_metadata: Option<Vec<KeyValue>>) -> Result<(u64, W)> {
         // compute file stats
         let num_rows = self.row_groups.iter().map(|group| group.num_rows).sum();
 
+        // write the bloom filters
+        let bloom_filters =
+            serialize_bloom_filters(self.offset, &mut self.row_groups, &self.bloom_filters)?;
+        self.writer.write_all(&bloom_filters).await?;
+        self.offset += bloom_filters.len() as u64;
+
+        // write the column and offset indexes, which must precede the file metadata
+        let indexes = serialize_indexes(self.offset, &mut self.row_groups, &self.page_specs, None)?;
+        self.writer.write_all(&indexes).await?;
+        self.offset += indexes.len() as u64;
+
         let metadata = FileMetaData::new(
             self.options.version.into(),
             self.schema.into_thrift()?,